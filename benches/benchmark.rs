@@ -1,492 +1,433 @@
-use core_affinity::{get_core_ids, set_for_current};
-use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
-use rayon::ThreadPoolBuilder;
-use std::{hint::black_box, process, time::Duration};
-
-use multiplikation::algorithmen::crossbeam;
-use multiplikation::algorithmen::manuell_sicher;
-use multiplikation::algorithmen::manuell_unsicher;
-use multiplikation::algorithmen::rayon as mein_rayon;
-use multiplikation::algorithmen::simd;
-use multiplikation::algorithmen::simd_tiling;
-use multiplikation::algorithmen::single;
-use multiplikation::algorithmen::tiling;
-use multiplikation::algorithmen::unroll;
-use multiplikation::matrix::zufallsmatrix_2d;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::{
+    env, fs,
+    hint::black_box,
+    path::PathBuf,
+    process,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use multiplikation::auswertung::{self, Ergebnis};
+use multiplikation::cpu;
+use multiplikation::fehler::kerne as alle_kerne;
+use multiplikation::generator::{Generator, Matrixart};
+use multiplikation::konfiguration::{self, Konfiguration};
+use multiplikation::matrix::Matrix;
+use multiplikation::multiplikator::{Einstellungen, Multiplikator, alle, finden};
+use multiplikation::numa::{self, Platzierung};
+use multiplikation::rechenpool::{self, Ausführung};
+use multiplikation::roofline::{self, Maschine};
+use multiplikation::topologie;
+use multiplikation::verteilung::Verteilung;
+use multiplikation::zahl;
 
 /*
     Einstellungen für alle Benchmarks
-    globale Variablen müssen const oder static sein
+    globale Variablen müssen const oder static sein. Algorithmen, Größen, Threads, pinnen, Anzahl und Zeit der
+    Messungen kommen aus konfiguration::Konfiguration, siehe Konfiguration::aus_umgebung und beispiel.conf.
+    Ein kurzer Durchlauf z.B. mit MULTIPLIKATION_BENCH_PROFIL=schnell cargo bench
 */
-const ANZAHL: usize = 10; // Anzahl der Durchläufe
-const ZEIT: u64 = 60; // maximale Zeit in Sekunden je Durchlauf
-const MATRIZEN: &[usize] = &[
-    4, 8, 11, 16, 25, 32, 64, 94, 128, 256, 357, 512, 787, 1024, 1667,
-]; // Matrixgrößen
+const BEREICHE: &[usize] = &[1, 4, 16, 64]; // Bereichsgrößen der Verteilungen
 
-/*
-    Single Thread
-*/
-pub fn run_single(einstellungen: &mut Criterion) {
-    let mut gruppe: criterion::BenchmarkGroup<'_, criterion::measurement::WallTime> =
-        einstellungen.benchmark_group("single thread");
-
-    // Benchmark Einstellungen
-    gruppe.sample_size(ANZAHL);
-    gruppe.measurement_time(Duration::from_secs(ZEIT));
-
-    // Kern für cpu pinning
-    let kerne: Vec<core_affinity::CoreId> = get_core_ids().unwrap();
-    let kern: &core_affinity::CoreId = &kerne[0];
-
-    for &n in MATRIZEN {
-        gruppe.bench_with_input(BenchmarkId::from_parameter("single"), &n, |messen, &n| {
-            // Matrizen initialisieren
-            let a: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-            let b: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-            let mut c: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
-
-            // Benchmark ausführen
-            messen.iter(|| {
-                single::ausführen(
-                    black_box(&a),
-                    black_box(&b),
-                    black_box(&mut c),
-                    black_box(n),
-                    black_box(kern),
-                );
-                black_box(&c);
-            });
-        });
-    }
-
-    // Benchmark abschließen und Statistiken erstellen
-    gruppe.finish();
-}
+// Algorithmen, die ihre Threads selbst starten oder aus dem Rechenpool nehmen
+const MANUELL: &[&str] = &[
+    "manuell_sicher",
+    "manuell_unsicher",
+    "unroll",
+    "tiling",
+    "simd",
+    "simd_tiling",
+    "gepack",
+];
 
-/*
-    Threads ohne unsafe
-*/
-pub fn run_manuell_sicher(einstellungen: &mut Criterion) {
-    let mut gruppe: criterion::BenchmarkGroup<'_, criterion::measurement::WallTime> =
-        einstellungen.benchmark_group("Threads ohne unsafe");
+// wer die Seiten der Matrizen zuerst beschreibt, auf Rechnern mit mehreren NUMA Knoten verteilt das den Speicher
+const PLATZIERUNG: Platzierung = Platzierung::ErsteBerührung;
 
-    // Benchmark Einstellungen
-    gruppe.sample_size(ANZAHL);
-    gruppe.measurement_time(Duration::from_secs(ZEIT));
+// Startwert der Zufallsmatrizen, a wird mit SEED und b mit SEED + 1 erzeugt. So misst jeder Lauf dieselben Eingaben
+const SEED: u64 = 42;
 
-    // Kern für cpu pinning
-    let kerne: Vec<core_affinity::CoreId> = get_core_ids().unwrap();
+// Algorithmus, gegen den speedup und Effizienz berechnet werden
+const REFERENZ: &str = "single";
 
-    for &n in MATRIZEN {
-        let a: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-        let b: Vec<Vec<f64>> = zufallsmatrix_2d(n);
+// Zeiten aller Benchmarks von run_algorithmus, werden nach allen Benchmarks als effizienz.csv geschrieben
+static ERGEBNISSE: Mutex<Vec<Ergebnis>> = Mutex::new(Vec::new());
 
-        for threads in 2..=kerne.len() {
-            gruppe.bench_with_input(
-                BenchmarkId::new("ohne_unsafe", format!("{}_{}", threads, n)),
-                &n,
-                |messen, &n| {
-                    let mut c: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
-
-                    // Benchmark ausführen
-                    messen.iter(|| {
-                        manuell_sicher::ausführen(
-                            black_box(&a),
-                            black_box(&b),
-                            black_box(&mut c),
-                            black_box(n),
-                            black_box(threads),
-                            black_box(&kerne),
-                        );
-                        black_box(&c);
-                    });
-                },
-            );
-        }
-    }
-    // Benchmark abschließen und Statistiken erstellen
-    gruppe.finish();
+/*
+    Kerne für cpu pinning nach konfiguration.pinnen, Thread z wird auf den z-ten Kern gepinnt. Kann die
+    Topologie nicht gelesen werden (z.B. ohne sysfs), werden die Kerne in der Reihenfolge von core_affinity
+    verwendet
+*/
+fn kerne(konfiguration: &Konfiguration) -> Vec<core_affinity::CoreId> {
+    topologie::kerne(konfiguration.pinnen)
+        .or_else(|_| alle_kerne())
+        .unwrap_or_else(|f| {
+            println!("Fehler beim ermitteln der Kerne: {}", f);
+            process::exit(1);
+        })
 }
 
 /*
-    Threads mit unsafe
+    reproduzierbare n x n Zufallsmatrix, deren Seiten nach PLATZIERUNG von Threads auf kerne angelegt werden
 */
-pub fn run_manuell_unsicher(einstellungen: &mut Criterion) {
-    let mut gruppe: criterion::BenchmarkGroup<'_, criterion::measurement::WallTime> =
-        einstellungen.benchmark_group("Threads mit unsafe");
-
-    // Benchmark Einstellungen
-    gruppe.sample_size(ANZAHL);
-    gruppe.measurement_time(Duration::from_secs(ZEIT));
-
-    // Kern für cpu pinning
-    let kerne: Vec<core_affinity::CoreId> = get_core_ids().unwrap();
+fn zufallsmatrix_2d(n: usize, seed: u64, kerne: &[core_affinity::CoreId]) -> Matrix {
+    Generator::neu(seed, Matrixart::Standard)
+        .matrix_parallel(n, n, kerne, PLATZIERUNG)
+        .unwrap_or_else(|f| {
+            println!("Fehler beim erstellen der Matrix: {}", f);
+            process::exit(1);
+        })
+}
 
-    for &n in MATRIZEN {
-        let a: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-        let b: Vec<Vec<f64>> = zufallsmatrix_2d(n);
+/*
+    Median der Zeiten je Multiplikation aus allen Aufrufen von iter_custom. Criterion ruft die Messung auch zum
+    Aufwärmen auf, der Median ist gegen diese Ausreißer robust
+*/
+fn median(mut zeiten: Vec<Duration>) -> Option<Duration> {
+    zeiten.sort();
+    zeiten.get(zeiten.len() / 2).copied()
+}
 
-        for threads in 2..=kerne.len() {
-            gruppe.bench_with_input(
-                BenchmarkId::new("mit_unsafe", format!("{}_{}", threads, n)),
-                &n,
-                |messen, &n| {
-                    let mut c: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
-
-                    // Benchmark ausführen
-                    messen.iter(|| {
-                        manuell_unsicher::ausführen(
-                            black_box(&a),
-                            black_box(&b),
-                            black_box(&mut c),
-                            black_box(n),
-                            black_box(threads),
-                            black_box(&kerne),
-                        );
-                        black_box(&c);
-                    });
-                },
-            );
-        }
-    }
-    // Benchmark abschließen und Statistiken erstellen
-    gruppe.finish();
+/*
+    Anzahl, Zeit und Aufwärmzeit der Messungen einer Gruppe aus der Konfiguration
+*/
+fn gruppe_einstellen(
+    gruppe: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    konfiguration: &Konfiguration,
+) {
+    gruppe.sample_size(konfiguration.anzahl);
+    gruppe.measurement_time(konfiguration.zeit);
+    gruppe.warm_up_time(konfiguration.aufwärmen);
 }
 
 /*
-    Threads mit loop unrolling
+    Benchmark eines Algorithmus für alle Matrixgrößen. Parallele Algorithmen werden mit den Anzahlen an Threads
+    aus der Konfiguration gemessen, sequentielle nur mit einem Thread
 */
-pub fn run_unroll(einstellungen: &mut Criterion) {
+fn run_algorithmus(
+    einstellungen: &mut Criterion,
+    konfiguration: &Konfiguration,
+    algorithmus: &dyn Multiplikator,
+) {
+    // bei simd Algorithmen wird der zur Laufzeit gewählte Pfad im Gruppennamen gespeichert, damit Ergebnisse von
+    // verschiedenen Rechnern vergleichbar bleiben
+    let name: String = if algorithmus.fähigkeiten().simd {
+        format!("{} ({})", algorithmus.beschreibung(), cpu::pfad())
+    } else {
+        algorithmus.beschreibung().to_string()
+    };
+
     let mut gruppe: criterion::BenchmarkGroup<'_, criterion::measurement::WallTime> =
-        einstellungen.benchmark_group("loop unrolling");
+        einstellungen.benchmark_group(name);
 
     // Benchmark Einstellungen
-    gruppe.sample_size(ANZAHL);
-    gruppe.measurement_time(Duration::from_secs(ZEIT));
-
-    // Kern für cpu pinning
-    let kerne: Vec<core_affinity::CoreId> = get_core_ids().unwrap();
+    gruppe_einstellen(&mut gruppe, konfiguration);
 
-    for &n in MATRIZEN {
-        let a: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-        let b: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-
-        for threads in 2..=kerne.len() {
-            gruppe.bench_with_input(
-                BenchmarkId::new("unrolling", format!("{}_{}", threads, n)),
-                &n,
-                |messen, &n| {
-                    let mut c: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
-
-                    // Benchmark ausführen
-                    messen.iter(|| {
-                        unroll::ausführen(
-                            black_box(&a),
-                            black_box(&b),
-                            black_box(&mut c),
-                            black_box(n),
-                            black_box(threads),
-                            black_box(&kerne),
-                        );
-                        black_box(&c);
-                    });
-                },
-            );
-        }
-    }
-    // Benchmark abschließen und Statistiken erstellen
-    gruppe.finish();
-}
+    // Kerne für cpu pinning
+    let kerne: Vec<core_affinity::CoreId> = kerne(konfiguration);
 
-pub fn run_tiling(einstellungen: &mut Criterion) {
-    let mut gruppe: criterion::BenchmarkGroup<'_, criterion::measurement::WallTime> =
-        einstellungen.benchmark_group("block tiling");
+    let threads: Vec<usize> = if algorithmus.fähigkeiten().parallel {
+        konfiguration.threads.threads(kerne.len())
+    } else {
+        vec![1]
+    };
 
-    // Benchmark Einstellungen
-    gruppe.sample_size(ANZAHL);
-    gruppe.measurement_time(Duration::from_secs(ZEIT));
+    for &n in &konfiguration.größen {
+        let a: Matrix = zufallsmatrix_2d(n, SEED, &kerne);
+        let b: Matrix = zufallsmatrix_2d(n, SEED + 1, &kerne);
 
-    // Kern für cpu pinning
-    let kerne: Vec<core_affinity::CoreId> = get_core_ids().unwrap();
+        // Criterion gibt den Durchsatz in Elementen pro Sekunde an, Gelem/s sind damit GFLOP/s
+        gruppe.throughput(Throughput::Elements(auswertung::operationen(n, n, n)));
 
-    for &n in MATRIZEN {
-        let a: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-        let b: Vec<Vec<f64>> = zufallsmatrix_2d(n);
+        for &thread in &threads {
+            let konfiguration: Einstellungen = Einstellungen::neu(thread, kerne.clone());
+            let mut zeiten: Vec<Duration> = Vec::new();
 
-        for threads in 2..=kerne.len() {
             gruppe.bench_with_input(
-                BenchmarkId::new("tiling", format!("{}_{}", threads, n)),
+                BenchmarkId::new(algorithmus.name(), format!("{}_{}", thread, n)),
                 &n,
                 |messen, &n| {
-                    let mut c: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
-
-                    // Benchmark ausführen
-                    messen.iter(|| {
-                        tiling::ausführen(
-                            black_box(&a),
-                            black_box(&b),
-                            black_box(&mut c),
-                            black_box(n),
-                            black_box(threads),
-                            black_box(&kerne),
-                        );
-                        black_box(&c);
+                    let mut c: Matrix = Matrix::neu(n, n);
+
+                    // Benchmark ausführen, die Zeit je Multiplikation wird für effizienz.csv mitgeschrieben
+                    messen.iter_custom(|iterationen| {
+                        let start: Instant = Instant::now();
+                        for _ in 0..iterationen {
+                            algorithmus
+                                .ausführen(
+                                    black_box(&a),
+                                    black_box(&b),
+                                    black_box(&mut c),
+                                    black_box(n),
+                                    black_box(n),
+                                    black_box(n),
+                                    black_box(&konfiguration),
+                                )
+                                .unwrap();
+                            black_box(&c);
+                        }
+                        let dauer: Duration = start.elapsed();
+                        zeiten.push(dauer.div_f64(iterationen.max(1) as f64));
+                        dauer
                     });
                 },
             );
+
+            // von Criterion herausgefilterte Benchmarks haben keine Zeiten
+            if let Some(zeit) = median(zeiten) {
+                ERGEBNISSE.lock().unwrap().push(Ergebnis {
+                    algorithmus: algorithmus.name().to_string(),
+                    threads: thread,
+                    m: n,
+                    k: n,
+                    n,
+                    zeit,
+                });
+            }
         }
     }
+
     // Benchmark abschließen und Statistiken erstellen
     gruppe.finish();
 }
 
-pub fn run_simd(einstellungen: &mut Criterion) {
+/*
+    Vergleich von neu gestarteten Threads und Rechenpool bei kleinen Matrizen, bei denen das Starten und Pinnen
+    der Threads einen großen Teil der Laufzeit ausmacht
+*/
+fn run_ausführung(einstellungen: &mut Criterion, konfiguration: &Konfiguration) {
     let mut gruppe: criterion::BenchmarkGroup<'_, criterion::measurement::WallTime> =
-        einstellungen.benchmark_group("simd");
-
-    // Benchmark Einstellungen
-    gruppe.sample_size(ANZAHL);
-    gruppe.measurement_time(Duration::from_secs(ZEIT));
-
-    // Kern für cpu pinning
-    let kerne: Vec<core_affinity::CoreId> = get_core_ids().unwrap();
-
-    for &n in MATRIZEN {
-        let a: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-        let b: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-
-        for threads in 2..=kerne.len() {
-            gruppe.bench_with_input(
-                BenchmarkId::new("simd", format!("{}_{}", threads, n)),
-                &n,
-                |messen, &n| {
-                    let mut c: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
-
-                    // Benchmark ausführen
-                    messen.iter(|| {
-                        simd::ausführen(
-                            black_box(&a),
-                            black_box(&b),
-                            black_box(&mut c),
-                            black_box(n),
-                            black_box(threads),
-                            black_box(&kerne),
-                        );
-                        black_box(&c);
-                    });
-                },
-            );
+        einstellungen.benchmark_group("Spawnen gegen Rechenpool");
+
+    gruppe_einstellen(&mut gruppe, konfiguration);
+
+    let kerne: Vec<core_affinity::CoreId> = kerne(konfiguration);
+
+    for &name in MANUELL.iter().filter(|&&name| konfiguration.gewählt(name)) {
+        let Some(algorithmus) = finden::<f64>(name) else {
+            continue;
+        };
+
+        for &n in &konfiguration.kleine_größen {
+            let a: Matrix = zufallsmatrix_2d(n, SEED, &kerne);
+            let b: Matrix = zufallsmatrix_2d(n, SEED + 1, &kerne);
+            gruppe.throughput(Throughput::Elements(auswertung::operationen(n, n, n)));
+
+            for ausführung in [Ausführung::Spawnen, Ausführung::Rechenpool] {
+                let konfiguration: Einstellungen =
+                    Einstellungen::neu(kerne.len(), kerne.clone()).mit_ausführung(ausführung);
+
+                gruppe.bench_with_input(
+                    BenchmarkId::new(format!("{}_{}", name, ausführung), n),
+                    &n,
+                    |messen, &n| {
+                        let mut c: Matrix = Matrix::neu(n, n);
+
+                        messen.iter(|| {
+                            algorithmus
+                                .ausführen(
+                                    black_box(&a),
+                                    black_box(&b),
+                                    black_box(&mut c),
+                                    black_box(n),
+                                    black_box(n),
+                                    black_box(n),
+                                    black_box(&konfiguration),
+                                )
+                                .unwrap();
+                            black_box(&c);
+                        });
+                    },
+                );
+            }
         }
     }
-    // Benchmark abschließen und Statistiken erstellen
+
     gruppe.finish();
 }
 
-pub fn run_simd_tiling(einstellungen: &mut Criterion) {
-    let mut gruppe: criterion::BenchmarkGroup<'_, criterion::measurement::WallTime> =
-        einstellungen.benchmark_group("block tiling und simd");
-
-    // Benchmark Einstellungen
-    gruppe.sample_size(ANZAHL);
-    gruppe.measurement_time(Duration::from_secs(ZEIT));
-
-    // Kern für cpu pinning
-    let kerne: Vec<core_affinity::CoreId> = get_core_ids().unwrap();
-
-    for &n in MATRIZEN {
-        let a: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-        let b: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-
-        for threads in 2..=kerne.len() {
-            gruppe.bench_with_input(
-                BenchmarkId::new("simd_tiling", format!("{}_{}", threads, n)),
-                &n,
-                |messen, &n| {
-                    let mut c: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
-
-                    // Benchmark ausführen
-                    messen.iter(|| {
-                        simd_tiling::ausführen(
-                            black_box(&a),
-                            black_box(&b),
-                            black_box(&mut c),
-                            black_box(n),
-                            black_box(threads),
-                            black_box(&kerne),
-                        );
-                        black_box(&c);
-                    });
-                },
-            );
-        }
+/*
+    alle Verteilungen, die parametrisierten mit jeder Bereichsgröße aus BEREICHE
+*/
+fn verteilungen() -> Vec<Verteilung> {
+    let mut verteilungen: Vec<Verteilung> = vec![Verteilung::StatischBlock];
+    for &zeilen in BEREICHE {
+        verteilungen.push(Verteilung::StatischZyklisch { zeilen });
+        verteilungen.push(Verteilung::Dynamisch { zeilen });
+        verteilungen.push(Verteilung::Geführt { minimum: zeilen });
+        verteilungen.push(Verteilung::Stehlen { zeilen });
+        verteilungen.push(Verteilung::Knoten { zeilen });
     }
-    // Benchmark abschließen und Statistiken erstellen
-    gruppe.finish();
+    verteilungen
 }
 
-pub fn run_rayon(einstellungen: &mut Criterion) {
-    let mut gruppe: criterion::BenchmarkGroup<'_, criterion::measurement::WallTime> =
-        einstellungen.benchmark_group("Rayon");
-
-    // Benchmark Einstellungen
-    gruppe.sample_size(ANZAHL);
-    gruppe.measurement_time(Duration::from_secs(ZEIT));
-
-    // Kern für cpu pinning
-    let kerne: Vec<core_affinity::CoreId> = get_core_ids().unwrap();
-
-    for &n in MATRIZEN {
-        let a: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-        let b: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-
-        for threads in 2..=kerne.len() {
-            // Kopie für jeden Thread
-            let kerne_kopie: Vec<core_affinity::CoreId> = kerne.clone();
-
-            // Threadpool erstellen
-            let pool = ThreadPoolBuilder::new()
-                .num_threads(threads)
-                .start_handler(move |id| {
-                    set_for_current(kerne_kopie[id]);
-                })
-                .build()
-                .unwrap_or_else(|f| {
-                    println!("Fehler beim erstellen des Threadpools: {}", f);
-                    process::exit(1);
-                });
-
-            gruppe.bench_with_input(
-                BenchmarkId::new("Rayon", format!("{}_{}", threads, n)),
-                &n,
-                |messen, &n| {
-                    let mut c: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
-
-                    // Benchmark ausführen
-                    messen.iter(|| {
-                        pool.install(|| {
-                            mein_rayon::ausführen(
-                                black_box(&a),
-                                black_box(&b),
-                                black_box(&mut c),
-                                black_box(n),
-                            );
+/*
+    Vergleich der Verteilungen der Zeilen auf die Threads mit allen Kernen. Auf Prozessoren mit unterschiedlich
+    schnellen Kernen (P- und E-Kerne) zeigt sich hier der Unterschied zwischen statischer und dynamischer Verteilung
+*/
+fn run_verteilung(einstellungen: &mut Criterion, konfiguration: &Konfiguration) {
+    let kerne: Vec<core_affinity::CoreId> = kerne(konfiguration);
+
+    for &name in MANUELL.iter().filter(|&&name| konfiguration.gewählt(name)) {
+        let Some(algorithmus) = finden::<f64>(name) else {
+            continue;
+        };
+
+        let mut gruppe: criterion::BenchmarkGroup<'_, criterion::measurement::WallTime> =
+            einstellungen.benchmark_group(format!("Verteilungen {}", name));
+
+        gruppe_einstellen(&mut gruppe, konfiguration);
+
+        for &n in &konfiguration.verteilung_größen {
+            let a: Matrix = zufallsmatrix_2d(n, SEED, &kerne);
+            let b: Matrix = zufallsmatrix_2d(n, SEED + 1, &kerne);
+            gruppe.throughput(Throughput::Elements(auswertung::operationen(n, n, n)));
+
+            for verteilung in verteilungen() {
+                let konfiguration: Einstellungen =
+                    Einstellungen::neu(kerne.len(), kerne.clone()).mit_verteilung(verteilung);
+
+                gruppe.bench_with_input(
+                    BenchmarkId::new(verteilung.to_string(), n),
+                    &n,
+                    |messen, &n| {
+                        let mut c: Matrix = Matrix::neu(n, n);
+
+                        messen.iter(|| {
+                            algorithmus
+                                .ausführen(
+                                    black_box(&a),
+                                    black_box(&b),
+                                    black_box(&mut c),
+                                    black_box(n),
+                                    black_box(n),
+                                    black_box(n),
+                                    black_box(&konfiguration),
+                                )
+                                .unwrap();
+                            black_box(&c);
                         });
-                        black_box(&c);
-                    });
-                },
-            );
+                    },
+                );
+            }
         }
+
+        gruppe.finish();
     }
-    // Benchmark abschließen und Statistiken erstellen
-    gruppe.finish();
 }
 
-pub fn run_crossbeam(einstellungen: &mut Criterion) {
-    let mut gruppe: criterion::BenchmarkGroup<'_, criterion::measurement::WallTime> =
-        einstellungen.benchmark_group("Crossbeam");
-
-    // Benchmark Einstellungen
-    gruppe.sample_size(ANZAHL);
-    gruppe.measurement_time(Duration::from_secs(ZEIT));
-
-    // Kern für cpu pinning
-    let kerne: Vec<core_affinity::CoreId> = get_core_ids().unwrap();
-
-    for &n in MATRIZEN {
-        let a: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-        let b: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-
-        for threads in 2..=kerne.len() {
-            gruppe.bench_with_input(
-                BenchmarkId::new("Crossbeam", format!("{}_{}", threads, n)),
-                &n,
-                |messen, &n| {
-                    let mut c: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
-
-                    // Benchmark ausführen
-                    messen.iter(|| {
-                        crossbeam::ausführen(
-                            black_box(&a),
-                            black_box(&b),
-                            black_box(&mut c),
-                            black_box(n),
-                            black_box(threads),
-                            black_box(&kerne),
-                        );
-                        black_box(&c);
-                    });
-                },
-            );
+/*
+    Roofline der gemessenen Algorithmen: Spitzenleistung und Bandbreite werden mit der größten gemessenen Anzahl
+    an Threads bestimmt, alle Punkte kommen als roofline.csv und roofline.svg neben die Berichte von Criterion
+*/
+fn roofline_schreiben(konfiguration: &Konfiguration) {
+    let ergebnisse: Vec<Ergebnis> = ERGEBNISSE.lock().unwrap().clone();
+    let Some(threads) = ergebnisse.iter().map(|ergebnis| ergebnis.threads).max() else {
+        return;
+    };
+
+    let maschine: Maschine = match Maschine::messen(&kerne(konfiguration), threads) {
+        Ok(maschine) => maschine,
+        Err(f) => {
+            println!("Fehler beim messen der Roofline: {}", f);
+            return;
+        }
+    };
+    println!(
+        "Roofline mit {} Threads: {:.1} GFLOP/s, {:.1} GB/s, Knick bei {:.2} FLOP/Byte",
+        threads,
+        maschine.spitze / 1e9,
+        maschine.bandbreite / 1e9,
+        maschine.knick()
+    );
+
+    let punkte: Vec<roofline::Punkt> = roofline::punkte(&ergebnisse, roofline::cache());
+    let ordner: PathBuf = berichte();
+    for (datei, inhalt) in [
+        ("roofline.csv", roofline::csv(&maschine, &punkte)),
+        ("roofline.svg", roofline::svg(&maschine, &punkte)),
+    ] {
+        let datei: PathBuf = ordner.join(datei);
+        match fs::create_dir_all(&ordner).and_then(|_| fs::write(&datei, inhalt)) {
+            Ok(()) => println!("Roofline geschrieben: {}", datei.display()),
+            Err(f) => println!("Fehler beim schreiben von {}: {}", datei.display(), f),
         }
     }
-    // Benchmark abschließen und Statistiken erstellen
-    gruppe.finish();
 }
 
-// Einzelne Benchmarks definieren
-criterion_group!(
-    name = single;
-    config = Criterion::default();
-    targets = run_single
-);
-
-criterion_group!(
-    name = manuell_sicher;
-    config = Criterion::default();
-    targets = run_manuell_sicher
-);
-
-criterion_group!(
-    name = manuell_unsicher;
-    config = Criterion::default();
-    targets = run_manuell_unsicher
-);
+// Ordner der Berichte von Criterion
+fn berichte() -> PathBuf {
+    env::var_os("CARGO_TARGET_DIR")
+        .map_or_else(|| PathBuf::from("target"), PathBuf::from)
+        .join("criterion")
+}
 
-criterion_group!(
-    name = unroll;
-    config = Criterion::default();
-    targets = run_unroll
-);
+/*
+    GFLOP/s, speedup und parallele Effizienz gegenüber REFERENZ für jede Anzahl an Threads und Matrixgröße als
+    CSV neben die Berichte von Criterion schreiben
+*/
+fn effizienz_schreiben() {
+    let ergebnisse: Vec<Ergebnis> = ERGEBNISSE.lock().unwrap().clone();
+    if ergebnisse.is_empty() {
+        return;
+    }
 
-criterion_group!(
-    name = tiling;
-    config = Criterion::default();
-    targets = run_tiling
-);
+    let ordner: PathBuf = berichte();
+    let datei: PathBuf = ordner.join("effizienz.csv");
+    let csv: String = auswertung::csv(&auswertung::effizienz(&ergebnisse, REFERENZ));
 
-criterion_group!(
-    name = simd;
-    config = Criterion::default();
-    targets = run_simd
-);
+    match fs::create_dir_all(&ordner).and_then(|_| fs::write(&datei, csv)) {
+        Ok(()) => println!("Effizienz geschrieben: {}", datei.display()),
+        Err(f) => println!("Fehler beim schreiben von {}: {}", datei.display(), f),
+    }
+}
 
-criterion_group!(
-    name = simd_tiling;
-    config = Criterion::default();
-    targets = run_simd_tiling
-);
+/*
+    alle registrierten Algorithmen nacheinander messen
+*/
+pub fn run_alle(einstellungen: &mut Criterion) {
+    let konfiguration: Konfiguration = Konfiguration::aus_umgebung().unwrap_or_else(|f| {
+        println!("Fehler in der Konfiguration: {}", f);
+        process::exit(1);
+    });
+    println!(
+        "Konfiguration (überschreibbar mit {} und {}*):\n{}",
+        konfiguration::DATEI,
+        konfiguration::PRÄFIX,
+        konfiguration
+    );
+    println!(
+        "simd Pfad: {} ({} Werte je Register, {})",
+        cpu::pfad(),
+        cpu::pfad().breite(),
+        zahl::UMSETZUNG
+    );
+    println!(
+        "pinnen: {}, Platzierung: {}, NUMA Knoten: {}, seed: {}",
+        konfiguration.pinnen,
+        PLATZIERUNG,
+        numa::knoten().len(),
+        SEED
+    );
+
+    for algorithmus in alle::<f64>() {
+        if konfiguration.gewählt(algorithmus.name()) {
+            run_algorithmus(einstellungen, &konfiguration, algorithmus);
+        }
+    }
+    run_ausführung(einstellungen, &konfiguration);
+    run_verteilung(einstellungen, &konfiguration);
+    effizienz_schreiben();
+    roofline_schreiben(&konfiguration);
 
-criterion_group!(
-    name = rayon;
-    config = Criterion::default();
-    targets = run_rayon
-);
+    // die Arbeiter der gemeinsamen Rechenpools beenden
+    rechenpool::alle_beenden();
+}
 
 criterion_group!(
-    name = crossbeam;
+    name = algorithmen;
     config = Criterion::default();
-    targets = run_crossbeam
+    targets = run_alle
 );
 
-criterion_main!(
-    single,
-    manuell_sicher,
-    manuell_unsicher,
-    unroll,
-    tiling,
-    simd,
-    simd_tiling,
-    rayon,
-    crossbeam
-);
+criterion_main!(algorithmen);
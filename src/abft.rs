@@ -0,0 +1,211 @@
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::zahl::Zahl;
+
+// Sicherheitsfaktor auf die Fehlerschranke der Prüfsummen, wie bei verifikation::freivalds
+const SPIELRAUM: f64 = 8.0;
+
+/*
+    Ergebnis der Prüfung einer Multiplikation mit Prüfsummen
+
+    Fehlerfrei: alle Zeilen- und Spaltensummen stimmen
+    Korrigiert: genau der Wert c[zeile][spalte] war falsch und wurde aus der Zeilenprüfsumme neu berechnet
+    Prüfsumme: nur eine der mitberechneten Prüfsummen war falsch, c selbst ist richtig. zeile ist die Zeile
+               der falschen Zeilenprüfsumme, spalte die Spalte der falschen Spaltenprüfsumme
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Befund {
+    Fehlerfrei,
+    Korrigiert {
+        zeile: usize,
+        spalte: usize,
+    },
+    Prüfsumme {
+        zeile: Option<usize>,
+        spalte: Option<usize>,
+    },
+}
+
+/*
+    algorithmusbasierte Fehlertoleranz (ABFT) nach Huang und Abraham: c = a * b wird mit algorithmus als
+    (m + 1) x (n + 1) Produkt berechnet
+
+    a wird um eine Zeile mit den Spaltensummen von a und b um eine Spalte mit den Zeilensummen von b erweitert.
+    Im Ergebnis enthält dann die letzte Spalte die Zeilensummen und die letzte Zeile die Spaltensummen von c.
+    Eine unbemerkte Veränderung eines Wertes während der Berechnung (z.B. ein gekipptes Bit) verletzt genau eine
+    Zeilen- und eine Spaltensumme, der Schnittpunkt ist der falsche Wert und wird korrigiert. Bei mehreren
+    falschen Werten wird c trotzdem geschrieben und die betroffenen Zeilen und Spalten als Fehler::Abft gemeldet
+
+    Die Summen von Gleitkommazahlen dürfen sich um die Rundungsfehler unterscheiden, die Schranke wächst wie bei
+    freivalds mit m + k + n und der Summe der Beträge aller Summanden
+*/
+pub fn ausführen<T: Zahl>(
+    algorithmus: &dyn Multiplikator<T>,
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
+    n: usize,
+    einstellungen: &Einstellungen,
+) -> Result<Befund, Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+
+    // a mit Spaltensummen, b mit Zeilensummen erweitern
+    let mut a_erweitert: Matrix<T> = Matrix::neu(m + 1, k);
+    for i in 0..m {
+        let zeile: &[T] = &a.zeile(i)[..k];
+        a_erweitert.zeile_mut(i).copy_from_slice(zeile);
+        for (summe, &wert) in a_erweitert.zeile_mut(m).iter_mut().zip(zeile) {
+            *summe += wert;
+        }
+    }
+    let mut b_erweitert: Matrix<T> = Matrix::neu(k, n + 1);
+    for p in 0..k {
+        let zeile: &[T] = &b.zeile(p)[..n];
+        let ziel: &mut [T] = b_erweitert.zeile_mut(p);
+        ziel[..n].copy_from_slice(zeile);
+        ziel[n] = zeile.iter().fold(T::NULL, |summe, &wert| summe + wert);
+    }
+
+    let mut c_erweitert: Matrix<T> = Matrix::neu(m + 1, n + 1);
+    algorithmus.ausführen(
+        &a_erweitert,
+        &b_erweitert,
+        &mut c_erweitert,
+        m + 1,
+        k,
+        n + 1,
+        einstellungen,
+    )?;
+
+    // Schranken je Zeile und Spalte: Summe der Beträge aller Summanden a[i][p] * b[p][j]
+    let toleranz: f64 = SPIELRAUM * T::EPSILON * (m + k + n + 2) as f64;
+    let b_beträge: Vec<f64> = (0..k).map(|p| betrag(&b.zeile(p)[..n])).collect();
+    let mut a_beträge: Vec<f64> = vec![0.0; k];
+    let mut zeilen_grenzen: Vec<f64> = vec![0.0; m];
+    for i in 0..m {
+        for (p, &wert) in a.zeile(i)[..k].iter().enumerate() {
+            a_beträge[p] += wert.abstand(T::NULL);
+            zeilen_grenzen[i] += wert.abstand(T::NULL) * b_beträge[p];
+        }
+    }
+    let mut spalten_grenzen: Vec<f64> = vec![0.0; n];
+    for p in 0..k {
+        for (j, &wert) in b.zeile(p)[..n].iter().enumerate() {
+            spalten_grenzen[j] += a_beträge[p] * wert.abstand(T::NULL);
+        }
+    }
+
+    let zeilen: Vec<usize> = (0..m)
+        .filter(|&i| {
+            let summe: T = zeilen_summe(&c_erweitert, i, n, None);
+            abweichung(summe, c_erweitert[(i, n)], toleranz * zeilen_grenzen[i])
+        })
+        .collect();
+    let spalten: Vec<usize> = (0..n)
+        .filter(|&j| {
+            let summe: T = spalten_summe(&c_erweitert, j, m, None);
+            abweichung(summe, c_erweitert[(m, j)], toleranz * spalten_grenzen[j])
+        })
+        .collect();
+
+    let befund: Result<Befund, Fehler> = match (zeilen.as_slice(), spalten.as_slice()) {
+        ([], []) => Ok(Befund::Fehlerfrei),
+        (&[i], &[j]) => {
+            // neu berechnen aus der Zeilensumme, die Spaltensumme muss danach wieder stimmen
+            c_erweitert[(i, j)] = c_erweitert[(i, n)] - zeilen_summe(&c_erweitert, i, n, Some(j));
+            let summe: T = spalten_summe(&c_erweitert, j, m, None);
+            if abweichung(summe, c_erweitert[(m, j)], toleranz * spalten_grenzen[j]) {
+                Err(Fehler::Abft { zeilen, spalten })
+            } else {
+                Ok(Befund::Korrigiert {
+                    zeile: i,
+                    spalte: j,
+                })
+            }
+        }
+        (&[i], []) => Ok(Befund::Prüfsumme {
+            zeile: Some(i),
+            spalte: None,
+        }),
+        ([], &[j]) => Ok(Befund::Prüfsumme {
+            zeile: None,
+            spalte: Some(j),
+        }),
+        _ => Err(Fehler::Abft { zeilen, spalten }),
+    };
+
+    for i in 0..m {
+        c.zeile_mut(i)[..n].copy_from_slice(&c_erweitert.zeile(i)[..n]);
+    }
+    befund
+}
+
+// Summe der ersten n Werte der Zeile i, ohne die Spalte ohne
+fn zeilen_summe<T: Zahl>(c: &Matrix<T>, i: usize, n: usize, ohne: Option<usize>) -> T {
+    c.zeile(i)[..n]
+        .iter()
+        .enumerate()
+        .filter(|&(j, _)| Some(j) != ohne)
+        .fold(T::NULL, |summe, (_, &wert)| summe + wert)
+}
+
+// Summe der ersten m Werte der Spalte j, ohne die Zeile ohne
+fn spalten_summe<T: Zahl>(c: &Matrix<T>, j: usize, m: usize, ohne: Option<usize>) -> T {
+    (0..m)
+        .filter(|&i| Some(i) != ohne)
+        .fold(T::NULL, |summe, i| summe + c[(i, j)])
+}
+
+fn betrag<T: Zahl>(werte: &[T]) -> f64 {
+    werte.iter().map(|wert| wert.abstand(T::NULL)).sum()
+}
+
+// NaN gilt immer als Abweichung
+fn abweichung<T: Zahl>(summe: T, prüfsumme: T, grenze: f64) -> bool {
+    let differenz: f64 = summe.abstand(prüfsumme);
+    differenz.is_nan() || differenz > grenze
+}
+
+/*
+    ABFT als Multiplikator um einen beliebigen Basis Algorithmus. Korrigierte Fehler bleiben unbemerkt, nur nicht
+    korrigierbare Fehler werden als Fehler::Abft zurückgegeben. Den Befund liefert abft::ausführen
+*/
+pub struct Abft<T: Zahl = f64> {
+    pub basis: &'static dyn Multiplikator<T>,
+}
+
+impl<T: Zahl> Abft<T> {
+    pub const fn neu(basis: &'static dyn Multiplikator<T>) -> Abft<T> {
+        Abft { basis }
+    }
+}
+
+impl<T: Zahl> Multiplikator<T> for Abft<T> {
+    fn name(&self) -> &'static str {
+        "abft"
+    }
+
+    fn beschreibung(&self) -> &'static str {
+        "ABFT mit Prüfsummen"
+    }
+
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        self.basis.fähigkeiten()
+    }
+
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        ausführen(self.basis, a, b, c, m, k, n, einstellungen).map(|_| ())
+    }
+}
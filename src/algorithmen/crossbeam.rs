@@ -1,18 +1,34 @@
-use core_affinity::{CoreId, set_for_current};
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::zaehler;
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
 use crossbeam::{channel::unbounded, thread};
 
+// von einem Thread berechnete Zeilen mit Zeilenindex
+type Berechnet<T> = Vec<(usize, Vec<T>)>;
+
+// Rückgabe eines Threads
+type Rückgabe<T> = Result<Berechnet<T>, Fehler>;
+
 /*
     dynamische Arbeitsverteilung mit Crossbeam Channels
     --> Threads holen sich selbständig Zeilenabschnitte aus einer globalen Warteschlange
 */
-pub fn ausführen(
-    a: &Vec<Vec<f64>>,
-    b: &Vec<Vec<f64>>,
-    c: &mut Vec<Vec<f64>>,
+pub fn ausführen<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
     n: usize,
     threads: usize,
-    pinnen: &Vec<CoreId>,
-) {
+    pinnen: &[CoreId],
+) -> Result<(), Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+    fehler::threads_prüfen(threads, pinnen)?;
+
     // jeder Thread darf sich jedesmal 4 Zeilen nehmen
     let zeilen: usize = 4;
 
@@ -20,45 +36,47 @@ pub fn ausführen(
     let (sender, empfänger) = unbounded::<usize>();
 
     // Warteschlange mit start indizes der jeweiligen Zeilen füllen (0, 4, 8, ...)
-    for i in (0..n).step_by(zeilen) {
+    for i in (0..m).step_by(zeilen) {
         sender.send(i).unwrap();
     }
 
     // Sender wird nicht mehr gebraucht da die komplette Arbeit (Indizes der Zeilen) in die Warteschlange eingreiht wurden
     drop(sender);
 
+    // die Crossbeam Threads zählen nicht mit
+    zaehler::nicht_erfasst();
+
     // Crossbeam Scope
     thread::scope(|s| {
         // Alle Thread handles speichern
         let mut sammeln = Vec::with_capacity(threads);
 
         // Worker Threads erstellen
-        for z in 0..threads {
-            let kern: CoreId = pinnen[z];
-
+        for &kern in &pinnen[..threads] {
             // Empfänger für jeden Thread clonen
             let empfänger_kopie = empfänger.clone();
 
             // Crossbeam Threads erzeugen
             let handle = s.spawn(move |_| {
-                set_for_current(kern);
+                fehler::pinnen(kern)?;
 
                 // berechnete Zeile
-                let mut berechnet: Vec<(usize, Vec<f64>)> = Vec::new();
+                let mut berechnet: Berechnet<T> = Vec::new();
 
                 // Matrixmultiplikation durchführen solange bis alle Zeilen berechnet wurden
                 for anfang in empfänger_kopie {
                     // Ende des aktuellen Zeilenbereichs berechnen
-                    let ende: usize = (anfang + zeilen).min(n);
+                    let ende: usize = (anfang + zeilen).min(m);
 
                     for i in anfang..ende {
-                        let mut ergebnis: Vec<f64> = vec![0.0; n];
+                        let a_zeile: &[T] = a.zeile(i);
+                        let mut ergebnis: Vec<T> = vec![T::NULL; n];
 
                         for j in 0..n {
-                            let mut summe = 0.0;
+                            let mut summe = T::NULL;
 
-                            for k in 0..n {
-                                summe = summe + a[i][k] * b[k][j];
+                            for p in 0..k {
+                                summe += a_zeile[p] * b[(p, j)];
                             }
                             ergebnis[j] = summe;
                         }
@@ -67,20 +85,66 @@ pub fn ausführen(
                     }
                 }
                 // Rückgabe
-                berechnet
+                Ok(berechnet)
             });
             sammeln.push(handle);
         }
 
-        // Nur Zeiger in der Ergebnismatrix ändern, keine Daten kopieren
-        for h in sammeln {
-            // Threads joinen und berechnet Werte auslesen
-            let rückgabe = h.join().unwrap();
+        // Threads joinen und berechnet Werte auslesen. Eine panic wird zu Fehler::ThreadPanik
+        let rückgaben: Vec<Rückgabe<T>> = sammeln
+            .into_iter()
+            .map(|h| h.join().unwrap_or(Err(Fehler::ThreadPanik)))
+            .collect();
 
-            for (i, zeile) in rückgabe {
-                c[i] = zeile;
+        // Zeilen in den zusammenhängenden Speicher der Ergebnismatrix kopieren
+        for rückgabe in rückgaben {
+            for (i, zeile) in rückgabe? {
+                c.zeile_mut(i)[..n].copy_from_slice(&zeile);
             }
         }
+        Ok(())
     })
-    .unwrap();
+    .unwrap_or(Err(Fehler::ThreadPanik))
+}
+
+pub struct Crossbeam;
+
+impl<T: Zahl> Multiplikator<T> for Crossbeam {
+    fn name(&self) -> &'static str {
+        "crossbeam"
+    }
+
+    fn beschreibung(&self) -> &'static str {
+        "Crossbeam"
+    }
+
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        Fähigkeiten {
+            parallel: true,
+            simd: false,
+            unsicher: false,
+        }
+    }
+
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        ausführen(
+            a,
+            b,
+            c,
+            m,
+            k,
+            n,
+            einstellungen.threads,
+            &einstellungen.pinnen,
+        )
+    }
 }
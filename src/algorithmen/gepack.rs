@@ -0,0 +1,352 @@
+use crate::cpu;
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::rechenpool::{self, Ausführung, Schranke, Wächter};
+use crate::verteilung::{Planer, Verteilung};
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
+use std::{
+    slice,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/*
+    Größe des Registerblocks des Mikrokerns. 6 x 8 Werte belegen bei AVX2 12 der 16 ymm Register
+    (2 Register je Zeile), dazu kommen 2 Register für b und eins für den Broadcast von a
+*/
+pub const MR: usize = 6;
+pub const NR: usize = 8;
+
+/*
+    Blockgrößen für die Cache Hierarchie nach dem BLIS Schema
+
+    kc: Länge der gepackten Streifen, ein MR x kc Streifen von a und ein kc x NR Streifen von b passen in L1
+    mc: Zeilen eines gepackten Blocks von a (mc x kc), passt in L2
+    nc: Spalten eines gepackten Blocks von b (kc x nc), passt in L3
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Blockgrößen {
+    pub mc: usize,
+    pub kc: usize,
+    pub nc: usize,
+}
+
+impl Default for Blockgrößen {
+    fn default() -> Blockgrößen {
+        Blockgrößen {
+            mc: 72,
+            kc: 256,
+            nc: 4080,
+        }
+    }
+}
+
+/*
+    verteilung::Planer verteilt die Arbeit auf Rust Threads nach dem BLIS Verfahren. a und b werden blockweise in
+    zusammenhängende Puffer gepackt, sodass der Mikrokern nur noch linear durch den Speicher läuft.
+
+    Schleifen von außen nach innen:
+        jc: Spalten von b in Blöcken von nc
+        pc: innere Dimension in Blöcken von kc, b wird gepackt (alle Threads packen gemeinsam)
+        ic: Zeilen von a in Blöcken von mc, a wird gepackt (mit der gewählten Verteilung auf die Threads verteilt)
+        jr, ir: MR x NR Mikrokern mit fused multiply-add
+*/
+pub fn ausführen<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
+    n: usize,
+    threads: usize,
+    pinnen: &[CoreId],
+    ausführung: Ausführung,
+    verteilung: Verteilung,
+) -> Result<(), Fehler> {
+    ausführen_mit_blöcken(
+        a,
+        b,
+        c,
+        m,
+        k,
+        n,
+        threads,
+        pinnen,
+        Blockgrößen::default(),
+        ausführung,
+        verteilung,
+    )
+}
+
+/*
+    wie ausführen, aber mit frei wählbaren Blockgrößen. mc wird auf ein Vielfaches von MR und nc auf ein
+    Vielfaches von NR aufgerundet
+*/
+pub fn ausführen_mit_blöcken<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
+    n: usize,
+    threads: usize,
+    pinnen: &[CoreId],
+    blöcke: Blockgrößen,
+    ausführung: Ausführung,
+    verteilung: Verteilung,
+) -> Result<(), Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+    fehler::threads_prüfen(threads, pinnen)?;
+
+    // leeres Produkt, c besteht nur aus Nullen
+    if k == 0 {
+        for i in 0..m {
+            c.zeile_mut(i).fill(T::NULL);
+        }
+        return Ok(());
+    }
+
+    let mc: usize = blöcke.mc.max(1).div_ceil(MR) * MR;
+    let kc: usize = blöcke.kc.max(1);
+    let nc: usize = blöcke.nc.max(1).div_ceil(NR) * NR;
+
+    // fma wird nur einmal abgefragt und nicht in jedem Mikrokern
+    let fma: bool = cpu::pfad().fma();
+
+    // gepackter Block von b, wird von allen Threads gemeinsam gefüllt und gelesen
+    let mut b_gepackt: Vec<T> = vec![T::NULL; kc * nc];
+    let b_zeiger: AtomicPtr<T> = AtomicPtr::new(b_gepackt.as_mut_ptr());
+
+    // Ergebnismatrix, jeder Thread schreibt nur in die Zeilen seiner ic Blöcke
+    let stride: usize = c.stride;
+    let c_zeiger: AtomicPtr<T> = AtomicPtr::new(c.daten.as_mut_ptr());
+
+    // Verteilung der ic Blöcke auf die Threads, eine Zeile des Planers ist hier ein ganzer ic Block
+    let ic_blöcke: usize = m.div_ceil(mc);
+    let planer: Planer = Planer::neu(verteilung, ic_blöcke, &pinnen[..threads]);
+
+    let schranke: Schranke = Schranke::neu(threads);
+
+    // verteilen führt den Thread auch bei einem Fehler beim pinnen aus, sonst würden die anderen Threads an der
+    // Schranke für immer warten. Bei einer panic bricht der Wächter die Schranke für alle Threads ab
+    rechenpool::verteilen(threads, pinnen, ausführung, |z| {
+        let _wächter: Wächter<'_> = schranke.wächter();
+        let b_puffer: *mut T = b_zeiger.load(Ordering::Relaxed);
+        let c_puffer: *mut T = c_zeiger.load(Ordering::Relaxed);
+
+        // jeder Thread packt seine eigenen Blöcke von a
+        let mut a_gepackt: Vec<T> = vec![T::NULL; mc * kc];
+
+        for jc in (0..n).step_by(nc) {
+            let nc_aktuell: usize = nc.min(n - jc);
+            let streifen_b: usize = nc_aktuell.div_ceil(NR);
+
+            for pc in (0..k).step_by(kc) {
+                let kc_aktuell: usize = kc.min(k - pc);
+
+                // die NR Streifen von b werden reihum auf die Threads verteilt. Die Streifen sind
+                // disjunkt, daher kann jeder Thread ohne Synchronisation in den Puffer schreiben
+                for streifen in (z..streifen_b).step_by(threads) {
+                    let ziel: &mut [T] = unsafe {
+                        slice::from_raw_parts_mut(
+                            b_puffer.add(streifen * NR * kc_aktuell),
+                            NR * kc_aktuell,
+                        )
+                    };
+                    b_packen(b, ziel, pc, kc_aktuell, jc + streifen * NR, n);
+                }
+
+                // warten bis b vollständig gepackt ist
+                schranke.warten()?;
+
+                let b_block: &[T] =
+                    unsafe { slice::from_raw_parts(b_puffer, streifen_b * NR * kc_aktuell) };
+
+                // Schleife für die Verteilung der ic Blöcke
+                while let Some(bereich) = planer.nächster(z) {
+                    for block in bereich {
+                        let ic: usize = block * mc;
+                        let mc_aktuell: usize = mc.min(m - ic);
+                        let streifen_a: usize = mc_aktuell.div_ceil(MR);
+
+                        for streifen in 0..streifen_a {
+                            let ziel: &mut [T] = &mut a_gepackt
+                                [streifen * MR * kc_aktuell..(streifen + 1) * MR * kc_aktuell];
+                            a_packen(a, ziel, ic + streifen * MR, m, pc, kc_aktuell);
+                        }
+
+                        for jr in 0..streifen_b {
+                            let b_streifen: &[T] =
+                                &b_block[jr * NR * kc_aktuell..(jr + 1) * NR * kc_aktuell];
+                            let spalte: usize = jc + jr * NR;
+                            let nr_aktuell: usize = NR.min(n - spalte);
+
+                            for ir in 0..streifen_a {
+                                let a_streifen: &[T] =
+                                    &a_gepackt[ir * MR * kc_aktuell..(ir + 1) * MR * kc_aktuell];
+                                let zeile: usize = ic + ir * MR;
+                                let mr_aktuell: usize = MR.min(m - zeile);
+
+                                let ab: [[T; NR]; MR] =
+                                    mikrokern(fma, kc_aktuell, a_streifen, b_streifen);
+
+                                // Registerblock in c schreiben. Die ic Blöcke sind disjunkt, daher
+                                // schreibt nur dieser Thread in diese Zeilen
+                                for i in 0..mr_aktuell {
+                                    let ergebnis: &mut [T] = unsafe {
+                                        slice::from_raw_parts_mut(
+                                            c_puffer.add((zeile + i) * stride + spalte),
+                                            nr_aktuell,
+                                        )
+                                    };
+                                    if pc == 0 {
+                                        ergebnis.copy_from_slice(&ab[i][..nr_aktuell]);
+                                    } else {
+                                        for j in 0..nr_aktuell {
+                                            ergebnis[j] += ab[i][j];
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // warten bis kein Thread mehr den gepackten Block von b liest
+                schranke.warten()?;
+
+                // Planer für den nächsten Block zurücksetzen. Die anderen Threads können ihn erst
+                // nach der nächsten Barriere wieder verwenden, die Thread 0 erst nach dem Zurücksetzen
+                // erreicht
+                if z == 0 {
+                    planer.zurücksetzen();
+                }
+            }
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/*
+    packt die Zeilen zeile..zeile + MR und die Spalten pc..pc + kc von a spaltenweise in ziel:
+    ziel[p * MR + i] = a[zeile + i][pc + p]. Zeilen außerhalb von a werden mit 0 aufgefüllt
+*/
+fn a_packen<T: Zahl>(a: &Matrix<T>, ziel: &mut [T], zeile: usize, m: usize, pc: usize, kc: usize) {
+    for i in 0..MR {
+        if zeile + i < m {
+            let a_zeile: &[T] = &a.zeile(zeile + i)[pc..pc + kc];
+            for p in 0..kc {
+                ziel[p * MR + i] = a_zeile[p];
+            }
+        } else {
+            for p in 0..kc {
+                ziel[p * MR + i] = T::NULL;
+            }
+        }
+    }
+}
+
+/*
+    packt die Zeilen pc..pc + kc und die Spalten spalte..spalte + NR von b zeilenweise in ziel:
+    ziel[p * NR + j] = b[pc + p][spalte + j]. Spalten außerhalb von b werden mit 0 aufgefüllt
+*/
+fn b_packen<T: Zahl>(b: &Matrix<T>, ziel: &mut [T], pc: usize, kc: usize, spalte: usize, n: usize) {
+    let breite: usize = NR.min(n - spalte);
+
+    for p in 0..kc {
+        let b_zeile: &[T] = &b.zeile(pc + p)[spalte..spalte + breite];
+        let ziel_zeile: &mut [T] = &mut ziel[p * NR..(p + 1) * NR];
+
+        ziel_zeile[..breite].copy_from_slice(b_zeile);
+        ziel_zeile[breite..].fill(T::NULL);
+    }
+}
+
+/*
+    berechnet den MR x NR Block a_streifen * b_streifen. Mit fma wird die Variante verwendet, die mit
+    fused multiply-add übersetzt wurde
+*/
+#[inline]
+fn mikrokern<T: Zahl>(fma: bool, kc: usize, a: &[T], b: &[T]) -> [[T; NR]; MR] {
+    #[cfg(target_arch = "x86_64")]
+    if fma {
+        // cpu::pfad() hat erkannt, dass der Prozessor avx2 und fma unterstützt
+        return unsafe { mikrokern_fma(kc, a, b) };
+    }
+    let _ = fma;
+    mikrokern_allgemein::<T, false>(kc, a, b)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,fma")]
+fn mikrokern_fma<T: Zahl>(kc: usize, a: &[T], b: &[T]) -> [[T; NR]; MR] {
+    mikrokern_allgemein::<T, true>(kc, a, b)
+}
+
+/*
+    Mikrokern mit festen Schleifengrenzen, damit der Compiler den Registerblock vollständig in simd Registern
+    halten kann
+*/
+#[inline(always)]
+fn mikrokern_allgemein<T: Zahl, const FMA: bool>(kc: usize, a: &[T], b: &[T]) -> [[T; NR]; MR] {
+    let mut ab: [[T; NR]; MR] = [[T::NULL; NR]; MR];
+
+    for (a_p, b_p) in a.chunks_exact(MR).zip(b.chunks_exact(NR)).take(kc) {
+        for i in 0..MR {
+            for j in 0..NR {
+                ab[i][j] = if FMA {
+                    a_p[i].mul_add(b_p[j], ab[i][j])
+                } else {
+                    ab[i][j] + a_p[i] * b_p[j]
+                };
+            }
+        }
+    }
+    ab
+}
+
+pub struct Gepack;
+
+impl<T: Zahl> Multiplikator<T> for Gepack {
+    fn name(&self) -> &'static str {
+        "gepack"
+    }
+
+    fn beschreibung(&self) -> &'static str {
+        "gepackter Mikrokern"
+    }
+
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        Fähigkeiten {
+            parallel: true,
+            simd: true,
+            unsicher: true,
+        }
+    }
+
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        ausführen(
+            a,
+            b,
+            c,
+            m,
+            k,
+            n,
+            einstellungen.threads,
+            &einstellungen.pinnen,
+            einstellungen.ausführung,
+            einstellungen.verteilung,
+        )
+    }
+}
@@ -1,78 +1,108 @@
-use core_affinity::{CoreId, set_for_current};
-use std::{
-    sync::atomic::{AtomicUsize, Ordering},
-    thread,
-};
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::rechenpool::{self, Ausführung};
+use crate::verteilung::{Planer, Verteilung};
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
+
+// von einem Thread berechnete Zeilen mit Zeilenindex
+type Berechnet<T> = Vec<(usize, Vec<T>)>;
 
 /*
-    dynamische Arbeitsverteilung mit Rust Threads. In dieser Variante wurde kein unsafe benutzt
+    verteilung::Planer verteilt die Arbeit auf Rust Threads. In dieser Variante wurde kein unsafe benutzt
 */
-pub fn ausführen(
-    a: &Vec<Vec<f64>>,
-    b: &Vec<Vec<f64>>,
-    c: &mut Vec<Vec<f64>>,
+pub fn ausführen<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
     n: usize,
     threads: usize,
-    pinnen: &Vec<CoreId>,
-) {
-    // jeder Thread darf sich jedesmal 4 Zeilen nehmen
-    let zeilen: usize = 4;
-
-    // atomarer Zähler für die dynamische Arbeitsverteilung mit Startwert null (= nächste zu verarbeitende Zeile)
-    let zähler: AtomicUsize = AtomicUsize::new(0);
+    pinnen: &[CoreId],
+    ausführung: Ausführung,
+    verteilung: Verteilung,
+) -> Result<(), Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+    fehler::threads_prüfen(threads, pinnen)?;
 
-    thread::scope(|s| {
-        // Thread Handles fürs joinen sammeln
-        let mut sammeln: Vec<thread::ScopedJoinHandle<'_, Vec<(usize, Vec<f64>)>>> =
-            Vec::with_capacity(threads);
+    // Verteilung der Zeilen auf die Threads
+    let planer: Planer = Planer::neu(verteilung, m, &pinnen[..threads]);
 
-        for z in 0..threads {
-            let kern: CoreId = pinnen[z];
+    let ergebnisse: Vec<Berechnet<T>> = rechenpool::verteilen(threads, pinnen, ausführung, |z| {
+        // berechnete Zeilen sammeln
+        let mut berechnet: Berechnet<T> = Vec::new();
 
-            let zähler_neu: &AtomicUsize = &zähler;
+        // Schleife für die Zeilenverteilung, nächster() liefert den nächsten Bereich dieses Threads
+        while let Some(bereich) = planer.nächster(z) {
+            for i in bereich {
+                let a_zeile: &[T] = a.zeile(i);
+                let mut zeile: Vec<T> = vec![T::NULL; n];
 
-            let handle: thread::ScopedJoinHandle<'_, Vec<(usize, Vec<f64>)>> = s.spawn(move || {
-                set_for_current(kern);
+                for j in 0..n {
+                    let mut summe: T = T::NULL;
+                    for p in 0..k {
+                        summe += a_zeile[p] * b[(p, j)];
+                    }
+                    zeile[j] = summe;
+                }
+                berechnet.push((i, zeile));
+            }
+        }
+        // Rückgabe von Thread
+        Ok(berechnet)
+    })?;
 
-                // berechnete Zeilen sammeln
-                let mut berechnet: Vec<(usize, Vec<f64>)> = Vec::new();
+    // Kopieren der Zeilen in den zusammenhängenden Speicher der Ergebnismatrix
+    for berechnet in ergebnisse {
+        for (i, zeile) in berechnet {
+            c.zeile_mut(i)[..n].copy_from_slice(&zeile);
+        }
+    }
+    Ok(())
+}
 
-                // Schleife für die dynamischen Zeilenverteilung
-                loop {
-                    // anfang des aktuellen Zeilenbereichs
-                    let anfang: usize = zähler_neu.fetch_add(zeilen, Ordering::Relaxed);
-                    if anfang >= n {
-                        break;
-                    }
+pub struct ManuellSicher;
 
-                    // ende des aktuellen Zeilenbereichs
-                    let ende: usize = (anfang + zeilen).min(n);
+impl<T: Zahl> Multiplikator<T> for ManuellSicher {
+    fn name(&self) -> &'static str {
+        "manuell_sicher"
+    }
 
-                    for i in anfang..ende {
-                        let mut zeile: Vec<f64> = vec![0.0; n];
+    fn beschreibung(&self) -> &'static str {
+        "Threads ohne unsafe"
+    }
 
-                        for j in 0..n {
-                            let mut summe: f64 = 0.0;
-                            for k in 0..n {
-                                summe = summe + a[i][k] * b[k][j];
-                            }
-                            zeile[j] = summe;
-                        }
-                        berechnet.push((i, zeile));
-                    }
-                }
-                // Rückgabe von Thread
-                berechnet
-            });
-            sammeln.push(handle);
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        Fähigkeiten {
+            parallel: true,
+            simd: false,
+            unsicher: false,
         }
+    }
 
-        // Speichern der Zeilen in der Ergebnismatrix durch austauschen der Zeiger (es werden keine Daten kopiert)
-        for h in sammeln {
-            let rückgabe: Vec<(usize, Vec<f64>)> = h.join().unwrap();
-            for (i, zeile) in rückgabe {
-                c[i] = zeile;
-            }
-        }
-    });
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        ausführen(
+            a,
+            b,
+            c,
+            m,
+            k,
+            n,
+            einstellungen.threads,
+            &einstellungen.pinnen,
+            einstellungen.ausführung,
+            einstellungen.verteilung,
+        )
+    }
 }
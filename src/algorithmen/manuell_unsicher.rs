@@ -1,70 +1,111 @@
-use core_affinity::{CoreId, set_for_current};
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::rechenpool::{self, Ausführung};
+use crate::verteilung::{Planer, Verteilung};
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
 use std::{
-    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
-    thread,
+    slice,
+    sync::atomic::{AtomicPtr, Ordering},
 };
 
 /*
-    dynamische Arbeitsverteilung mit Rust Threads. In dieser Variante wurde unsafe benutzt
+    verteilung::Planer verteilt die Arbeit auf Rust Threads. In dieser Variante wurde unsafe benutzt
 */
-pub fn ausführen(
-    a: &Vec<Vec<f64>>,
-    b: &Vec<Vec<f64>>,
-    c: &mut Vec<Vec<f64>>,
+pub fn ausführen<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
     n: usize,
     threads: usize,
-    pinnen: &Vec<CoreId>,
-) {
-    // jeder Thread darf sich jedesmal 4 Zeilen nehmen
-    let zeilen: usize = 4;
+    pinnen: &[CoreId],
+    ausführung: Ausführung,
+    verteilung: Verteilung,
+) -> Result<(), Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+    fehler::threads_prüfen(threads, pinnen)?;
 
-    // atomarer Zähler für die dynamische Arbeitsverteilung mit Startwert null (= nächste zu verarbeitende Zeile)
-    let zähler: AtomicUsize = AtomicUsize::new(0);
+    // Verteilung der Zeilen auf die Threads
+    let planer: Planer = Planer::neu(verteilung, m, &pinnen[..threads]);
+
+    // Abstand zwischen zwei Zeilenanfängen in der Ergebnismatrix
+    let stride: usize = c.stride;
 
     // Thread sichere verteilung des rohen Zeigers zwishcen den Threads.
     // Atomar wird nicht wegen dem Zugriff benötigt sondern weil es Send/Sync kompatibel ist
-    let c_zeiger: AtomicPtr<Vec<f64>> = AtomicPtr::new(c.as_mut_ptr());
+    let c_zeiger: AtomicPtr<T> = AtomicPtr::new(c.daten.as_mut_ptr());
 
-    thread::scope(|s| {
-        for z in 0..threads {
-            let kern: CoreId = pinnen[z];
-            let zähler_neu: &AtomicUsize = &zähler;
-            // atomar ist nicht wegen der Atomarität notwendig, sondern dass der Zeiger Thread sicher an
-            // die Threads verteilt werden darf.
-            let c_neu: &AtomicPtr<Vec<f64>> = &c_zeiger;
+    rechenpool::verteilen(threads, pinnen, ausführung, |z| {
+        // Zeiger auf Ergebnismatrix laden
+        let zeiger: *mut T = c_zeiger.load(Ordering::Relaxed);
 
-            s.spawn(move || {
-                set_for_current(kern);
+        // Schleife für die Zeilenverteilung, nächster() liefert den nächsten Bereich dieses Threads
+        while let Some(bereich) = planer.nächster(z) {
+            for i in bereich {
+                let a_zeile: &[T] = a.zeile(i);
 
-                // Zeiger auf Ergebnismatrix laden
-                let zeiger: *mut Vec<f64> = c_neu.load(Ordering::Relaxed);
+                // Jeder Thread arbeitet zwar in unterschiedlichen Zeilen, aber der Compiler kann dies zu
+                // compilezeit nicht garantieren. Daher ist ein unsicherer Zugriff auf i-te Zeile notwendig
+                let ergebnis: &mut [T] =
+                    unsafe { slice::from_raw_parts_mut(zeiger.add(i * stride), n) };
 
-                // Schleife für die dynamischen Zeilenverteilung
-                loop {
-                    // anfang des aktuellen Zeilenbereichs
-                    let anfang: usize = zähler_neu.fetch_add(zeilen, Ordering::Relaxed);
-                    if anfang >= n {
-                        break;
+                for j in 0..n {
+                    let mut summe: T = T::NULL;
+                    for p in 0..k {
+                        summe += a_zeile[p] * b[(p, j)];
                     }
+                    ergebnis[j] = summe;
+                }
+            }
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
 
-                    // ende des aktuellen Zeilenbereichs
-                    let ende: usize = (anfang + zeilen).min(n);
+pub struct ManuellUnsicher;
 
-                    for i in anfang..ende {
-                        // Jeder Thread arbeitet zwar in unterschiedlichen Zeilen, aber der Compiler kann dies zu
-                        // compilezeit nicht garantieren. Daher ist ein unsicherer Zugriff auf i-te Zeile notwendig
-                        let ergebnis: &mut Vec<f64> = unsafe { &mut *zeiger.add(i) };
+impl<T: Zahl> Multiplikator<T> for ManuellUnsicher {
+    fn name(&self) -> &'static str {
+        "manuell_unsicher"
+    }
 
-                        for j in 0..n {
-                            let mut summe: f64 = 0.0;
-                            for k in 0..n {
-                                summe = summe + a[i][k] * b[k][j];
-                            }
-                            ergebnis[j] = summe;
-                        }
-                    }
-                }
-            });
+    fn beschreibung(&self) -> &'static str {
+        "Threads mit unsafe"
+    }
+
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        Fähigkeiten {
+            parallel: true,
+            simd: false,
+            unsicher: true,
         }
-    });
+    }
+
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        ausführen(
+            a,
+            b,
+            c,
+            m,
+            k,
+            n,
+            einstellungen.threads,
+            &einstellungen.pinnen,
+            einstellungen.ausführung,
+            einstellungen.verteilung,
+        )
+    }
 }
@@ -1,21 +1,122 @@
-use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::zaehler;
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
+use rayon::{
+    ThreadPool, ThreadPoolBuilder, iter::IndexedParallelIterator, iter::ParallelIterator,
+    slice::ParallelSliceMut,
+};
+use std::{
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{Arc, Mutex},
+};
 
 /*
     Implementierung mit der Parallelisierungsbibliothek Rayon
 
     Die Parallelisierung erfolgt in der äußeren Schleife, da es sich für die inneren Schleifen nicht lohnt. Dabei
-    ersetzt man nur den sequentiellen iterator iter_mut() durch den parallelen Iterator von Rayon par_iter_mut()
+    wird der zusammenhängende Speicher der Ergebnismatrix mit par_chunks_mut() in Zeilen aufgeteilt, die Rayon
+    parallel abarbeitet
 */
-pub fn ausführen(a: &Vec<Vec<f64>>, b: &Vec<Vec<f64>>, c: &mut Vec<Vec<f64>>, n: usize) {
-    c.par_iter_mut()
-        .enumerate()
-        .for_each(|(i, zeile): (usize, &mut Vec<f64>)| {
-            for j in 0..n {
-                let mut summe: f64 = 0.0;
-                for k in 0..n {
-                    summe = summe + a[i][k] * b[k][j];
+pub fn ausführen<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
+    n: usize,
+) -> Result<(), Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+
+    // die Threads des Pools zählen nicht mit
+    zaehler::nicht_erfasst();
+
+    // par_chunks_mut() erlaubt keine leeren Zeilen
+    let stride: usize = c.stride.max(1);
+
+    // eine panic in einem Thread des Pools wird an den Aufrufer weitergegeben und hier abgefangen
+    catch_unwind(AssertUnwindSafe(|| {
+        c.daten.par_chunks_mut(stride).take(m).enumerate().for_each(
+            |(i, zeile): (usize, &mut [T])| {
+                let a_zeile: &[T] = a.zeile(i);
+
+                for j in 0..n {
+                    let mut summe: T = T::NULL;
+                    for p in 0..k {
+                        summe += a_zeile[p] * b[(p, j)];
+                    }
+                    zeile[j] = summe;
                 }
-                zeile[j] = summe;
-            }
-        });
+            },
+        );
+    }))
+    .map_err(|_| Fehler::ThreadPanik)
+}
+
+// bereits erstellte Threadpools. Das Erstellen eines Pools ist teuer und würde sonst jede Messung verfälschen
+type Pools = Vec<(Vec<CoreId>, Arc<ThreadPool>)>;
+static POOLS: Mutex<Pools> = Mutex::new(Vec::new());
+
+/*
+    liefert einen Threadpool, dessen Thread z auf pinnen[z] gepinnt ist. Pools werden nur einmal erstellt
+*/
+fn pool(pinnen: &[CoreId]) -> Result<Arc<ThreadPool>, Fehler> {
+    let mut pools = POOLS.lock().map_err(|_| Fehler::ThreadPanik)?;
+
+    if let Some((_, pool)) = pools.iter().find(|(kerne, _)| kerne == pinnen) {
+        return Ok(Arc::clone(pool));
+    }
+
+    let kopie: Vec<CoreId> = pinnen.to_vec();
+    let pool: Arc<ThreadPool> = Arc::new(
+        ThreadPoolBuilder::new()
+            .num_threads(pinnen.len())
+            .start_handler(move |id| {
+                // der start_handler kann keinen Fehler zurückgeben, ein nicht gepinnter Thread rechnet trotzdem richtig
+                let _ = fehler::pinnen(kopie[id]);
+            })
+            .build()
+            .map_err(|f| Fehler::Threadpool(f.to_string()))?,
+    );
+    pools.push((pinnen.to_vec(), Arc::clone(&pool)));
+    Ok(pool)
+}
+
+pub struct Rayon;
+
+impl<T: Zahl> Multiplikator<T> for Rayon {
+    fn name(&self) -> &'static str {
+        "rayon"
+    }
+
+    fn beschreibung(&self) -> &'static str {
+        "Rayon"
+    }
+
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        Fähigkeiten {
+            parallel: true,
+            simd: false,
+            unsicher: false,
+        }
+    }
+
+    // ausführen im Threadpool mit einstellungen.threads gepinnten Threads
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        fehler::threads_prüfen(einstellungen.threads, &einstellungen.pinnen)?;
+
+        pool(&einstellungen.pinnen[..einstellungen.threads])?
+            .install(|| ausführen(a, b, c, m, k, n))
+    }
 }
@@ -1,105 +1,286 @@
-use core_affinity::{CoreId, set_for_current};
-use std::{
-    simd::f64x4,
-    sync::atomic::{AtomicUsize, Ordering},
-    thread,
-};
+use crate::cpu::{self, SimdPfad};
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::rechenpool::{self, Ausführung};
+use crate::verteilung::{Planer, Verteilung};
+use crate::zahl::Zahl;
+use crate::zerlegung::{self, Kachel, Zerlegung};
+use core_affinity::CoreId;
+use std::ops::Range;
+
+// von einem Thread berechnete Zeilen mit Zeilenindex
+type Berechnet<T> = Vec<(usize, Vec<T>)>;
 
 /*
-    dynamische Arbeitsverteilung mit Rust Threads. Es wurde die Instruktion simd verwendet.
+    verteilung::Planer verteilt die Arbeit auf Rust Threads. Es wurde die Instruktion simd verwendet.
 
-    Zum testen wurde ein i7-14700k verwendet. Der Prozessor hat eine AVX2 Registerbreite von 256 bit (= 4 * 64 bit)
+    Die Registerbreite wird zur Laufzeit mit cpu::pfad() gewählt (sse2: 128, avx2+fma: 256, avx512: 512 bit), die
+    Anzahl der Werte je Register hängt zusätzlich vom Elementtyp ab
 */
-pub fn ausführen(
-    a: &Vec<Vec<f64>>,
-    b: &Vec<Vec<f64>>,
-    c: &mut Vec<Vec<f64>>,
+pub fn ausführen<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
     n: usize,
     threads: usize,
-    pinnen: &Vec<CoreId>,
-) {
-    // jeder Thread darf sich jedesmal 4 Zeilen nehmen
-    let zeilen: usize = 4;
-
-    // atomarer Zähler für die dynamische Arbeitsverteilung mit Startwert null (= nächste zu verarbeitende Zeile)
-    let zähler: AtomicUsize = AtomicUsize::new(0);
-
-    thread::scope(|s| {
-        // Thread Handles fürs joinen sammeln
-        let mut sammeln: Vec<thread::ScopedJoinHandle<'_, Vec<(usize, Vec<f64>)>>> =
-            Vec::with_capacity(threads);
-
-        for z in 0..threads {
-            let kern: CoreId = pinnen[z];
-
-            let zähler_neu: &AtomicUsize = &zähler;
-
-            let handle: thread::ScopedJoinHandle<'_, Vec<(usize, Vec<f64>)>> = s.spawn(move || {
-                set_for_current(kern);
-
-                // berechnete Zeilen sammeln
-                let mut berechnet: Vec<(usize, Vec<f64>)> = Vec::new();
-
-                // Schleife für die dynamischen Zeilenverteilung
-                loop {
-                    // anfang des aktuellen Zeilenbereichs
-                    let anfang: usize = zähler_neu.fetch_add(zeilen, Ordering::Relaxed);
-                    if anfang >= n {
-                        break;
-                    }
-
-                    let rest: usize = (n / 4) * 4;
-
-                    // ende des aktuellen Zeilenbereichs
-                    let ende: usize = (anfang + zeilen).min(n);
-
-                    for i in anfang..ende {
-                        let mut zeile: Vec<f64> = vec![0.0; n];
-
-                        for j in (0..rest).step_by(4) {
-                            let mut summe: std::simd::Simd<f64, 4> = f64x4::splat(0.0);
-                            for k in 0..n {
-                                let teil1: std::simd::Simd<f64, 4> = f64x4::splat(a[i][k]);
-
-                                let teil2: std::simd::Simd<f64, 4> = f64x4::from_array([
-                                    b[k][j],
-                                    b[k][j + 1],
-                                    b[k][j + 2],
-                                    b[k][j + 3],
-                                ]);
-                                summe = summe + teil1 * teil2;
-                            }
-
-                            // Ergebnisse speichern
-                            let zwischen: [f64; 4] = summe.to_array();
-                            for l in 0..4 {
-                                zeile[j + l] = zwischen[l];
-                            }
-                        }
-
-                        // restliche Spalten einzelen berechnen
-                        for x in rest..n {
-                            let mut summe2 = 0.0;
-                            for y in 0..n {
-                                summe2 = summe2 + a[i][y] * b[y][x];
-                            }
-                            zeile[x] = summe2;
-                        }
-                        berechnet.push((i, zeile));
-                    }
+    pinnen: &[CoreId],
+    ausführung: Ausführung,
+    verteilung: Verteilung,
+    zerlegung: Zerlegung,
+) -> Result<(), Fehler> {
+    ausführen_mit_pfad(
+        a,
+        b,
+        c,
+        m,
+        k,
+        n,
+        threads,
+        pinnen,
+        cpu::pfad(),
+        ausführung,
+        verteilung,
+        zerlegung,
+    )
+}
+
+/*
+    wie ausführen, aber mit festem simd Pfad, z.B. um alle Pfade eines Prozessors zu testen
+*/
+pub fn ausführen_mit_pfad<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
+    n: usize,
+    threads: usize,
+    pinnen: &[CoreId],
+    pfad: SimdPfad,
+    ausführung: Ausführung,
+    verteilung: Verteilung,
+    zerlegung: Zerlegung,
+) -> Result<(), Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+    fehler::threads_prüfen(threads, pinnen)?;
+    if !cpu::verfügbar(pfad) {
+        return Err(Fehler::SimdPfad(pfad));
+    }
+
+    // Kacheln von c, zeile_berechnen rechnet dann nur über die Spalten und Summanden der Kachel
+    if let Zerlegung::Kacheln { .. } = zerlegung {
+        return zerlegung::kacheln_berechnen(
+            c,
+            m,
+            k,
+            n,
+            zerlegung,
+            threads,
+            pinnen,
+            ausführung,
+            verteilung,
+            |kachel: &Kachel, werte: &mut [T]| {
+                let breite: usize = kachel.spalten.len();
+                for (zeile, i) in kachel.zeilen.clone().enumerate() {
+                    zeile_berechnen(
+                        pfad,
+                        a.zeile(i),
+                        b,
+                        &mut werte[zeile * breite..(zeile + 1) * breite],
+                        kachel.spalten.clone(),
+                        kachel.tiefe.clone(),
+                    );
                 }
-                // Rückgabe von Thread
-                berechnet
-            });
-            sammeln.push(handle);
-        }
+            },
+        );
+    }
+
+    // Verteilung der Zeilen auf die Threads
+    let planer: Planer = Planer::neu(verteilung, m, &pinnen[..threads]);
+
+    let ergebnisse: Vec<Berechnet<T>> = rechenpool::verteilen(threads, pinnen, ausführung, |z| {
+        // berechnete Zeilen sammeln
+        let mut berechnet: Berechnet<T> = Vec::new();
 
-        // Speichern der Zeilen in der Ergebnismatrix durch austauschen der Zeiger (es werden keine Daten kopiert)
-        for h in sammeln {
-            let rückgabe: Vec<(usize, Vec<f64>)> = h.join().unwrap();
-            for (i, zeile) in rückgabe {
-                c[i] = zeile;
+        // Schleife für die Zeilenverteilung, nächster() liefert den nächsten Bereich dieses Threads
+        while let Some(bereich) = planer.nächster(z) {
+            for i in bereich {
+                let a_zeile: &[T] = a.zeile(i);
+                let mut zeile: Vec<T> = vec![T::NULL; n];
+                zeile_berechnen(pfad, a_zeile, b, &mut zeile, 0..n, 0..k);
+                berechnet.push((i, zeile));
             }
         }
-    });
+        // Rückgabe von Thread
+        Ok(berechnet)
+    })?;
+
+    // Kopieren der Zeilen in den zusammenhängenden Speicher der Ergebnismatrix
+    for berechnet in ergebnisse {
+        for (i, zeile) in berechnet {
+            c.zeile_mut(i)[..n].copy_from_slice(&zeile);
+        }
+    }
+    Ok(())
+}
+
+/*
+    berechnet die Werte spalten einer Zeile von c über die Summanden p in tiefe mit der Registerbreite des Pfads.
+    Die Varianten mit avx werden mit den entsprechenden Prozessorfeatures übersetzt, damit der Compiler die
+    breiten Register verwendet
+*/
+fn zeile_berechnen<T: Zahl>(
+    pfad: SimdPfad,
+    a_zeile: &[T],
+    b: &Matrix<T>,
+    zeile: &mut [T],
+    spalten: Range<usize>,
+    tiefe: Range<usize>,
+) {
+    match pfad {
+        // ausführen_mit_pfad() hat geprüft, dass der Prozessor den Pfad unterstützt
+        #[cfg(target_arch = "x86_64")]
+        SimdPfad::Avx2Fma => unsafe { zeile_avx2(a_zeile, b, zeile, spalten, tiefe) },
+        #[cfg(target_arch = "x86_64")]
+        SimdPfad::Avx512 => unsafe { zeile_avx512(a_zeile, b, zeile, spalten, tiefe) },
+        _ => zeile_breite::<T, false>(SimdPfad::Sse2.bytes(), a_zeile, b, zeile, spalten, tiefe),
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,fma")]
+fn zeile_avx2<T: Zahl>(
+    a_zeile: &[T],
+    b: &Matrix<T>,
+    zeile: &mut [T],
+    spalten: Range<usize>,
+    tiefe: Range<usize>,
+) {
+    zeile_breite::<T, true>(SimdPfad::Avx2Fma.bytes(), a_zeile, b, zeile, spalten, tiefe)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f,fma")]
+fn zeile_avx512<T: Zahl>(
+    a_zeile: &[T],
+    b: &Matrix<T>,
+    zeile: &mut [T],
+    spalten: Range<usize>,
+    tiefe: Range<usize>,
+) {
+    zeile_breite::<T, true>(SimdPfad::Avx512.bytes(), a_zeile, b, zeile, spalten, tiefe)
+}
+
+/*
+    die Anzahl der Werte je Register hängt vom Elementtyp ab, z.B. passen in ein avx2 Register 4 f64 oder 8 f32
+    Werte. Der match wird für jeden Typ schon beim Übersetzen aufgelöst
+*/
+#[inline(always)]
+fn zeile_breite<T: Zahl, const FMA: bool>(
+    bytes: usize,
+    a_zeile: &[T],
+    b: &Matrix<T>,
+    zeile: &mut [T],
+    spalten: Range<usize>,
+    tiefe: Range<usize>,
+) {
+    match bytes / size_of::<T>() {
+        16.. => zeile_allgemein::<T, 16, FMA>(a_zeile, b, zeile, spalten, tiefe),
+        8..16 => zeile_allgemein::<T, 8, FMA>(a_zeile, b, zeile, spalten, tiefe),
+        4..8 => zeile_allgemein::<T, 4, FMA>(a_zeile, b, zeile, spalten, tiefe),
+        2..4 => zeile_allgemein::<T, 2, FMA>(a_zeile, b, zeile, spalten, tiefe),
+        _ => zeile_allgemein::<T, 1, FMA>(a_zeile, b, zeile, spalten, tiefe),
+    }
+}
+
+/*
+    Zeile mit L Werten je Register, mit FMA wird fused multiply-add verwendet
+*/
+#[inline(always)]
+fn zeile_allgemein<T: Zahl, const L: usize, const FMA: bool>(
+    a_zeile: &[T],
+    b: &Matrix<T>,
+    zeile: &mut [T],
+    spalten: Range<usize>,
+    tiefe: Range<usize>,
+) {
+    // Spalten relativ zum Anfang von zeile
+    let anfang: usize = spalten.start;
+    let breite: usize = spalten.len();
+    let rest: usize = (breite / L) * L;
+
+    for j in (0..rest).step_by(L) {
+        let mut summe: T::Vektor<L> = T::splat(T::NULL);
+        for p in tiefe.clone() {
+            let teil1: T::Vektor<L> = T::splat(a_zeile[p]);
+
+            // die L Werte liegen im zusammenhängenden Speicher direkt hintereinander
+            let teil2: T::Vektor<L> = T::laden(&b.zeile(p)[anfang + j..anfang + j + L]);
+            summe = if FMA {
+                T::vektor_mul_add(teil1, teil2, summe)
+            } else {
+                summe + teil1 * teil2
+            };
+        }
+
+        // Ergebnisse speichern
+        T::speichern(summe, &mut zeile[j..j + L]);
+    }
+
+    // restliche Spalten einzelen berechnen
+    for x in rest..breite {
+        let mut summe2 = T::NULL;
+        for y in tiefe.clone() {
+            summe2 += a_zeile[y] * b[(y, anfang + x)];
+        }
+        zeile[x] = summe2;
+    }
+}
+
+pub struct Simd;
+
+impl<T: Zahl> Multiplikator<T> for Simd {
+    fn name(&self) -> &'static str {
+        "simd"
+    }
+
+    fn beschreibung(&self) -> &'static str {
+        "simd"
+    }
+
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        Fähigkeiten {
+            parallel: true,
+            simd: true,
+            unsicher: false,
+        }
+    }
+
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        ausführen(
+            a,
+            b,
+            c,
+            m,
+            k,
+            n,
+            einstellungen.threads,
+            &einstellungen.pinnen,
+            einstellungen.ausführung,
+            einstellungen.verteilung,
+            einstellungen.zerlegung,
+        )
+    }
 }
@@ -1,24 +1,70 @@
-use core_affinity::{CoreId, set_for_current};
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
 
 /*
     single Thread
 */
-pub fn ausführen(
-    a: &Vec<Vec<f64>>,
-    b: &Vec<Vec<f64>>,
-    c: &mut Vec<Vec<f64>>,
+pub fn ausführen<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
     n: usize,
     kern: &CoreId,
-) {
-    set_for_current(*kern);
+) -> Result<(), Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+    fehler::pinnen(*kern)?;
+
+    for i in 0..m {
+        let a_zeile: &[T] = a.zeile(i);
+        let ergebnis: &mut [T] = c.zeile_mut(i);
 
-    for i in 0..n {
         for j in 0..n {
-            let mut summe = 0.0;
-            for k in 0..n {
-                summe = summe + a[i][k] * b[k][j];
+            let mut summe = T::NULL;
+            for p in 0..k {
+                summe += a_zeile[p] * b[(p, j)];
             }
-            c[i][j] = summe;
+            ergebnis[j] = summe;
+        }
+    }
+    Ok(())
+}
+
+pub struct Single;
+
+impl<T: Zahl> Multiplikator<T> for Single {
+    fn name(&self) -> &'static str {
+        "single"
+    }
+
+    fn beschreibung(&self) -> &'static str {
+        "single thread"
+    }
+
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        Fähigkeiten {
+            parallel: false,
+            simd: false,
+            unsicher: false,
         }
     }
+
+    // es wird nur der erste Kern aus pinnen verwendet
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        let kern: &CoreId = einstellungen.pinnen.first().ok_or(Fehler::KeineKerne)?;
+        ausführen(a, b, c, m, k, n, kern)
+    }
 }
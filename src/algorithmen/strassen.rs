@@ -0,0 +1,352 @@
+use crate::algorithmen::gepack;
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::zaehler;
+use crate::zahl::Zahl;
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+/*
+    Strassen Algorithmus: statt 8 werden nur 7 Multiplikationen der halben Größe benötigt (O(n^2.81)).
+
+    Die Rekursion endet sobald eine der Dimensionen m, k, n höchstens grenze ist, ab dann rechnet der Basis
+    Algorithmus. Ungerade Größen werden aufgefüllt: die Quadranten haben die Größe ceil(m/2) x ceil(k/2) usw.
+    und die fehlenden Zeilen und Spalten sind 0. Die 7 Teilprodukte werden auf die gepinnten Threads verteilt
+*/
+pub struct Strassen<T: Zahl = f64> {
+    pub grenze: usize,
+    pub basis: &'static dyn Multiplikator<T>,
+}
+
+impl<T: Zahl> Strassen<T> {
+    pub const fn neu(grenze: usize, basis: &'static dyn Multiplikator<T>) -> Strassen<T> {
+        Strassen { grenze, basis }
+    }
+
+    // Variante im Register aller Algorithmen
+    pub const fn standard() -> Strassen<T> {
+        Strassen::neu(256, &gepack::Gepack)
+    }
+}
+
+pub const STANDARD: Strassen = Strassen::standard();
+
+// Faktorpaare der 7 Teilprodukte
+pub(crate) type Paare<T> = Vec<(Matrix<T>, Matrix<T>)>;
+
+// die vier Quadranten [x11, x12, x21, x22] einer Matrix
+pub(crate) type Quadranten<T> = [Matrix<T>; 4];
+
+// von einer Gruppe berechnete Teilprodukte mit Index
+type Rückgabe<T> = Result<Vec<(usize, Matrix<T>)>, Fehler>;
+
+/*
+    Rechenvorschrift einer Variante von Strassen
+
+    produkte: bildet aus den Quadranten [x11, x12, x21, x22] von a und b die 7 Faktorpaare
+    zusammensetzen: bildet aus den 7 Produkten die Quadranten [c11, c12, c21, c22]
+*/
+pub(crate) struct Schema<T: Zahl> {
+    pub(crate) produkte: fn(&Quadranten<T>, &Quadranten<T>) -> Paare<T>,
+    pub(crate) zusammensetzen: fn(&[Matrix<T>]) -> Quadranten<T>,
+}
+
+fn schema<T: Zahl>() -> Schema<T> {
+    Schema {
+        produkte,
+        zusammensetzen,
+    }
+}
+
+/*
+    M1 = (A11 + A22)(B11 + B22)     M5 = (A11 + A12) B22
+    M2 = (A21 + A22) B11            M6 = (A21 - A11)(B11 + B12)
+    M3 = A11 (B12 - B22)            M7 = (A12 - A22)(B21 + B22)
+    M4 = A22 (B21 - B11)
+*/
+fn produkte<T: Zahl>(a: &[Matrix<T>; 4], b: &[Matrix<T>; 4]) -> Paare<T> {
+    let [a11, a12, a21, a22] = a;
+    let [b11, b12, b21, b22] = b;
+
+    vec![
+        (plus(a11, a22), plus(b11, b22)),
+        (plus(a21, a22), b11.clone()),
+        (a11.clone(), minus(b12, b22)),
+        (a22.clone(), minus(b21, b11)),
+        (plus(a11, a12), b22.clone()),
+        (minus(a21, a11), plus(b11, b12)),
+        (minus(a12, a22), plus(b21, b22)),
+    ]
+}
+
+/*
+    C11 = M1 + M4 - M5 + M7     C12 = M3 + M5
+    C21 = M2 + M4               C22 = M1 - M2 + M3 + M6
+*/
+fn zusammensetzen<T: Zahl>(m: &[Matrix<T>]) -> [Matrix<T>; 4] {
+    [
+        plus(&minus(&plus(&m[0], &m[3]), &m[4]), &m[6]),
+        plus(&m[2], &m[4]),
+        plus(&m[1], &m[3]),
+        plus(&plus(&minus(&m[0], &m[1]), &m[2]), &m[5]),
+    ]
+}
+
+/*
+    gemeinsamer Ablauf für Strassen und Winograd: prüfen, rekursiv rechnen und in c kopieren
+*/
+pub(crate) fn ausführen_mit_schema<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
+    n: usize,
+    grenze: usize,
+    basis: &dyn Multiplikator<T>,
+    einstellungen: &Einstellungen,
+    schema: &Schema<T>,
+) -> Result<(), Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+    fehler::threads_prüfen(einstellungen.threads, &einstellungen.pinnen)?;
+
+    // ohne Rekursion direkt in c rechnen
+    if m.min(k).min(n) <= grenze.max(1) {
+        return basis.ausführen(a, b, c, m, k, n, einstellungen);
+    }
+
+    let ergebnis: Matrix<T> = rekursiv(a, b, grenze.max(1), basis, einstellungen, schema)?;
+    einsetzen(c, &ergebnis, 0, 0);
+    Ok(())
+}
+
+/*
+    berechnet a * b rekursiv und gibt das Produkt als neue Matrix zurück
+*/
+fn rekursiv<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    grenze: usize,
+    basis: &dyn Multiplikator<T>,
+    einstellungen: &Einstellungen,
+    schema: &Schema<T>,
+) -> Result<Matrix<T>, Fehler> {
+    let (m, k, n): (usize, usize, usize) = (a.zeilen, a.spalten, b.spalten);
+
+    if m.min(k).min(n) <= grenze {
+        let mut c: Matrix<T> = Matrix::neu(m, n);
+        basis.ausführen(a, b, &mut c, m, k, n, einstellungen)?;
+        return Ok(c);
+    }
+
+    // Größe der Quadranten, bei ungeraden Größen wird mit 0 aufgefüllt
+    let (hm, hk, hn): (usize, usize, usize) = (m.div_ceil(2), k.div_ceil(2), n.div_ceil(2));
+
+    let a_teile: [Matrix<T>; 4] = quadranten(a, hm, hk);
+    let b_teile: [Matrix<T>; 4] = quadranten(b, hk, hn);
+
+    let paare: Paare<T> = (schema.produkte)(&a_teile, &b_teile);
+    let produkte: Vec<Matrix<T>> =
+        parallel_multiplizieren(&paare, grenze, basis, einstellungen, schema)?;
+
+    let [c11, c12, c21, c22] = (schema.zusammensetzen)(&produkte);
+
+    // nur den gültigen Bereich zurückkopieren, die aufgefüllten Zeilen und Spalten fallen weg
+    let mut c: Matrix<T> = Matrix::neu(m, n);
+    einsetzen(&mut c, &c11, 0, 0);
+    einsetzen(&mut c, &c12, 0, hn);
+    einsetzen(&mut c, &c21, hm, 0);
+    einsetzen(&mut c, &c22, hm, hn);
+    Ok(c)
+}
+
+/*
+    berechnet die Teilprodukte parallel. Die Threads werden in gleich große Gruppen aufgeteilt (höchstens eine
+    Gruppe pro Produkt). Jede Gruppe holt sich dynamisch das nächste Produkt und rechnet es mit ihren Kernen
+    weiter, bei mindestens 14 Threads sind so auch die tieferen Ebenen parallel
+*/
+fn parallel_multiplizieren<T: Zahl>(
+    paare: &[(Matrix<T>, Matrix<T>)],
+    grenze: usize,
+    basis: &dyn Multiplikator<T>,
+    einstellungen: &Einstellungen,
+    schema: &Schema<T>,
+) -> Result<Vec<Matrix<T>>, Fehler> {
+    let threads: usize = einstellungen.threads;
+
+    // sequentiell ohne neue Threads
+    if threads == 1 {
+        return paare
+            .iter()
+            .map(|(x, y)| rekursiv(x, y, grenze, basis, einstellungen, schema))
+            .collect();
+    }
+
+    let gruppen: usize = threads.min(paare.len());
+
+    // die Threads der Gruppen zählen nicht mit
+    zaehler::nicht_erfasst();
+
+    // atomarer Zähler für die dynamische Verteilung der Produkte
+    let zähler: AtomicUsize = AtomicUsize::new(0);
+
+    thread::scope(|s| {
+        // Thread Handles fürs joinen sammeln
+        let mut sammeln: Vec<thread::ScopedJoinHandle<'_, Rückgabe<T>>> =
+            Vec::with_capacity(gruppen);
+
+        for g in 0..gruppen {
+            // Kerne der Gruppe g, alle anderen Einstellungen gelten auch für die Teilprodukte
+            let gruppe: Einstellungen = Einstellungen {
+                threads: (g + 1) * threads / gruppen - g * threads / gruppen,
+                pinnen: einstellungen.pinnen[g * threads / gruppen..(g + 1) * threads / gruppen]
+                    .to_vec(),
+                ..einstellungen.clone()
+            };
+            let zähler_neu: &AtomicUsize = &zähler;
+
+            let handle = s.spawn(move || {
+                fehler::pinnen(gruppe.pinnen[0])?;
+
+                let mut berechnet: Vec<(usize, Matrix<T>)> = Vec::new();
+                loop {
+                    let i: usize = zähler_neu.fetch_add(1, Ordering::Relaxed);
+                    if i >= paare.len() {
+                        break;
+                    }
+                    let (x, y) = &paare[i];
+                    berechnet.push((i, rekursiv(x, y, grenze, basis, &gruppe, schema)?));
+                }
+                Ok(berechnet)
+            });
+            sammeln.push(handle);
+        }
+
+        // zuerst alle Threads joinen, damit bei einem Fehler kein Thread mehr läuft
+        let rückgaben: Vec<Rückgabe<T>> = sammeln.into_iter().map(fehler::joinen).collect();
+
+        // Produkte wieder in die richtige Reihenfolge bringen
+        let mut produkte: Vec<Option<Matrix<T>>> = vec![None; paare.len()];
+        for rückgabe in rückgaben {
+            for (i, produkt) in rückgabe? {
+                produkte[i] = Some(produkt);
+            }
+        }
+        Ok(produkte.into_iter().flatten().collect())
+    })
+}
+
+/*
+    teilt x in die vier Quadranten [x11, x12, x21, x22] der Größe zeilen x spalten auf
+*/
+pub(crate) fn quadranten<T: Zahl>(x: &Matrix<T>, zeilen: usize, spalten: usize) -> [Matrix<T>; 4] {
+    [
+        block(x, 0, 0, zeilen, spalten),
+        block(x, 0, spalten, zeilen, spalten),
+        block(x, zeilen, 0, zeilen, spalten),
+        block(x, zeilen, spalten, zeilen, spalten),
+    ]
+}
+
+/*
+    kopiert den zeilen x spalten Block ab (zeile, spalte) aus x. Werte außerhalb von x sind 0
+*/
+fn block<T: Zahl>(
+    x: &Matrix<T>,
+    zeile: usize,
+    spalte: usize,
+    zeilen: usize,
+    spalten: usize,
+) -> Matrix<T> {
+    let mut ergebnis: Matrix<T> = Matrix::neu(zeilen, spalten);
+
+    let gültige_zeilen: usize = zeilen.min(x.zeilen.saturating_sub(zeile));
+    let gültige_spalten: usize = spalten.min(x.spalten.saturating_sub(spalte));
+
+    for i in 0..gültige_zeilen {
+        ergebnis.zeile_mut(i)[..gültige_spalten]
+            .copy_from_slice(&x.zeile(zeile + i)[spalte..spalte + gültige_spalten]);
+    }
+    ergebnis
+}
+
+/*
+    kopiert x ab (zeile, spalte) in c, soweit es in c hineinpasst
+*/
+pub(crate) fn einsetzen<T: Zahl>(c: &mut Matrix<T>, x: &Matrix<T>, zeile: usize, spalte: usize) {
+    let gültige_zeilen: usize = x.zeilen.min(c.zeilen.saturating_sub(zeile));
+    let gültige_spalten: usize = x.spalten.min(c.spalten.saturating_sub(spalte));
+
+    for i in 0..gültige_zeilen {
+        c.zeile_mut(zeile + i)[spalte..spalte + gültige_spalten]
+            .copy_from_slice(&x.zeile(i)[..gültige_spalten]);
+    }
+}
+
+/*
+    elementweise Summe und Differenz zweier gleich großer Matrizen
+*/
+pub(crate) fn plus<T: Zahl>(x: &Matrix<T>, y: &Matrix<T>) -> Matrix<T> {
+    elementweise(x, y, |u, v| u + v)
+}
+
+pub(crate) fn minus<T: Zahl>(x: &Matrix<T>, y: &Matrix<T>) -> Matrix<T> {
+    elementweise(x, y, |u, v| u - v)
+}
+
+fn elementweise<T: Zahl>(x: &Matrix<T>, y: &Matrix<T>, f: impl Fn(T, T) -> T) -> Matrix<T> {
+    let mut ergebnis: Matrix<T> = Matrix::neu(x.zeilen, x.spalten);
+
+    for i in 0..x.zeilen {
+        let (x_zeile, y_zeile) = (x.zeile(i), y.zeile(i));
+        for (j, wert) in ergebnis.zeile_mut(i).iter_mut().enumerate() {
+            *wert = f(x_zeile[j], y_zeile[j]);
+        }
+    }
+    ergebnis
+}
+
+impl<T: Zahl> Multiplikator<T> for Strassen<T> {
+    fn name(&self) -> &'static str {
+        "strassen"
+    }
+
+    fn beschreibung(&self) -> &'static str {
+        "Strassen"
+    }
+
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        Fähigkeiten {
+            parallel: true,
+            simd: false,
+            unsicher: false,
+        }
+    }
+
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        ausführen_mit_schema(
+            a,
+            b,
+            c,
+            m,
+            k,
+            n,
+            self.grenze,
+            self.basis,
+            einstellungen,
+            &schema(),
+        )
+    }
+}
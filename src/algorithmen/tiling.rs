@@ -1,92 +1,167 @@
-use core_affinity::{CoreId, set_for_current};
-use std::{
-    sync::atomic::{AtomicUsize, Ordering},
-    thread,
-};
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::rechenpool::{self, Ausführung};
+use crate::verteilung::{Planer, Verteilung};
+use crate::zahl::Zahl;
+use crate::zerlegung::{self, Kachel, Zerlegung};
+use core_affinity::CoreId;
+
+// von einem Thread berechnete Zeilen mit Zeilenindex
+type Berechnet<T> = Vec<(usize, Vec<T>)>;
 
 /*
-    dynamische Arbeitsverteilung mit Rust Threads. Es wird Block Tiling verwendet
+    verteilung::Planer verteilt die Arbeit auf Rust Threads. Es wird Block Tiling verwendet
 */
-pub fn ausführen(
-    a: &Vec<Vec<f64>>,
-    b: &Vec<Vec<f64>>,
-    c: &mut Vec<Vec<f64>>,
+pub fn ausführen<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
     n: usize,
     threads: usize,
-    pinnen: &Vec<CoreId>,
-) {
-    // jeder Thread darf sich jedesmal 4 Zeilen nehmen
-    let zeilen: usize = 4;
+    pinnen: &[CoreId],
+    ausführung: Ausführung,
+    verteilung: Verteilung,
+    zerlegung: Zerlegung,
+) -> Result<(), Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+    fehler::threads_prüfen(threads, pinnen)?;
 
     // Blockgröße
     let block = 8;
 
-    // atomarer Zähler für die dynamische Arbeitsverteilung mit Startwert null (= nächste zu verarbeitende Zeile)
-    let zähler: AtomicUsize = AtomicUsize::new(0);
-
-    thread::scope(|s| {
-        // Thread Handles fürs joinen sammeln
-        let mut sammeln: Vec<thread::ScopedJoinHandle<'_, Vec<(usize, Vec<f64>)>>> =
-            Vec::with_capacity(threads);
-
-        for z in 0..threads {
-            let kern: CoreId = pinnen[z];
-
-            let zähler_neu: &AtomicUsize = &zähler;
-
-            let handle: thread::ScopedJoinHandle<'_, Vec<(usize, Vec<f64>)>> = s.spawn(move || {
-                set_for_current(kern);
-
-                // berechnete Zeilen sammeln
-                let mut berechnet: Vec<(usize, Vec<f64>)> = Vec::new();
-
-                // Schleife für die dynamischen Zeilenverteilung
-                loop {
-                    // anfang des aktuellen Zeilenbereichs
-                    let anfang: usize = zähler_neu.fetch_add(zeilen, Ordering::Relaxed);
-                    if anfang >= n {
-                        break;
-                    }
-
-                    // ende des aktuellen Zeilenbereichs
-                    let ende: usize = (anfang + zeilen).min(n);
-
-                    for i in anfang..ende {
-                        let mut zeile: Vec<f64> = vec![0.0; n];
-
-                        // äußere Schleife über j-Blöcke um b[k][j] erneut zu benutzen
-                        for k_block in (0..n).step_by(block) {
-                            let k_max = (k_block + block).min(n);
-
-                            // innere Schleife über k Blöcke
-                            for j_block in (0..n).step_by(block) {
-                                let j_max = (j_block + block).min(n);
-
-                                for k in k_block..k_max {
-                                    // ändert sich in Schleife j nicht
-                                    let optimiert = a[i][k];
-
-                                    for j in j_block..j_max {
-                                        zeile[j] = zeile[j] + optimiert * b[k][j];
-                                    }
+    // Kacheln von c, die Blöcke laufen dann nur über die Spalten und Summanden der Kachel
+    if let Zerlegung::Kacheln { .. } = zerlegung {
+        return zerlegung::kacheln_berechnen(
+            c,
+            m,
+            k,
+            n,
+            zerlegung,
+            threads,
+            pinnen,
+            ausführung,
+            verteilung,
+            |kachel: &Kachel, werte: &mut [T]| {
+                let anfang: usize = kachel.spalten.start;
+                let breite: usize = kachel.spalten.len();
+
+                for (zeile, i) in kachel.zeilen.clone().enumerate() {
+                    let a_zeile: &[T] = a.zeile(i);
+                    let ziel: &mut [T] = &mut werte[zeile * breite..(zeile + 1) * breite];
+
+                    for k_block in kachel.tiefe.clone().step_by(block) {
+                        let k_max = (k_block + block).min(kachel.tiefe.end);
+
+                        for j_block in kachel.spalten.clone().step_by(block) {
+                            let j_max = (j_block + block).min(kachel.spalten.end);
+
+                            for p in k_block..k_max {
+                                let optimiert = a_zeile[p];
+                                let b_zeile: &[T] = b.zeile(p);
+
+                                for j in j_block..j_max {
+                                    ziel[j - anfang] += optimiert * b_zeile[j];
                                 }
                             }
                         }
-                        berechnet.push((i, zeile));
                     }
                 }
-                // Rückgabe von Thread
-                berechnet
-            });
-            sammeln.push(handle);
+            },
+        );
+    }
+
+    // Verteilung der Zeilen auf die Threads
+    let planer: Planer = Planer::neu(verteilung, m, &pinnen[..threads]);
+
+    let ergebnisse: Vec<Berechnet<T>> = rechenpool::verteilen(threads, pinnen, ausführung, |z| {
+        // berechnete Zeilen sammeln
+        let mut berechnet: Berechnet<T> = Vec::new();
+
+        // Schleife für die Zeilenverteilung, nächster() liefert den nächsten Bereich dieses Threads
+        while let Some(bereich) = planer.nächster(z) {
+            for i in bereich {
+                let a_zeile: &[T] = a.zeile(i);
+                let mut zeile: Vec<T> = vec![T::NULL; n];
+
+                // äußere Schleife über j-Blöcke um b[k][j] erneut zu benutzen
+                for k_block in (0..k).step_by(block) {
+                    let k_max = (k_block + block).min(k);
+
+                    // innere Schleife über k Blöcke
+                    for j_block in (0..n).step_by(block) {
+                        let j_max = (j_block + block).min(n);
+
+                        for p in k_block..k_max {
+                            // ändert sich in Schleife j nicht
+                            let optimiert = a_zeile[p];
+                            let b_zeile: &[T] = b.zeile(p);
+
+                            for j in j_block..j_max {
+                                zeile[j] += optimiert * b_zeile[j];
+                            }
+                        }
+                    }
+                }
+                berechnet.push((i, zeile));
+            }
         }
+        // Rückgabe von Thread
+        Ok(berechnet)
+    })?;
+
+    // Kopieren der Zeilen in den zusammenhängenden Speicher der Ergebnismatrix
+    for berechnet in ergebnisse {
+        for (i, zeile) in berechnet {
+            c.zeile_mut(i)[..n].copy_from_slice(&zeile);
+        }
+    }
+    Ok(())
+}
 
-        // Speichern der Zeilen in der Ergebnismatrix durch austauschen der Zeiger (es werden keine Daten kopiert)
-        for h in sammeln {
-            let rückgabe: Vec<(usize, Vec<f64>)> = h.join().unwrap();
-            for (i, zeile) in rückgabe {
-                c[i] = zeile;
-            }
+pub struct Tiling;
+
+impl<T: Zahl> Multiplikator<T> for Tiling {
+    fn name(&self) -> &'static str {
+        "tiling"
+    }
+
+    fn beschreibung(&self) -> &'static str {
+        "block tiling"
+    }
+
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        Fähigkeiten {
+            parallel: true,
+            simd: false,
+            unsicher: false,
         }
-    });
+    }
+
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        ausführen(
+            a,
+            b,
+            c,
+            m,
+            k,
+            n,
+            einstellungen.threads,
+            &einstellungen.pinnen,
+            einstellungen.ausführung,
+            einstellungen.verteilung,
+            einstellungen.zerlegung,
+        )
+    }
 }
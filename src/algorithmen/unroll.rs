@@ -1,94 +1,123 @@
-use core_affinity::{CoreId, set_for_current};
-use std::{
-    sync::atomic::{AtomicUsize, Ordering},
-    thread,
-};
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::rechenpool::{self, Ausführung};
+use crate::verteilung::{Planer, Verteilung};
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
+
+// von einem Thread berechnete Zeilen mit Zeilenindex
+type Berechnet<T> = Vec<(usize, Vec<T>)>;
 
 /*
-    dynamische Arbeitsverteilung mit Rust Threads. Es wird loop unrolling verwendet
+    verteilung::Planer verteilt die Arbeit auf Rust Threads. Es wird loop unrolling verwendet
 */
-pub fn ausführen(
-    a: &Vec<Vec<f64>>,
-    b: &Vec<Vec<f64>>,
-    c: &mut Vec<Vec<f64>>,
+pub fn ausführen<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
     n: usize,
     threads: usize,
-    pinnen: &Vec<CoreId>,
-) {
-    // jeder Thread darf sich jedesmal 4 Zeilen nehmen
-    let zeilen: usize = 4;
+    pinnen: &[CoreId],
+    ausführung: Ausführung,
+    verteilung: Verteilung,
+) -> Result<(), Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+    fehler::threads_prüfen(threads, pinnen)?;
 
     // zeilen per loop unrolling
     let faktor: usize = 4;
 
-    // atomarer Zähler für die dynamische Arbeitsverteilung mit Startwert null (= nächste zu verarbeitende Zeile)
-    let zähler: AtomicUsize = AtomicUsize::new(0);
-
-    thread::scope(|s| {
-        // Thread Handles fürs joinen sammeln
-        let mut sammeln: Vec<thread::ScopedJoinHandle<'_, Vec<(usize, Vec<f64>)>>> =
-            Vec::with_capacity(threads);
-
-        for z in 0..threads {
-            let kern: CoreId = pinnen[z];
-
-            let zähler_neu: &AtomicUsize = &zähler;
-
-            let handle: thread::ScopedJoinHandle<'_, Vec<(usize, Vec<f64>)>> = s.spawn(move || {
-                set_for_current(kern);
-
-                // berechnete Zeilen sammeln
-                let mut berechnet: Vec<(usize, Vec<f64>)> = Vec::new();
-
-                // Schleife für die dynamischen Zeilenverteilung
-                loop {
-                    // anfang des aktuellen Zeilenbereichs
-                    let anfang: usize = zähler_neu.fetch_add(zeilen, Ordering::Relaxed);
-                    if anfang >= n {
-                        break;
+    // Verteilung der Zeilen auf die Threads
+    let planer: Planer = Planer::neu(verteilung, m, &pinnen[..threads]);
+
+    let ergebnisse: Vec<Berechnet<T>> = rechenpool::verteilen(threads, pinnen, ausführung, |z| {
+        // berechnete Zeilen sammeln
+        let mut berechnet: Berechnet<T> = Vec::new();
+
+        // Schleife für die Zeilenverteilung, nächster() liefert den nächsten Bereich dieses Threads
+        while let Some(bereich) = planer.nächster(z) {
+            // ab hier bleiben weniger als faktor Summanden übrig
+            let grenze = k - k % faktor;
+
+            for i in bereich {
+                let a_zeile: &[T] = a.zeile(i);
+                let mut zeile: Vec<T> = vec![T::NULL; n];
+
+                for j in 0..n {
+                    let mut summe: T = T::NULL;
+                    for p in (0..grenze).step_by(faktor) {
+                        summe += a_zeile[p] * b[(p, j)]
+                            + a_zeile[p + 1] * b[(p + 1, j)]
+                            + a_zeile[p + 2] * b[(p + 2, j)]
+                            + a_zeile[p + 3] * b[(p + 3, j)];
                     }
 
-                    // ende des aktuellen Zeilenbereichs
-                    let ende: usize = (anfang + zeilen).min(n);
-
-                    // restliche Zeilen
-                    let grenze = n - n % faktor;
-
-                    for i in anfang..ende {
-                        let mut zeile: Vec<f64> = vec![0.0; n];
-
-                        for j in 0..n {
-                            let mut summe: f64 = 0.0;
-                            for k in (0..grenze).step_by(faktor) {
-                                summe = summe
-                                    + a[i][k] * b[k][j]
-                                    + a[i][k + 1] * b[k + 1][j]
-                                    + a[i][k + 2] * b[k + 2][j]
-                                    + a[i][k + 3] * b[k + 3][j];
-                            }
-
-                            // restliche Zeilen
-                            for k in grenze..n {
-                                summe = summe + a[i][k] * b[k][j];
-                            }
-                            zeile[j] = summe;
-                        }
-
-                        berechnet.push((i, zeile));
+                    // restliche Summanden
+                    for p in grenze..k {
+                        summe += a_zeile[p] * b[(p, j)];
                     }
+                    zeile[j] = summe;
                 }
-                // Rückgabe von Thread
-                berechnet
-            });
-            sammeln.push(handle);
-        }
 
-        // Speichern der Zeilen in der Ergebnismatrix durch austauschen der Zeiger (es werden keine Daten kopiert)
-        for h in sammeln {
-            let rückgabe: Vec<(usize, Vec<f64>)> = h.join().unwrap();
-            for (i, zeile) in rückgabe {
-                c[i] = zeile;
+                berechnet.push((i, zeile));
             }
         }
-    });
+        // Rückgabe von Thread
+        Ok(berechnet)
+    })?;
+
+    // Kopieren der Zeilen in den zusammenhängenden Speicher der Ergebnismatrix
+    for berechnet in ergebnisse {
+        for (i, zeile) in berechnet {
+            c.zeile_mut(i)[..n].copy_from_slice(&zeile);
+        }
+    }
+    Ok(())
+}
+
+pub struct Unroll;
+
+impl<T: Zahl> Multiplikator<T> for Unroll {
+    fn name(&self) -> &'static str {
+        "unroll"
+    }
+
+    fn beschreibung(&self) -> &'static str {
+        "loop unrolling"
+    }
+
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        Fähigkeiten {
+            parallel: true,
+            simd: false,
+            unsicher: false,
+        }
+    }
+
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        ausführen(
+            a,
+            b,
+            c,
+            m,
+            k,
+            n,
+            einstellungen.threads,
+            &einstellungen.pinnen,
+            einstellungen.ausführung,
+            einstellungen.verteilung,
+        )
+    }
 }
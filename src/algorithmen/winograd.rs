@@ -0,0 +1,128 @@
+use crate::algorithmen::gepack;
+use crate::algorithmen::strassen::{self, Paare, Schema, minus, plus};
+use crate::fehler::Fehler;
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator};
+use crate::zahl::Zahl;
+
+/*
+    Strassen-Winograd Variante: ebenfalls 7 Multiplikationen, aber nur 15 statt 18 Additionen je Ebene, da
+    Zwischensummen wiederverwendet werden. Rekursion, Auffüllen ungerader Größen und parallele Berechnung der
+    Teilprodukte sind identisch zu strassen.rs
+*/
+pub struct Winograd<T: Zahl = f64> {
+    pub grenze: usize,
+    pub basis: &'static dyn Multiplikator<T>,
+}
+
+impl<T: Zahl> Winograd<T> {
+    pub const fn neu(grenze: usize, basis: &'static dyn Multiplikator<T>) -> Winograd<T> {
+        Winograd { grenze, basis }
+    }
+
+    // Variante im Register aller Algorithmen
+    pub const fn standard() -> Winograd<T> {
+        Winograd::neu(256, &gepack::Gepack)
+    }
+}
+
+pub const STANDARD: Winograd = Winograd::standard();
+
+fn schema<T: Zahl>() -> Schema<T> {
+    Schema {
+        produkte,
+        zusammensetzen,
+    }
+}
+
+/*
+    S1 = A21 + A22      T1 = B12 - B11      P1 = A11 B11    P5 = S1 T1
+    S2 = S1 - A11       T2 = B22 - T1       P2 = A12 B21    P6 = S2 T2
+    S3 = A11 - A21      T3 = B22 - B12      P3 = S4 B22     P7 = S3 T3
+    S4 = A12 - S2       T4 = T2 - B21       P4 = A22 T4
+*/
+fn produkte<T: Zahl>(a: &[Matrix<T>; 4], b: &[Matrix<T>; 4]) -> Paare<T> {
+    let [a11, a12, a21, a22] = a;
+    let [b11, b12, b21, b22] = b;
+
+    let s1: Matrix<T> = plus(a21, a22);
+    let s2: Matrix<T> = minus(&s1, a11);
+    let s3: Matrix<T> = minus(a11, a21);
+    let s4: Matrix<T> = minus(a12, &s2);
+
+    let t1: Matrix<T> = minus(b12, b11);
+    let t2: Matrix<T> = minus(b22, &t1);
+    let t3: Matrix<T> = minus(b22, b12);
+    let t4: Matrix<T> = minus(&t2, b21);
+
+    vec![
+        (a11.clone(), b11.clone()),
+        (a12.clone(), b21.clone()),
+        (s4, b22.clone()),
+        (a22.clone(), t4),
+        (s1, t1),
+        (s2, t2),
+        (s3, t3),
+    ]
+}
+
+/*
+    U1 = P1 + P2 = C11      U5 = U4 + P3 = C12
+    U2 = P1 + P6            U6 = U3 - P4 = C21
+    U3 = U2 + P7            U7 = U3 + P5 = C22
+    U4 = U2 + P5
+*/
+fn zusammensetzen<T: Zahl>(p: &[Matrix<T>]) -> [Matrix<T>; 4] {
+    let u2: Matrix<T> = plus(&p[0], &p[5]);
+    let u3: Matrix<T> = plus(&u2, &p[6]);
+    let u4: Matrix<T> = plus(&u2, &p[4]);
+
+    [
+        plus(&p[0], &p[1]),
+        plus(&u4, &p[2]),
+        minus(&u3, &p[3]),
+        plus(&u3, &p[4]),
+    ]
+}
+
+impl<T: Zahl> Multiplikator<T> for Winograd<T> {
+    fn name(&self) -> &'static str {
+        "winograd"
+    }
+
+    fn beschreibung(&self) -> &'static str {
+        "Strassen-Winograd"
+    }
+
+    fn fähigkeiten(&self) -> Fähigkeiten {
+        Fähigkeiten {
+            parallel: true,
+            simd: false,
+            unsicher: false,
+        }
+    }
+
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        strassen::ausführen_mit_schema(
+            a,
+            b,
+            c,
+            m,
+            k,
+            n,
+            self.grenze,
+            self.basis,
+            einstellungen,
+            &schema(),
+        )
+    }
+}
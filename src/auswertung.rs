@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+/*
+    Anzahl der Gleitkommaoperationen einer Multiplikation einer m x k mit einer k x n Matrix: je Wert von c
+    k Multiplikationen und k Additionen
+*/
+pub fn operationen(m: usize, k: usize, n: usize) -> u64 {
+    2 * m as u64 * k as u64 * n as u64
+}
+
+// Milliarden Gleitkommaoperationen pro Sekunde bei einer Laufzeit von zeit
+pub fn gflops(m: usize, k: usize, n: usize, zeit: Duration) -> f64 {
+    operationen(m, k, n) as f64 / zeit.as_secs_f64() / 1e9
+}
+
+/*
+    gemessene Zeit einer Multiplikation eines Algorithmus mit threads Threads
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ergebnis {
+    pub algorithmus: String,
+    pub threads: usize,
+    pub m: usize,
+    pub k: usize,
+    pub n: usize,
+    pub zeit: Duration,
+}
+
+impl Ergebnis {
+    pub fn gflops(&self) -> f64 {
+        gflops(self.m, self.k, self.n, self.zeit)
+    }
+}
+
+/*
+    Ergebnis mit Beschleunigung (speedup) und paralleler Effizienz gegenüber dem Referenz Algorithmus derselben
+    Größe. speedup ist zeit der Referenz / zeit, effizienz ist speedup / threads. Beide sind None, wenn die
+    Referenz für diese Größe nicht gemessen wurde
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Effizienz {
+    pub ergebnis: Ergebnis,
+    pub speedup: Option<f64>,
+    pub effizienz: Option<f64>,
+}
+
+/*
+    berechnet speedup und effizienz aller ergebnisse gegenüber dem Algorithmus referenz (z.B. "single"). Gibt es
+    mehrere Messungen der Referenz für eine Größe, wird die mit einem Thread bzw. den wenigsten Threads verwendet
+*/
+pub fn effizienz(ergebnisse: &[Ergebnis], referenz: &str) -> Vec<Effizienz> {
+    ergebnisse
+        .iter()
+        .map(|ergebnis| {
+            let basis: Option<&Ergebnis> = ergebnisse
+                .iter()
+                .filter(|r| {
+                    r.algorithmus == referenz
+                        && (r.m, r.k, r.n) == (ergebnis.m, ergebnis.k, ergebnis.n)
+                })
+                .min_by_key(|r| r.threads);
+            let speedup: Option<f64> =
+                basis.map(|basis| basis.zeit.as_secs_f64() / ergebnis.zeit.as_secs_f64());
+
+            Effizienz {
+                ergebnis: ergebnis.clone(),
+                speedup,
+                effizienz: speedup.map(|speedup| speedup / ergebnis.threads.max(1) as f64),
+            }
+        })
+        .collect()
+}
+
+/*
+    CSV mit Kopfzeile, eine Zeile je Ergebnis. Fehlende speedup und effizienz bleiben leer
+*/
+pub fn csv(effizienzen: &[Effizienz]) -> String {
+    let mut text: String =
+        String::from("algorithmus,threads,m,k,n,zeit_ms,gflops,speedup,effizienz\n");
+    let optional = |wert: Option<f64>| wert.map_or(String::new(), |wert| wert.to_string());
+
+    for effizienz in effizienzen {
+        let ergebnis: &Ergebnis = &effizienz.ergebnis;
+        text += &format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            ergebnis.algorithmus,
+            ergebnis.threads,
+            ergebnis.m,
+            ergebnis.k,
+            ergebnis.n,
+            ergebnis.zeit.as_secs_f64() * 1e3,
+            ergebnis.gflops(),
+            optional(effizienz.speedup),
+            optional(effizienz.effizienz)
+        );
+    }
+    text
+}
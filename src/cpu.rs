@@ -0,0 +1,106 @@
+use std::{env, fmt, fs, sync::OnceLock};
+
+/*
+    simd Pfad der Kernel. Die Prozessorfeatures werden beim ersten Aufruf von pfad() einmal erkannt, danach
+    wählen simd und simd_tiling damit die Registerbreite aus. So läuft dieselbe Binary auf allen Rechnern
+
+    Sse2: 128 bit Register (= 2 * 64 bit), auf x86_64 immer vorhanden
+    Avx2Fma: 256 bit Register (= 4 * 64 bit) mit fused multiply-add
+    Avx512: 512 bit Register (= 8 * 64 bit) mit fused multiply-add
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimdPfad {
+    Sse2,
+    Avx2Fma,
+    Avx512,
+}
+
+impl SimdPfad {
+    // Anzahl der f64 Werte in einem Register
+    pub fn breite(self) -> usize {
+        self.bytes() / size_of::<f64>()
+    }
+
+    // Registerbreite in Bytes, die Anzahl der Werte hängt vom Elementtyp ab
+    pub fn bytes(self) -> usize {
+        match self {
+            SimdPfad::Sse2 => 16,
+            SimdPfad::Avx2Fma => 32,
+            SimdPfad::Avx512 => 64,
+        }
+    }
+
+    // Pfad verwendet fused multiply-add
+    pub fn fma(self) -> bool {
+        self != SimdPfad::Sse2
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SimdPfad::Sse2 => "sse2",
+            SimdPfad::Avx2Fma => "avx2+fma",
+            SimdPfad::Avx512 => "avx512",
+        }
+    }
+}
+
+impl fmt::Display for SimdPfad {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+static PFAD: OnceLock<SimdPfad> = OnceLock::new();
+
+/*
+    der beste vom Prozessor unterstützte Pfad, wird nur beim ersten Aufruf erkannt
+*/
+pub fn pfad() -> SimdPfad {
+    *PFAD.get_or_init(|| unterstützt().into_iter().last().unwrap_or(SimdPfad::Sse2))
+}
+
+/*
+    alle vom Prozessor unterstützten Pfade, aufsteigend nach Registerbreite. Auf anderen Architekturen als x86_64
+    wird nur Sse2 zurückgegeben, dort übersetzt der Compiler die 128 bit Vektoren für die jeweilige Plattform
+*/
+pub fn unterstützt() -> Vec<SimdPfad> {
+    let mut pfade: Vec<SimdPfad> = vec![SimdPfad::Sse2];
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+            pfade.push(SimdPfad::Avx2Fma);
+        }
+        // auf dem avx512 Pfad laufen auch Kerne mit avx2 (z.B. der Mikrokern von gepack)
+        if is_x86_feature_detected!("avx512f")
+            && is_x86_feature_detected!("avx2")
+            && is_x86_feature_detected!("fma")
+        {
+            pfade.push(SimdPfad::Avx512);
+        }
+    }
+    pfade
+}
+
+/*
+    prüft ob ein Pfad auf diesem Prozessor ausgeführt werden darf
+*/
+pub fn verfügbar(pfad: SimdPfad) -> bool {
+    unterstützt().contains(&pfad)
+}
+
+/*
+    Modellname des Prozessors aus /proc/cpuinfo, z.B. um Messungen verschiedener Rechner zu unterscheiden. Ohne
+    /proc/cpuinfo wird die Architektur zurückgegeben
+*/
+pub fn modell() -> String {
+    fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|text| {
+            text.lines().find_map(|zeile| {
+                let (schlüssel, wert) = zeile.split_once(':')?;
+                (schlüssel.trim() == "model name").then(|| wert.trim().to_string())
+            })
+        })
+        .unwrap_or_else(|| env::consts::ARCH.to_string())
+}
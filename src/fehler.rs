@@ -0,0 +1,203 @@
+use crate::cpu::SimdPfad;
+use crate::matrix::Matrix;
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
+use std::{fmt, thread::ScopedJoinHandle};
+
+/*
+    Fehler die bei einer Matrixmultiplikation auftreten können
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Fehler {
+    // eine Matrix hat nicht die für m, k, n erwartete Größe (zeilen, spalten)
+    Dimension {
+        matrix: &'static str,
+        erwartet: (usize, usize),
+        tatsächlich: (usize, usize),
+    },
+    // es sollen 0 Threads verwendet werden
+    KeineThreads,
+    // mehr Threads als Kerne zum pinnen
+    ZuVieleThreads {
+        threads: usize,
+        kerne: usize,
+    },
+    // die Liste der Kerne zum pinnen ist leer
+    KeineKerne,
+    // Kerne konnten nicht ermittelt werden oder ein Thread konnte nicht gepinnt werden
+    Affinität(Option<CoreId>),
+    // ein Thread wurde mit einer panic beendet
+    ThreadPanik,
+    // der Threadpool einer Bibliothek konnte nicht erstellt werden
+    Threadpool(String),
+    // der angeforderte simd Pfad wird vom Prozessor nicht unterstützt
+    SimdPfad(SimdPfad),
+    // die Topologie der Prozessoren konnte nicht aus sysfs gelesen werden
+    Topologie(String),
+    // die Prüfung mit Freivalds hat in zeile von c eine Abweichung gefunden
+    Verifikation {
+        zeile: usize,
+        wiederholung: usize,
+    },
+    // ABFT hat mehr als einen falschen Wert gefunden, betroffen sind die Schnittpunkte der Zeilen und Spalten
+    Abft {
+        zeilen: Vec<usize>,
+        spalten: Vec<usize>,
+    },
+    // eine Datei konnte nicht gelesen oder geschrieben werden
+    Datei(String),
+    // der Inhalt einer Datei entspricht nicht dem Format
+    Dateiformat {
+        format: &'static str,
+        grund: String,
+    },
+    // ein Argument der Kommandozeile oder ein Wert der Konfiguration ist ungültig
+    Argument(String),
+    // Hardware Zähler konnten nicht mit perf_event_open geöffnet oder gelesen werden
+    Zähler(String),
+    // für eine Matrix mit zeilen x spalten Werten kann kein Speicher reserviert werden
+    Größe {
+        zeilen: usize,
+        spalten: usize,
+    },
+}
+
+impl fmt::Display for Fehler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fehler::Dimension {
+                matrix,
+                erwartet,
+                tatsächlich,
+            } => write!(
+                f,
+                "Matrix {} hat die Größe {}x{}, erwartet wurde {}x{}",
+                matrix, tatsächlich.0, tatsächlich.1, erwartet.0, erwartet.1
+            ),
+            Fehler::KeineThreads => write!(f, "es muss mindestens ein Thread verwendet werden"),
+            Fehler::ZuVieleThreads { threads, kerne } => write!(
+                f,
+                "{} Threads angefordert, aber nur {} Kerne zum pinnen angegeben",
+                threads, kerne
+            ),
+            Fehler::KeineKerne => write!(f, "die Liste der Kerne zum pinnen ist leer"),
+            Fehler::Affinität(Some(kern)) => {
+                write!(f, "Thread konnte nicht auf Kern {} gepinnt werden", kern.id)
+            }
+            Fehler::Affinität(None) => {
+                write!(f, "die Kerne des Systems konnten nicht ermittelt werden")
+            }
+            Fehler::ThreadPanik => write!(f, "ein Thread wurde mit einer panic beendet"),
+            Fehler::Threadpool(grund) => {
+                write!(f, "Fehler beim erstellen des Threadpools: {}", grund)
+            }
+            Fehler::SimdPfad(pfad) => {
+                write!(
+                    f,
+                    "der simd Pfad {} wird vom Prozessor nicht unterstützt",
+                    pfad
+                )
+            }
+            Fehler::Topologie(grund) => {
+                write!(f, "die Topologie konnte nicht gelesen werden: {}", grund)
+            }
+            Fehler::Verifikation {
+                zeile,
+                wiederholung,
+            } => write!(
+                f,
+                "Zeile {} des Ergebnisses ist falsch (erkannt in Wiederholung {} der Prüfung)",
+                zeile, wiederholung
+            ),
+            Fehler::Abft { zeilen, spalten } => write!(
+                f,
+                "die Prüfsummen der Zeilen {:?} und Spalten {:?} stimmen nicht, der Fehler kann nicht korrigiert werden",
+                zeilen, spalten
+            ),
+            Fehler::Datei(grund) => write!(f, "Fehler beim Zugriff auf die Datei {}", grund),
+            Fehler::Dateiformat { format, grund } => {
+                write!(f, "fehlerhafte {} Datei: {}", format, grund)
+            }
+            Fehler::Argument(grund) => write!(f, "ungültiges Argument: {}", grund),
+            Fehler::Zähler(grund) => write!(f, "Hardware Zähler nicht verfügbar: {}", grund),
+            Fehler::Größe { zeilen, spalten } => write!(
+                f,
+                "für eine Matrix mit {}x{} Werten kann kein Speicher reserviert werden",
+                zeilen, spalten
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Fehler {}
+
+/*
+    prüft ob a eine m x k, b eine k x n und c eine m x n Matrix ist
+*/
+pub fn dimensionen_prüfen<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &Matrix<T>,
+    m: usize,
+    k: usize,
+    n: usize,
+) -> Result<(), Fehler> {
+    for (name, matrix, erwartet) in [("a", a, (m, k)), ("b", b, (k, n)), ("c", c, (m, n))] {
+        let tatsächlich: (usize, usize) = (matrix.zeilen, matrix.spalten);
+        if tatsächlich != erwartet {
+            return Err(Fehler::Dimension {
+                matrix: name,
+                erwartet,
+                tatsächlich,
+            });
+        }
+    }
+    Ok(())
+}
+
+/*
+    prüft ob für jeden der threads Threads ein Kern zum pinnen vorhanden ist
+*/
+pub fn threads_prüfen(threads: usize, pinnen: &[CoreId]) -> Result<(), Fehler> {
+    if pinnen.is_empty() {
+        return Err(Fehler::KeineKerne);
+    }
+    if threads == 0 {
+        return Err(Fehler::KeineThreads);
+    }
+    if threads > pinnen.len() {
+        return Err(Fehler::ZuVieleThreads {
+            threads,
+            kerne: pinnen.len(),
+        });
+    }
+    Ok(())
+}
+
+/*
+    pinnt den aktuellen Thread auf kern
+*/
+pub fn pinnen(kern: CoreId) -> Result<(), Fehler> {
+    if core_affinity::set_for_current(kern) {
+        Ok(())
+    } else {
+        Err(Fehler::Affinität(Some(kern)))
+    }
+}
+
+/*
+    alle Kerne auf die der Prozess Threads pinnen darf
+*/
+pub fn kerne() -> Result<Vec<CoreId>, Fehler> {
+    match core_affinity::get_core_ids() {
+        Some(kerne) if !kerne.is_empty() => Ok(kerne),
+        _ => Err(Fehler::Affinität(None)),
+    }
+}
+
+/*
+    wartet auf das Ende eines Threads. Eine panic im Thread wird zu Fehler::ThreadPanik
+*/
+pub fn joinen<T>(handle: ScopedJoinHandle<'_, Result<T, Fehler>>) -> Result<T, Fehler> {
+    handle.join().unwrap_or(Err(Fehler::ThreadPanik))
+}
@@ -0,0 +1,158 @@
+use crate::fehler::Fehler;
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Multiplikator};
+use crate::zahl::Zahl;
+
+/*
+    gibt an ob eine Matrix für die Multiplikation transponiert verwendet wird (op(X) = X oder op(X) = X^T)
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transponierung {
+    Nein,
+    Ja,
+}
+
+impl Transponierung {
+    // Größe (zeilen, spalten) von op(x)
+    fn größe<T: Zahl>(self, x: &Matrix<T>) -> (usize, usize) {
+        match self {
+            Transponierung::Nein => (x.zeilen, x.spalten),
+            Transponierung::Ja => (x.spalten, x.zeilen),
+        }
+    }
+
+    // Größe die x haben muss, damit op(x) die Größe (zeilen, spalten) hat
+    fn gespeichert(self, zeilen: usize, spalten: usize) -> (usize, usize) {
+        match self {
+            Transponierung::Nein => (zeilen, spalten),
+            Transponierung::Ja => (spalten, zeilen),
+        }
+    }
+}
+
+/*
+    BLAS GEMM Schnittstelle: c = alpha * op(a) * op(b) + beta * c
+
+    op(a) ist eine m x k, op(b) eine k x n und c eine m x n Matrix. Die Multiplikation selbst wird mit einem
+    beliebigen registrierten Algorithmus durchgeführt, der das Produkt immer in eine eigene Matrix schreibt.
+    Transponierte Kopien werden nur erstellt wenn es nicht anders geht:
+
+    - op(a) * op(b) = a * b: direkt
+    - op(a) * op(b) = a^T * b^T = (b * a)^T: b * a berechnen und beim Aufsummieren transponiert lesen
+    - a^T * b oder a * b^T: der Algorithmus erwartet row-major Matrizen, daher wird eine Kopie transponiert
+
+    Bei beta = 0 wird c nicht gelesen, d.h. NaN oder Inf in c haben wie bei BLAS keinen Einfluss auf das Ergebnis.
+    Mit einstellungen.verifikation wird das Produkt vor dem Aufsummieren mit Freivalds geprüft
+*/
+pub fn gemm<T: Zahl>(
+    trans_a: Transponierung,
+    trans_b: Transponierung,
+    alpha: T,
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    beta: T,
+    c: &mut Matrix<T>,
+    algorithmus: &dyn Multiplikator<T>,
+    einstellungen: &Einstellungen,
+) -> Result<(), Fehler> {
+    // Dimensionen aus op(a) und op(b) bestimmen und prüfen
+    let (m, k): (usize, usize) = trans_a.größe(a);
+    let n: usize = trans_b.größe(b).1;
+
+    if trans_b.größe(b).0 != k {
+        return Err(Fehler::Dimension {
+            matrix: "b",
+            erwartet: trans_b.gespeichert(k, n),
+            tatsächlich: (b.zeilen, b.spalten),
+        });
+    }
+    if (c.zeilen, c.spalten) != (m, n) {
+        return Err(Fehler::Dimension {
+            matrix: "c",
+            erwartet: (m, n),
+            tatsächlich: (c.zeilen, c.spalten),
+        });
+    }
+
+    // das Produkt trägt nichts bei, c muss nur skaliert werden
+    if alpha == T::NULL || k == 0 {
+        skalieren(c, beta);
+        return Ok(());
+    }
+
+    match (trans_a, trans_b) {
+        (Transponierung::Nein, Transponierung::Nein) => {
+            // häufigster Fall ohne Zwischenspeicher
+            if alpha == T::EINS && beta == T::NULL {
+                return algorithmus.multiplizieren(a, b, c, m, k, n, einstellungen);
+            }
+            let mut produkt: Matrix<T> = Matrix::neu(m, n);
+            algorithmus.multiplizieren(a, b, &mut produkt, m, k, n, einstellungen)?;
+            aufsummieren(c, &produkt, alpha, beta, false);
+        }
+        (Transponierung::Ja, Transponierung::Ja) => {
+            // a^T * b^T = (b * a)^T, b ist n x k und a ist k x m
+            let mut produkt: Matrix<T> = Matrix::neu(n, m);
+            algorithmus.multiplizieren(b, a, &mut produkt, n, k, m, einstellungen)?;
+            aufsummieren(c, &produkt, alpha, beta, true);
+        }
+        (Transponierung::Ja, Transponierung::Nein) => {
+            let a_t: Matrix<T> = a.transponiert();
+            let mut produkt: Matrix<T> = Matrix::neu(m, n);
+            algorithmus.multiplizieren(&a_t, b, &mut produkt, m, k, n, einstellungen)?;
+            aufsummieren(c, &produkt, alpha, beta, false);
+        }
+        (Transponierung::Nein, Transponierung::Ja) => {
+            let b_t: Matrix<T> = b.transponiert();
+            let mut produkt: Matrix<T> = Matrix::neu(m, n);
+            algorithmus.multiplizieren(a, &b_t, &mut produkt, m, k, n, einstellungen)?;
+            aufsummieren(c, &produkt, alpha, beta, false);
+        }
+    }
+    Ok(())
+}
+
+/*
+    c = beta * c, bei beta = 0 wird c ohne lesen auf 0 gesetzt
+*/
+fn skalieren<T: Zahl>(c: &mut Matrix<T>, beta: T) {
+    for i in 0..c.zeilen {
+        let zeile: &mut [T] = c.zeile_mut(i);
+        if beta == T::NULL {
+            zeile.fill(T::NULL);
+        } else if beta != T::EINS {
+            for wert in zeile.iter_mut() {
+                *wert = beta * *wert;
+            }
+        }
+    }
+}
+
+/*
+    c = alpha * produkt + beta * c. Bei transponiert = true wird produkt^T verwendet
+*/
+fn aufsummieren<T: Zahl>(
+    c: &mut Matrix<T>,
+    produkt: &Matrix<T>,
+    alpha: T,
+    beta: T,
+    transponiert: bool,
+) {
+    for i in 0..c.zeilen {
+        let zeile: &mut [T] = c.zeile_mut(i);
+
+        for j in 0..zeile.len() {
+            let wert: T = if transponiert {
+                produkt[(j, i)]
+            } else {
+                produkt[(i, j)]
+            };
+
+            zeile[j] = if beta == T::NULL {
+                alpha * wert
+            } else {
+                alpha * wert + beta * zeile[j]
+            };
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use crate::fehler::Fehler;
+use crate::matrix::Matrix;
+use crate::numa::{self, Platzierung};
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
+use std::{f64::consts::TAU, fmt};
+
+/*
+    Art der Werte einer Zufallsmatrix
+
+    Standard: wie Zahl::zufall, Gleitkommazahlen gleichverteilt in [-1, 1), ganze Zahlen in [-10, 10]
+    Gleichverteilt: gleichverteilt in [von, bis)
+    Normal: normalverteilt mit mittelwert und standardabweichung
+    Ganzzahlig: ganze Zahlen gleichverteilt in [von, bis], auch für Gleitkommazahlen
+    Dünn: jeder Wert ist mit Wahrscheinlichkeit dichte ungleich 0 und dann gleichverteilt in [-1, 1)
+    Symmetrisch: gleichverteilt in [-1, 1) mit a[i][j] = a[j][i], nur für quadratische Matrizen
+    DiagonalDominant: gleichverteilt in [-1, 1), jeder Wert der Diagonale ist um 1 größer als die Summe der
+                      Beträge der anderen Werte seiner Zeile
+    Einheit: 1 auf der Diagonale, sonst 0
+    Orthogonal: orthonormale Spalten (bei zeilen >= spalten) oder Zeilen, mit Gram-Schmidt aus einer
+                normalverteilten Matrix
+    Konditioniert: U * S * V^T mit zufälligen orthogonalen U und V, die Singulärwerte fallen geometrisch von 1
+                   bis 1 / kondition. Eine kondition unter 1 wird wie 1 behandelt
+
+    Komplexe Zahlen bekommen einen zufälligen Real- und Imaginärteil, ganze Zahlen werden gerundet. Orthogonal
+    und Konditioniert werden in f64 berechnet und sind nur für Gleitkommazahlen sinnvoll. Sie werden mit O(n^3)
+    seriell auf dem aufrufenden Thread berechnet, nur das Schreiben der fertigen Werte wird verteilt
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Matrixart {
+    Standard,
+    Gleichverteilt {
+        von: f64,
+        bis: f64,
+    },
+    Normal {
+        mittelwert: f64,
+        standardabweichung: f64,
+    },
+    Ganzzahlig {
+        von: i64,
+        bis: i64,
+    },
+    Dünn {
+        dichte: f64,
+    },
+    Symmetrisch,
+    DiagonalDominant,
+    Einheit,
+    Orthogonal,
+    Konditioniert {
+        kondition: f64,
+    },
+}
+
+impl fmt::Display for Matrixart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Matrixart::Standard => write!(f, "standard"),
+            Matrixart::Gleichverteilt { von, bis } => write!(f, "gleichverteilt_{}_{}", von, bis),
+            Matrixart::Normal {
+                mittelwert,
+                standardabweichung,
+            } => write!(f, "normal_{}_{}", mittelwert, standardabweichung),
+            Matrixart::Ganzzahlig { von, bis } => write!(f, "ganzzahlig_{}_{}", von, bis),
+            Matrixart::Dünn { dichte } => write!(f, "dünn_{}", dichte),
+            Matrixart::Symmetrisch => write!(f, "symmetrisch"),
+            Matrixart::DiagonalDominant => write!(f, "diagonal_dominant"),
+            Matrixart::Einheit => write!(f, "einheit"),
+            Matrixart::Orthogonal => write!(f, "orthogonal"),
+            Matrixart::Konditioniert { kondition } => write!(f, "konditioniert_{}", kondition),
+        }
+    }
+}
+
+/*
+    reproduzierbare Zufallsmatrizen: jeder Wert hängt nur von seed, art und seiner Position (i, j) ab. Für einen
+    seed entsteht daher immer dieselbe Matrix, unabhängig von Rechner, Anzahl der Threads und Platzierung. So
+    kann ein fehlgeschlagener Test oder ein Benchmark mit genau denselben Eingaben wiederholt werden
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Generator {
+    pub seed: u64,
+    pub art: Matrixart,
+}
+
+// Zahlenfolge von SplitMix64, jede Position (i, j) bekommt ihre eigene Folge
+struct Strom(u64);
+
+const GOLDEN: u64 = 0x9E37_79B9_7F4A_7C15;
+
+fn mischen(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+impl Strom {
+    fn neu(seed: u64, i: usize, j: usize) -> Strom {
+        Strom(mischen(
+            mischen(seed ^ mischen(i as u64)).wrapping_add(j as u64),
+        ))
+    }
+
+    // gleichverteilt in [0, 1) mit 53 zufälligen Bits
+    fn gleich(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(GOLDEN);
+        (mischen(self.0) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn bereich(&mut self, von: f64, bis: f64) -> f64 {
+        von + (bis - von) * self.gleich()
+    }
+
+    // standardnormalverteilt mit Box-Muller
+    fn normal(&mut self) -> f64 {
+        let u: f64 = 1.0 - self.gleich();
+        (-2.0 * u.ln()).sqrt() * (TAU * self.gleich()).cos()
+    }
+
+    fn ganzzahl(&mut self, von: i64, bis: i64) -> f64 {
+        let breite: f64 = (bis as f64 - von as f64 + 1.0).max(1.0);
+        (von as f64 + (breite * self.gleich()).floor()).min(bis.max(von) as f64)
+    }
+}
+
+impl Generator {
+    pub fn neu(seed: u64, art: Matrixart) -> Generator {
+        Generator { seed, art }
+    }
+
+    /*
+        erzeugt eine zeilen x spalten Matrix auf dem aufrufenden Thread
+    */
+    pub fn matrix<T: Zahl>(&self, zeilen: usize, spalten: usize) -> Result<Matrix<T>, Fehler> {
+        self.matrix_parallel(zeilen, spalten, &[], Platzierung::Hauptthread)
+    }
+
+    /*
+        wie matrix, die Werte werden aber wie bei numa::matrix von den Threads auf pinnen nach platzierung
+        geschrieben. Das Ergebnis ist dasselbe wie bei matrix
+    */
+    pub fn matrix_parallel<T: Zahl>(
+        &self,
+        zeilen: usize,
+        spalten: usize,
+        pinnen: &[CoreId],
+        platzierung: Platzierung,
+    ) -> Result<Matrix<T>, Fehler> {
+        // Arten die nicht elementweise entstehen werden zuerst in f64 berechnet. Die Zwischenergebnisse sind nicht
+        // größer als die Matrix, ein Fehler beim Reservieren wird mit der verlangten Größe gemeldet
+        let zu_groß = |_: Fehler| Fehler::Größe { zeilen, spalten };
+        let vorberechnet: Option<Matrix<f64>> = match self.art {
+            Matrixart::Symmetrisch if zeilen != spalten => {
+                return Err(Fehler::Dimension {
+                    matrix: "symmetrisch",
+                    erwartet: (zeilen, zeilen),
+                    tatsächlich: (zeilen, spalten),
+                });
+            }
+            Matrixart::Orthogonal => Some(orthogonal(self.seed, zeilen, spalten).map_err(zu_groß)?),
+            Matrixart::Konditioniert { kondition } => {
+                Some(konditioniert(self.seed, zeilen, spalten, kondition).map_err(zu_groß)?)
+            }
+            _ => None,
+        };
+
+        numa::matrix(
+            zeilen,
+            spalten,
+            pinnen,
+            platzierung,
+            |i, j| match &vorberechnet {
+                Some(werte) => T::aus_f64(werte[(i, j)]),
+                None => self.wert(i, j, spalten),
+            },
+        )
+    }
+
+    // Wert an der Stelle (i, j) für alle elementweisen Arten
+    fn wert<T: Zahl>(&self, i: usize, j: usize, spalten: usize) -> T {
+        let mut strom: Strom = Strom::neu(self.seed, i, j);
+
+        match self.art {
+            Matrixart::Standard if T::EPSILON == 0.0 => {
+                T::aus_komponenten(|| strom.ganzzahl(-10, 10))
+            }
+            Matrixart::Standard => T::aus_komponenten(|| strom.bereich(-1.0, 1.0)),
+            Matrixart::Gleichverteilt { von, bis } => {
+                T::aus_komponenten(|| strom.bereich(von, bis))
+            }
+            Matrixart::Normal {
+                mittelwert,
+                standardabweichung,
+            } => T::aus_komponenten(|| mittelwert + standardabweichung * strom.normal()),
+            Matrixart::Ganzzahlig { von, bis } => T::aus_komponenten(|| strom.ganzzahl(von, bis)),
+            Matrixart::Dünn { dichte } => {
+                if strom.gleich() < dichte {
+                    T::aus_komponenten(|| strom.bereich(-1.0, 1.0))
+                } else {
+                    T::NULL
+                }
+            }
+            Matrixart::Symmetrisch => {
+                let mut strom: Strom = Strom::neu(self.seed, i.min(j), i.max(j));
+                T::aus_komponenten(|| strom.bereich(-1.0, 1.0))
+            }
+            Matrixart::DiagonalDominant if i == j => {
+                let summe: f64 = (0..spalten)
+                    .filter(|&x| x != i)
+                    .map(|x| self.wert::<T>(i, x, spalten).abstand(T::NULL))
+                    .sum();
+                T::aus_f64(summe + 1.0)
+            }
+            Matrixart::DiagonalDominant => T::aus_komponenten(|| strom.bereich(-1.0, 1.0)),
+            Matrixart::Einheit if i == j => T::EINS,
+            Matrixart::Einheit => T::NULL,
+            Matrixart::Orthogonal | Matrixart::Konditioniert { .. } => {
+                unreachable!("wird vorberechnet")
+            }
+        }
+    }
+}
+
+/*
+    zeilen x spalten Matrix mit orthonormalen Spalten (zeilen >= spalten) bzw. Zeilen (zeilen < spalten). Die
+    Spalten einer normalverteilten Matrix werden mit Gram-Schmidt orthonormalisiert, zweimal für die Genauigkeit
+*/
+fn orthogonal(seed: u64, zeilen: usize, spalten: usize) -> Result<Matrix<f64>, Fehler> {
+    if zeilen < spalten {
+        return transponieren(&orthogonal(seed, spalten, zeilen)?);
+    }
+
+    // die Spalten als Zeilen von q, damit jeder Vektor zusammenhängend ist
+    let normal: Generator = Generator::neu(
+        seed,
+        Matrixart::Normal {
+            mittelwert: 0.0,
+            standardabweichung: 1.0,
+        },
+    );
+    let mut q: Matrix<f64> = nullen(spalten, zeilen)?;
+    for c in 0..spalten {
+        for x in 0..zeilen {
+            q[(c, x)] = normal.wert(x, c, spalten);
+        }
+    }
+
+    for c in 0..spalten {
+        for _ in 0..2 {
+            for p in 0..c {
+                let projektion: f64 = (0..zeilen).map(|x| q[(c, x)] * q[(p, x)]).sum();
+                for x in 0..zeilen {
+                    q[(c, x)] -= projektion * q[(p, x)];
+                }
+            }
+        }
+        let länge: f64 = q.zeile(c).iter().map(|x| x * x).sum::<f64>().sqrt();
+        for wert in q.zeile_mut(c) {
+            *wert /= länge;
+        }
+    }
+    transponieren(&q)
+}
+
+/*
+    U * S * V^T mit U (zeilen x r) und V (spalten x r) aus orthogonal, r = min(zeilen, spalten) und den
+    Singulärwerten kondition^(-t / (r - 1)) für t = 0..r
+*/
+fn konditioniert(
+    seed: u64,
+    zeilen: usize,
+    spalten: usize,
+    kondition: f64,
+) -> Result<Matrix<f64>, Fehler> {
+    // zuerst das Ergebnis, u und v sind nicht größer
+    let mut ergebnis: Matrix<f64> = nullen(zeilen, spalten)?;
+    let r: usize = zeilen.min(spalten);
+    let u: Matrix<f64> = orthogonal(mischen(seed ^ 1), zeilen, r)?;
+    let v: Matrix<f64> = orthogonal(mischen(seed ^ 2), spalten, r)?;
+    let singulärwerte: Vec<f64> = (0..r)
+        .map(|t| kondition.max(1.0).powf(-(t as f64) / (r.max(2) - 1) as f64))
+        .collect();
+
+    for i in 0..zeilen {
+        for j in 0..spalten {
+            ergebnis[(i, j)] = (0..r)
+                .map(|t| u[(i, t)] * singulärwerte[t] * v[(j, t)])
+                .sum();
+        }
+    }
+    Ok(ergebnis)
+}
+
+// Matrix aus Nullen, zu große Matrizen sind wie bei numa::matrix ein Fehler statt eines Abbruchs
+fn nullen(zeilen: usize, spalten: usize) -> Result<Matrix<f64>, Fehler> {
+    Matrix::reservieren(zeilen, spalten).ok_or(Fehler::Größe { zeilen, spalten })
+}
+
+// wie Matrix::transponiert mit geprüftem Speicher
+fn transponieren(matrix: &Matrix<f64>) -> Result<Matrix<f64>, Fehler> {
+    let mut ergebnis: Matrix<f64> = nullen(matrix.spalten, matrix.zeilen)?;
+    for i in 0..matrix.zeilen {
+        for (j, &wert) in matrix.zeile(i).iter().enumerate() {
+            ergebnis[(j, i)] = wert;
+        }
+    }
+    Ok(ergebnis)
+}
@@ -0,0 +1,690 @@
+use crate::auswertung;
+use crate::cpu;
+use crate::fehler::{self, Fehler};
+use crate::generator::{Generator, Matrixart};
+use crate::matrix::Matrix;
+use crate::multiplikator::{Einstellungen, Multiplikator, alle, finden};
+use crate::numa::{self, Platzierung};
+use crate::regression::{self, Eintrag};
+use crate::topologie::{self, Strategie};
+use crate::verifikation;
+use crate::zaehler::{self, Ereignis, Erfassung, Werte};
+use core_affinity::CoreId;
+use std::{
+    fmt,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+pub const HILFE: &str = "\
+Matrixmultiplikation mit den Algorithmen aus dem Register messen
+
+Aufruf: multiplikation [Optionen]
+
+Optionen:
+  -a, --algorithmus NAME[,NAME]  Algorithmen aus dem Register, ohne Angabe alle (siehe --liste)
+  -g, --größe N | MxKxN[,...]    Größen der Multiplikation, N steht für NxNxN (Standard: 512)
+  -t, --threads N                Threads der parallelen Algorithmen (Standard: alle Kerne)
+  -p, --pinnen STRATEGIE         aufzählung, physisch_zuerst, nur_leistung, nur_effizienz oder je_l2
+                                 (Standard: physisch_zuerst)
+      --datei-a DATEI            a aus einer Datei laden (Matrix Market, .npy oder Rohformat)
+      --datei-b DATEI            b aus einer Datei laden, nur zusammen mit --datei-a
+  -s, --seed N                   Startwert der Zufallsmatrizen (Standard: zufällig, wird ausgegeben)
+  -w, --wiederholungen N         Messungen je Algorithmus und Größe (Standard: 3)
+  -f, --format FORMAT            tabelle, csv oder json (Standard: tabelle)
+      --prüfen N                 Wiederholungen von Freivalds zur Prüfung des Ergebnisses, 0 prüft nicht
+                                 (Standard: 10)
+      --ergebnisse DATEI         Datei der gespeicherten Ergebnisse (Standard: ergebnisse.tsv)
+      --speichern NAME           Messungen als Basislinie NAME in den Ergebnissen speichern
+      --vergleichen NAME         Messungen mit der Basislinie NAME vergleichen, der Bericht wird auf stderr
+                                 ausgegeben. Bei einer Regression ist der Exit Code 3
+      --schwelle PROZENT         ab dieser Verlangsamung ist ein signifikanter Unterschied eine Regression
+                                 (Standard: 5)
+      --signifikanz ALPHA        Signifikanzniveau des t-Tests nach Welch (Standard: 0.05)
+      --zähler                   Hardware Zähler (Zyklen, Instruktionen, L1 und LLC Fehlzugriffe, falsch
+                                 vorhergesagte Sprünge) je Thread mit perf_event_open erfassen. Threads
+                                 von rayon, crossbeam, strassen und winograd werden nicht gezählt, deren
+                                 Summe wird als unvollständig ausgegeben
+  -l, --liste                    registrierte Algorithmen ausgeben
+  -h, --hilfe                    diese Hilfe ausgeben
+";
+
+/*
+    Format der Ausgabe der Messungen
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ausgabe {
+    Tabelle,
+    Csv,
+    Json,
+}
+
+/*
+    Optionen der Kommandozeile, siehe HILFE. algorithmen ist leer wenn alle Algorithmen gemessen werden sollen,
+    threads None für alle Kerne. schwelle ist ein Anteil, z.B. 0.05 für --schwelle 5
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Optionen {
+    pub algorithmen: Vec<String>,
+    pub größen: Vec<(usize, usize, usize)>,
+    pub threads: Option<usize>,
+    pub pinnen: Strategie,
+    pub datei_a: Option<PathBuf>,
+    pub datei_b: Option<PathBuf>,
+    pub seed: Option<u64>,
+    pub wiederholungen: usize,
+    pub ausgabe: Ausgabe,
+    pub prüfen: usize,
+    pub ergebnisse: PathBuf,
+    pub speichern: Option<String>,
+    pub vergleichen: Option<String>,
+    pub schwelle: f64,
+    pub signifikanz: f64,
+    pub zähler: bool,
+    pub liste: bool,
+    pub hilfe: bool,
+}
+
+impl Default for Optionen {
+    fn default() -> Optionen {
+        Optionen {
+            algorithmen: Vec::new(),
+            größen: vec![(512, 512, 512)],
+            threads: None,
+            pinnen: Strategie::PhysischZuerst,
+            datei_a: None,
+            datei_b: None,
+            seed: None,
+            wiederholungen: 3,
+            ausgabe: Ausgabe::Tabelle,
+            prüfen: 10,
+            ergebnisse: PathBuf::from(regression::DATEI),
+            speichern: None,
+            vergleichen: None,
+            schwelle: 0.05,
+            signifikanz: 0.05,
+            zähler: false,
+            liste: false,
+            hilfe: false,
+        }
+    }
+}
+
+fn argumentfehler(grund: String) -> Fehler {
+    Fehler::Argument(grund)
+}
+
+fn zahl_lesen<Z: std::str::FromStr>(option: &str, wert: &str) -> Result<Z, Fehler> {
+    wert.parse()
+        .map_err(|_| argumentfehler(format!("{} erwartet eine Zahl, nicht {}", option, wert)))
+}
+
+// "N" für N x N x N oder "MxKxN"
+fn größe_lesen(wert: &str) -> Result<(usize, usize, usize), Fehler> {
+    let teile: Vec<usize> = wert
+        .split('x')
+        .map(|teil| zahl_lesen("--größe", teil))
+        .collect::<Result<_, _>>()?;
+    match teile.as_slice() {
+        [n] => Ok((*n, *n, *n)),
+        [m, k, n] => Ok((*m, *k, *n)),
+        _ => Err(argumentfehler(format!(
+            "--größe erwartet N oder MxKxN, nicht {}",
+            wert
+        ))),
+    }
+}
+
+impl Optionen {
+    /*
+        liest die Argumente ohne den Programmnamen. Werte können als "--option wert" oder "--option=wert"
+        angegeben werden, Listen durch Kommas getrennt oder mit mehrfach angegebenen Optionen
+    */
+    pub fn lesen(argumente: &[String]) -> Result<Optionen, Fehler> {
+        let mut optionen: Optionen = Optionen::default();
+        let mut größen: Vec<(usize, usize, usize)> = Vec::new();
+        let mut argumente = argumente.iter();
+
+        while let Some(argument) = argumente.next() {
+            let (option, eingebettet): (&str, Option<&str>) = match argument.split_once('=') {
+                Some((option, wert)) if option.starts_with("--") => (option, Some(wert)),
+                _ => (argument.as_str(), None),
+            };
+            let mut wert = || -> Result<String, Fehler> {
+                eingebettet
+                    .map(str::to_string)
+                    .or_else(|| argumente.next().cloned())
+                    .ok_or_else(|| argumentfehler(format!("{} erwartet einen Wert", option)))
+            };
+
+            match option {
+                "-h" | "--hilfe" | "--help" => optionen.hilfe = true,
+                "-l" | "--liste" => optionen.liste = true,
+                "--zähler" | "--zaehler" => optionen.zähler = true,
+                "-a" | "--algorithmus" => {
+                    for name in wert()?.split(',').filter(|name| !name.is_empty()) {
+                        if finden::<f64>(name).is_none() {
+                            return Err(argumentfehler(format!(
+                                "unbekannter Algorithmus {}, verfügbar: {}",
+                                name,
+                                alle::<f64>()
+                                    .iter()
+                                    .map(|a| a.name())
+                                    .collect::<Vec<&str>>()
+                                    .join(", ")
+                            )));
+                        }
+                        optionen.algorithmen.push(name.to_string());
+                    }
+                }
+                "-g" | "--größe" | "--groesse" => {
+                    for teil in wert()?.split(',').filter(|teil| !teil.is_empty()) {
+                        größen.push(größe_lesen(teil)?);
+                    }
+                }
+                "-t" | "--threads" => optionen.threads = Some(zahl_lesen(option, &wert()?)?),
+                "-p" | "--pinnen" => {
+                    let name: String = wert()?;
+                    optionen.pinnen = Strategie::aus_name(&name).ok_or_else(|| {
+                        argumentfehler(format!("unbekannte Strategie zum pinnen {}", name))
+                    })?;
+                }
+                "--datei-a" => optionen.datei_a = Some(PathBuf::from(wert()?)),
+                "--datei-b" => optionen.datei_b = Some(PathBuf::from(wert()?)),
+                "-s" | "--seed" => optionen.seed = Some(zahl_lesen(option, &wert()?)?),
+                "-w" | "--wiederholungen" => {
+                    optionen.wiederholungen = zahl_lesen(option, &wert()?)?;
+                }
+                "-f" | "--format" => {
+                    optionen.ausgabe = match wert()?.as_str() {
+                        "tabelle" => Ausgabe::Tabelle,
+                        "csv" => Ausgabe::Csv,
+                        "json" => Ausgabe::Json,
+                        anderes => {
+                            return Err(argumentfehler(format!("unbekanntes Format {}", anderes)));
+                        }
+                    };
+                }
+                "--prüfen" | "--pruefen" => optionen.prüfen = zahl_lesen(option, &wert()?)?,
+                "--ergebnisse" => optionen.ergebnisse = PathBuf::from(wert()?),
+                "--speichern" => optionen.speichern = Some(wert()?),
+                "--vergleichen" => optionen.vergleichen = Some(wert()?),
+                "--schwelle" => {
+                    let prozent: f64 = zahl_lesen(option, &wert()?)?;
+                    if !(prozent >= 0.0 && prozent.is_finite()) {
+                        return Err(argumentfehler(format!(
+                            "--schwelle muss mindestens 0 sein, nicht {}",
+                            prozent
+                        )));
+                    }
+                    optionen.schwelle = prozent / 100.0;
+                }
+                "--signifikanz" => {
+                    optionen.signifikanz = zahl_lesen(option, &wert()?)?;
+                    if !(optionen.signifikanz > 0.0 && optionen.signifikanz < 1.0) {
+                        return Err(argumentfehler(format!(
+                            "--signifikanz muss zwischen 0 und 1 liegen, nicht {}",
+                            optionen.signifikanz
+                        )));
+                    }
+                }
+                _ => return Err(argumentfehler(format!("unbekannte Option {}", argument))),
+            }
+        }
+
+        if !größen.is_empty() {
+            optionen.größen = größen;
+        }
+        if optionen.wiederholungen == 0 {
+            return Err(argumentfehler(
+                "--wiederholungen muss mindestens 1 sein".to_string(),
+            ));
+        }
+        if optionen.datei_b.is_some() != optionen.datei_a.is_some() {
+            return Err(argumentfehler(
+                "--datei-a und --datei-b müssen zusammen angegeben werden".to_string(),
+            ));
+        }
+        Ok(optionen)
+    }
+}
+
+/*
+    Ergebnis der Prüfung mit Freivalds
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prüfung {
+    Aus,
+    Richtig,
+    Falsch,
+}
+
+impl fmt::Display for Prüfung {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Prüfung::Aus => write!(f, "aus"),
+            Prüfung::Richtig => write!(f, "richtig"),
+            Prüfung::Falsch => write!(f, "falsch"),
+        }
+    }
+}
+
+/*
+    Messung eines Algorithmus für eine Größe. seed ist None wenn die Matrizen aus Dateien geladen wurden, zeiten
+    enthält alle Wiederholungen. zähler sind die Hardware Zähler der schnellsten Wiederholung, None ohne --zähler
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Messung {
+    pub algorithmus: &'static str,
+    pub m: usize,
+    pub k: usize,
+    pub n: usize,
+    pub threads: usize,
+    pub seed: Option<u64>,
+    pub beste: Duration,
+    pub mittel: Duration,
+    pub zeiten: Vec<Duration>,
+    pub prüfung: Prüfung,
+    pub zähler: Option<Erfassung>,
+}
+
+impl Messung {
+    // GFLOP/s in der besten Zeit
+    pub fn gflops(&self) -> f64 {
+        auswertung::gflops(self.m, self.k, self.n, self.beste)
+    }
+}
+
+/*
+    misst alle gewählten Algorithmen für alle Größen bzw. für die Matrizen aus den Dateien. Die Matrizen werden
+    mit dem Generator aus seed (a) und seed + 1 (b) auf den gepinnten Threads angelegt, erst wenn ihre Größe
+    gemessen wird. So liegt immer nur ein Paar im Speicher
+*/
+pub fn messen(optionen: &Optionen) -> Result<Vec<Messung>, Fehler> {
+    let kerne: Vec<CoreId> = topologie::kerne(optionen.pinnen).or_else(|_| fehler::kerne())?;
+    let threads: usize = optionen.threads.unwrap_or(kerne.len());
+    fehler::threads_prüfen(threads, &kerne)?;
+
+    let algorithmen: Vec<&'static dyn Multiplikator> = if optionen.algorithmen.is_empty() {
+        alle()
+    } else {
+        optionen
+            .algorithmen
+            .iter()
+            .filter_map(|name| finden(name))
+            .collect()
+    };
+
+    // mit Dateien gibt es genau eine Eingabe, sonst eine je Größe
+    let dateien: Option<(&PathBuf, &PathBuf)> =
+        optionen.datei_a.as_ref().zip(optionen.datei_b.as_ref());
+    let seed: Option<u64> = match dateien {
+        Some(_) => None,
+        None => Some(optionen.seed.unwrap_or_else(rand::random)),
+    };
+    let eingaben: usize = if dateien.is_some() {
+        1
+    } else {
+        optionen.größen.len()
+    };
+
+    // Eingaben und Ergebnis werden von denselben gepinnten Threads zuerst berührt
+    let pinnen: &[CoreId] = &kerne[..threads];
+
+    let mut messungen: Vec<Messung> = Vec::new();
+    for eingabe in 0..eingaben {
+        let (a, b): (Matrix, Matrix) = match (dateien, seed) {
+            (Some((a, b)), _) => (Matrix::laden(a)?, Matrix::laden(b)?),
+            (None, seed) => {
+                let seed: u64 = seed.unwrap_or_default();
+                let (m, k, n): (usize, usize, usize) = optionen.größen[eingabe];
+                let a: Matrix = Generator::neu(seed, Matrixart::Standard).matrix_parallel(
+                    m,
+                    k,
+                    pinnen,
+                    Platzierung::ErsteBerührung,
+                )?;
+                let b: Matrix = Generator::neu(seed.wrapping_add(1), Matrixart::Standard)
+                    .matrix_parallel(k, n, pinnen, Platzierung::ErsteBerührung)?;
+                (a, b)
+            }
+        };
+        let (a, b): (&Matrix, &Matrix) = (&a, &b);
+        let (m, k, n): (usize, usize, usize) = (a.zeilen, a.spalten, b.spalten);
+
+        for &algorithmus in &algorithmen {
+            let threads: usize = if algorithmus.fähigkeiten().parallel {
+                threads
+            } else {
+                1
+            };
+            let einstellungen: Einstellungen = Einstellungen::neu(threads, kerne.clone());
+            let mut c: Matrix =
+                numa::matrix(m, n, pinnen, Platzierung::ErsteBerührung, |_, _| 0.0)?;
+
+            let mut ausführen = || -> Result<Duration, Fehler> {
+                let start: Instant = Instant::now();
+                algorithmus.ausführen(a, b, &mut c, m, k, n, &einstellungen)?;
+                Ok(start.elapsed())
+            };
+
+            let mut zeiten: Vec<Duration> = Vec::with_capacity(optionen.wiederholungen);
+            let mut zähler: Option<Erfassung> = None;
+            for _ in 0..optionen.wiederholungen {
+                let (zeit, erfassung): (Duration, Option<Erfassung>) = if optionen.zähler {
+                    let (zeit, erfassung) = zaehler::messen(&mut ausführen);
+                    (zeit?, Some(erfassung))
+                } else {
+                    (ausführen()?, None)
+                };
+                if zeiten.iter().all(|&schneller| zeit < schneller) {
+                    zähler = erfassung;
+                }
+                zeiten.push(zeit);
+            }
+
+            let prüfung: Prüfung = if optionen.prüfen == 0 {
+                Prüfung::Aus
+            } else {
+                match verifikation::freivalds(a, b, &c, m, k, n, optionen.prüfen) {
+                    Ok(()) => Prüfung::Richtig,
+                    Err(Fehler::Verifikation { .. }) => Prüfung::Falsch,
+                    Err(f) => return Err(f),
+                }
+            };
+
+            messungen.push(Messung {
+                algorithmus: algorithmus.name(),
+                m,
+                k,
+                n,
+                threads,
+                seed,
+                beste: zeiten.iter().copied().min().unwrap_or_default(),
+                mittel: zeiten.iter().sum::<Duration>() / zeiten.len() as u32,
+                zeiten,
+                prüfung,
+                zähler,
+            });
+        }
+    }
+    Ok(messungen)
+}
+
+fn millisekunden(zeit: Duration) -> f64 {
+    zeit.as_secs_f64() * 1e3
+}
+
+/*
+    Messungen als Tabelle für das Terminal, als CSV mit Kopfzeile oder als JSON Array mit einem Objekt je Messung.
+    Mit Hardware Zählern folgt in der Tabelle eine zweite Tabelle mit einer Zeile je Thread, die CSV bekommt die
+    Summen aller Threads als weitere Spalten und jedes JSON Objekt ein Objekt zaehler. Ist eine Erfassung
+    unvollständig, bleibt die Summe leer und vollstaendig ist false
+*/
+pub fn ausgeben(messungen: &[Messung], ausgabe: Ausgabe) -> String {
+    let mut text: String = String::new();
+    let zähler: bool = messungen.iter().any(|messung| messung.zähler.is_some());
+
+    match ausgabe {
+        Ausgabe::Tabelle => {
+            let breite: usize = messungen
+                .iter()
+                .map(|messung| messung.algorithmus.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max("algorithmus".len());
+            if let Some(seed) = messungen.first().and_then(|messung| messung.seed) {
+                text += &format!("seed: {}\n", seed);
+            }
+            text += &format!(
+                "{:<breite$} {:>6} {:>6} {:>6} {:>7} {:>12} {:>12} {:>9} {:>8}\n",
+                "algorithmus",
+                "m",
+                "k",
+                "n",
+                "threads",
+                "beste [ms]",
+                "mittel [ms]",
+                "GFLOP/s",
+                "prüfung"
+            );
+            for messung in messungen {
+                text += &format!(
+                    "{:<breite$} {:>6} {:>6} {:>6} {:>7} {:>12.3} {:>12.3} {:>9.2} {:>8}\n",
+                    messung.algorithmus,
+                    messung.m,
+                    messung.k,
+                    messung.n,
+                    messung.threads,
+                    millisekunden(messung.beste),
+                    millisekunden(messung.mittel),
+                    messung.gflops(),
+                    messung.prüfung
+                );
+            }
+            if zähler {
+                text += &zähler_tabelle(messungen, breite);
+            }
+        }
+        Ausgabe::Csv => {
+            text += "algorithmus,m,k,n,threads,seed,beste_ms,mittel_ms,gflops,prüfung";
+            if zähler {
+                for ereignis in Ereignis::ALLE {
+                    text += &format!(",{}", ereignis);
+                }
+                text += ",ipc,vollstaendig";
+            }
+            text += "\n";
+            for messung in messungen {
+                text += &format!(
+                    "{},{},{},{},{},{},{},{},{},{}",
+                    messung.algorithmus,
+                    messung.m,
+                    messung.k,
+                    messung.n,
+                    messung.threads,
+                    messung.seed.map_or(String::new(), |seed| seed.to_string()),
+                    millisekunden(messung.beste),
+                    millisekunden(messung.mittel),
+                    messung.gflops(),
+                    messung.prüfung
+                );
+                if zähler {
+                    let summe: Werte = messung
+                        .zähler
+                        .as_ref()
+                        .filter(|erfassung| !erfassung.unvollständig)
+                        .map_or_else(Werte::default, Erfassung::summe);
+                    for ereignis in Ereignis::ALLE {
+                        text += &format!(",{}", zähler_text(summe.wert(ereignis), ""));
+                    }
+                    text += &format!(
+                        ",{}",
+                        summe.ipc().map_or(String::new(), |ipc| ipc.to_string())
+                    );
+                    text += &format!(
+                        ",{}",
+                        messung
+                            .zähler
+                            .as_ref()
+                            .is_some_and(|erfassung| !erfassung.unvollständig)
+                    );
+                }
+                text += "\n";
+            }
+        }
+        Ausgabe::Json => {
+            let objekte: Vec<String> = messungen
+                .iter()
+                .map(|messung| {
+                    format!(
+                        "  {{\"algorithmus\": \"{}\", \"m\": {}, \"k\": {}, \"n\": {}, \"threads\": {}, \
+                         \"seed\": {}, \"beste_ms\": {}, \"mittel_ms\": {}, \"gflops\": {}, \"prüfung\": \"{}\"{}}}",
+                        messung.algorithmus,
+                        messung.m,
+                        messung.k,
+                        messung.n,
+                        messung.threads,
+                        messung.seed.map_or("null".to_string(), |seed| seed.to_string()),
+                        json_zahl(millisekunden(messung.beste)),
+                        json_zahl(millisekunden(messung.mittel)),
+                        json_zahl(messung.gflops()),
+                        messung.prüfung,
+                        messung.zähler.as_ref().map_or(String::new(), |erfassung| format!(
+                            ", \"zaehler\": {}",
+                            zähler_json(erfassung)
+                        ))
+                    )
+                })
+                .collect();
+            text += &format!("[\n{}\n]\n", objekte.join(",\n"));
+        }
+    }
+    text
+}
+
+// Zählerstand oder leer für ein nicht gezähltes Ereignis
+fn zähler_text(wert: Option<u64>, leer: &str) -> String {
+    wert.map_or(leer.to_string(), |wert| wert.to_string())
+}
+
+/*
+    Zähler je Messung mit einer Zeile für den Aufrufer, je Thread von rechenpool::verteilen und ihrer Summe.
+    Bei einer unvollständigen Erfassung steht statt der Summe eine leere Zeile unvollständig
+*/
+fn zähler_tabelle(messungen: &[Messung], breite: usize) -> String {
+    let mut text: String = format!(
+        "\nHardware Zähler der schnellsten Wiederholung:\n{:<breite$} {:>6} {:>6} {:>6} {:>8}",
+        "algorithmus", "m", "k", "n", "thread"
+    );
+    for ereignis in Ereignis::ALLE {
+        text += &format!(" {:>16}", ereignis.name());
+    }
+    text += &format!(" {:>6}\n", "ipc");
+
+    for messung in messungen {
+        let Some(erfassung) = &messung.zähler else {
+            continue;
+        };
+        let mut zeilen: Vec<(String, Werte)> = vec![("aufrufer".to_string(), erfassung.aufrufer)];
+        zeilen.extend(
+            erfassung
+                .threads
+                .iter()
+                .enumerate()
+                .map(|(z, werte)| (z.to_string(), *werte)),
+        );
+        // ohne alle Threads gibt es keine Summe
+        if erfassung.unvollständig {
+            zeilen.push(("unvollständig".to_string(), Werte::default()));
+        } else if !erfassung.threads.is_empty() {
+            zeilen.push(("summe".to_string(), erfassung.summe()));
+        }
+
+        for (thread, werte) in zeilen {
+            text += &format!(
+                "{:<breite$} {:>6} {:>6} {:>6} {:>8}",
+                messung.algorithmus, messung.m, messung.k, messung.n, thread
+            );
+            for ereignis in Ereignis::ALLE {
+                text += &format!(" {:>16}", zähler_text(werte.wert(ereignis), "-"));
+            }
+            text += &format!(
+                " {:>6}\n",
+                werte
+                    .ipc()
+                    .map_or("-".to_string(), |ipc| format!("{:.2}", ipc))
+            );
+        }
+    }
+    text
+}
+
+// Objekt mit den Zählern des Aufrufers, je Thread, der Summe (null wenn unvollständig) und dem Fehler
+fn zähler_json(erfassung: &Erfassung) -> String {
+    let werte = |werte: &Werte| -> String {
+        let felder: Vec<String> = Ereignis::ALLE
+            .iter()
+            .map(|&ereignis| {
+                format!(
+                    "\"{}\": {}",
+                    ereignis,
+                    zähler_text(werte.wert(ereignis), "null")
+                )
+            })
+            .collect();
+        format!("{{{}}}", felder.join(", "))
+    };
+    format!(
+        "{{\"aufrufer\": {}, \"threads\": [{}], \"summe\": {}, \"vollstaendig\": {}, \"fehler\": {}}}",
+        werte(&erfassung.aufrufer),
+        erfassung
+            .threads
+            .iter()
+            .map(werte)
+            .collect::<Vec<String>>()
+            .join(", "),
+        if erfassung.unvollständig {
+            "null".to_string()
+        } else {
+            werte(&erfassung.summe())
+        },
+        !erfassung.unvollständig,
+        erfassung
+            .fehler
+            .as_ref()
+            .map_or("null".to_string(), |f| format!(
+                "\"{}\"",
+                f.to_string().replace('\\', "\\\\").replace('"', "\\\"")
+            ))
+    )
+}
+
+// JSON kennt weder inf noch NaN, z.B. GFLOP/s bei einer Zeit von 0
+fn json_zahl(wert: f64) -> String {
+    if wert.is_finite() {
+        wert.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/*
+    Liste der registrierten Algorithmen für --liste
+*/
+pub fn liste() -> String {
+    alle::<f64>()
+        .iter()
+        .map(|algorithmus| {
+            format!(
+                "{:<16} {}\n",
+                algorithmus.name(),
+                algorithmus.beschreibung()
+            )
+        })
+        .collect()
+}
+
+/*
+    Messungen als Einträge der Ergebnisdatei unter dem Namen basislinie, mit dem Prozessormodell und dem
+    aktuellen Commit
+*/
+pub fn einträge(messungen: &[Messung], basislinie: &str) -> Vec<Eintrag> {
+    let prozessor: String = cpu::modell();
+    let commit: String = regression::commit();
+
+    messungen
+        .iter()
+        .map(|messung| Eintrag {
+            basislinie: basislinie.to_string(),
+            algorithmus: messung.algorithmus.to_string(),
+            m: messung.m,
+            k: messung.k,
+            n: messung.n,
+            threads: messung.threads,
+            prozessor: prozessor.clone(),
+            commit: commit.clone(),
+            zeiten: messung.zeiten.clone(),
+        })
+        .collect()
+}
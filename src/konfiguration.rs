@@ -0,0 +1,333 @@
+use crate::fehler::Fehler;
+use crate::multiplikator::finden;
+use crate::topologie::Strategie;
+use std::{env, fmt, fs, time::Duration};
+
+// Umgebungsvariable mit dem Pfad einer Konfigurationsdatei
+pub const DATEI: &str = "MULTIPLIKATION_BENCH_DATEI";
+
+// Präfix der Umgebungsvariablen, die einzelne Schlüssel setzen, z.B. MULTIPLIKATION_BENCH_PROFIL=schnell
+pub const PRÄFIX: &str = "MULTIPLIKATION_BENCH_";
+
+// alle Schlüssel der Konfiguration, nur ASCII damit sie auch als Umgebungsvariablen funktionieren
+pub const SCHLÜSSEL: [&str; 10] = [
+    "profil",
+    "algorithmen",
+    "groessen",
+    "kleine_groessen",
+    "verteilung_groessen",
+    "threads",
+    "pinnen",
+    "anzahl",
+    "zeit",
+    "aufwaermen",
+];
+
+/*
+    Anzahlen an Threads, mit denen parallele Algorithmen gemessen werden. Sequentielle Algorithmen laufen immer
+    mit einem Thread
+
+    Alle: 2 bis alle Kerne
+    Maximum: nur mit allen Kernen
+    Liste: die angegebenen Anzahlen, größere als die Anzahl der Kerne werden übersprungen
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Threadauswahl {
+    Alle,
+    Maximum,
+    Liste(Vec<usize>),
+}
+
+impl Threadauswahl {
+    pub fn threads(&self, kerne: usize) -> Vec<usize> {
+        match self {
+            Threadauswahl::Alle => (2..=kerne).collect(),
+            Threadauswahl::Maximum => vec![kerne],
+            Threadauswahl::Liste(threads) => threads
+                .iter()
+                .copied()
+                .filter(|&thread| thread <= kerne)
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Threadauswahl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Threadauswahl::Alle => write!(f, "alle"),
+            Threadauswahl::Maximum => write!(f, "maximum"),
+            Threadauswahl::Liste(threads) => write!(f, "{}", liste(threads)),
+        }
+    }
+}
+
+/*
+    Konfiguration der Benchmarks
+
+    algorithmen: Namen aus dem Register, leer für alle
+    größen: Matrixgrößen der Benchmarks aller Algorithmen
+    kleine_größen: Matrixgrößen für den Vergleich von Spawnen und Rechenpool
+    verteilung_größen: Matrixgrößen für den Vergleich der Verteilungen
+    anzahl: Anzahl der Messungen je Benchmark, Criterion verlangt mindestens 10
+    zeit: Zeit für die Messungen je Benchmark
+    aufwärmen: Zeit zum Aufwärmen vor den Messungen
+
+    Eine leere Liste von Größen überspringt die Benchmarks dieser Gruppe
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Konfiguration {
+    pub algorithmen: Vec<String>,
+    pub größen: Vec<usize>,
+    pub kleine_größen: Vec<usize>,
+    pub verteilung_größen: Vec<usize>,
+    pub threads: Threadauswahl,
+    pub pinnen: Strategie,
+    pub anzahl: usize,
+    pub zeit: Duration,
+    pub aufwärmen: Duration,
+}
+
+impl Konfiguration {
+    /*
+        alle Algorithmen mit allen Größen und Anzahlen an Threads, auf großen Rechnern dauert das sehr lange
+    */
+    pub fn voll() -> Konfiguration {
+        Konfiguration {
+            algorithmen: Vec::new(),
+            größen: vec![
+                4, 8, 11, 16, 25, 32, 64, 94, 128, 256, 357, 512, 787, 1024, 1667,
+            ],
+            kleine_größen: vec![4, 8, 11, 16],
+            verteilung_größen: vec![94, 357, 1024],
+            threads: Threadauswahl::Alle,
+            pinnen: Strategie::PhysischZuerst,
+            anzahl: 10,
+            zeit: Duration::from_secs(60),
+            aufwärmen: Duration::from_secs(3),
+        }
+    }
+
+    /*
+        kurzer Durchlauf aller Benchmarks mit wenigen kleinen Matrizen und allen Kernen, um z.B. in CI zu
+        prüfen, dass alles läuft
+    */
+    pub fn schnell() -> Konfiguration {
+        Konfiguration {
+            algorithmen: Vec::new(),
+            größen: vec![16, 64, 128],
+            kleine_größen: vec![4, 16],
+            verteilung_größen: vec![94],
+            threads: Threadauswahl::Maximum,
+            pinnen: Strategie::PhysischZuerst,
+            anzahl: 10,
+            zeit: Duration::from_secs(1),
+            aufwärmen: Duration::from_millis(500),
+        }
+    }
+
+    pub fn profil(name: &str) -> Option<Konfiguration> {
+        match name {
+            "voll" => Some(Konfiguration::voll()),
+            "schnell" => Some(Konfiguration::schnell()),
+            _ => None,
+        }
+    }
+
+    // soll der Algorithmus name gemessen werden
+    pub fn gewählt(&self, name: &str) -> bool {
+        self.algorithmen.is_empty() || self.algorithmen.iter().any(|a| a == name)
+    }
+
+    /*
+        setzt einen Schlüssel auf wert, bei einem ungültigen Wert wird der Grund zurückgegeben. Listen sind durch
+        Kommas getrennt
+    */
+    fn setzen(&mut self, schlüssel: &str, wert: &str) -> Result<(), String> {
+        match schlüssel {
+            "profil" => {
+                *self = Konfiguration::profil(wert).ok_or_else(|| {
+                    format!("unbekanntes Profil {}, erlaubt: voll, schnell", wert)
+                })?;
+            }
+            "algorithmen" if wert == "alle" => self.algorithmen = Vec::new(),
+            "algorithmen" => {
+                self.algorithmen = Vec::new();
+                for name in liste_lesen(wert) {
+                    if finden::<f64>(name).is_none() {
+                        return Err(format!("unbekannter Algorithmus {}", name));
+                    }
+                    self.algorithmen.push(name.to_string());
+                }
+            }
+            "groessen" => self.größen = größen_lesen(wert)?,
+            "kleine_groessen" => self.kleine_größen = größen_lesen(wert)?,
+            "verteilung_groessen" => self.verteilung_größen = größen_lesen(wert)?,
+            "threads" => {
+                self.threads = match wert {
+                    "alle" => Threadauswahl::Alle,
+                    "maximum" => Threadauswahl::Maximum,
+                    _ => Threadauswahl::Liste(größen_lesen(wert)?),
+                };
+            }
+            "pinnen" => {
+                self.pinnen = Strategie::aus_name(wert)
+                    .ok_or_else(|| format!("unbekannte Strategie zum pinnen {}", wert))?;
+            }
+            "anzahl" => {
+                self.anzahl = zahl_lesen(wert)?;
+                if self.anzahl < 10 {
+                    return Err(format!(
+                        "Criterion braucht mindestens 10 Messungen, nicht {}",
+                        wert
+                    ));
+                }
+            }
+            "zeit" => self.zeit = sekunden_lesen(wert)?,
+            "aufwaermen" => self.aufwärmen = sekunden_lesen(wert)?,
+            _ => return Err(format!("unbekannter Schlüssel {}", schlüssel)),
+        }
+        Ok(())
+    }
+
+    /*
+        liest eine Konfiguration im Format von Display: eine Zeile "schlüssel = wert" je Schlüssel, leere Zeilen
+        und Kommentare mit # werden übersprungen. Nicht angegebene Schlüssel kommen aus dem Profil voll
+    */
+    pub fn aus_text(text: &str) -> Result<Konfiguration, Fehler> {
+        Konfiguration::aus_quellen(Some(("Konfiguration", text)), |_| None)
+    }
+
+    /*
+        liest die Datei aus der Umgebungsvariable DATEI, falls gesetzt, und danach die Umgebungsvariablen PRÄFIX
+        + SCHLÜSSEL in Großbuchstaben, z.B. MULTIPLIKATION_BENCH_GROESSEN=64,128. Umgebungsvariablen haben
+        Vorrang vor der Datei
+    */
+    pub fn aus_umgebung() -> Result<Konfiguration, Fehler> {
+        let datei: Option<(String, String)> = match env::var_os(DATEI) {
+            Some(pfad) => {
+                let name: String = pfad.to_string_lossy().into_owned();
+                let text: String = fs::read_to_string(&pfad)
+                    .map_err(|f| Fehler::Datei(format!("{}: {}", name, f)))?;
+                Some((name, text))
+            }
+            None => None,
+        };
+
+        Konfiguration::aus_quellen(
+            datei
+                .as_ref()
+                .map(|(name, text)| (name.as_str(), text.as_str())),
+            |variable| env::var(variable).ok(),
+        )
+    }
+
+    /*
+        wie aus_umgebung, datei ist (Name für Fehlermeldungen, Inhalt) und umgebung liefert den Wert einer
+        Umgebungsvariable. Ein Profil wird immer zuerst angewendet, damit es die anderen Schlüssel nicht
+        überschreibt
+    */
+    pub fn aus_quellen(
+        datei: Option<(&str, &str)>,
+        umgebung: impl Fn(&str) -> Option<String>,
+    ) -> Result<Konfiguration, Fehler> {
+        // (Herkunft, Schlüssel, Wert)
+        let mut einträge: Vec<(String, String, String)> = Vec::new();
+
+        if let Some((name, text)) = datei {
+            for (nummer, zeile) in text.lines().enumerate() {
+                let zeile: &str = zeile.split('#').next().unwrap_or("").trim();
+                if zeile.is_empty() {
+                    continue;
+                }
+                let herkunft: String = format!("{}:{}", name, nummer + 1);
+                let Some((schlüssel, wert)) = zeile.split_once('=') else {
+                    return Err(Fehler::Argument(format!(
+                        "{}: erwartet schlüssel = wert, nicht {}",
+                        herkunft, zeile
+                    )));
+                };
+                einträge.push((
+                    herkunft,
+                    schlüssel.trim().to_string(),
+                    wert.trim().to_string(),
+                ));
+            }
+        }
+        for schlüssel in SCHLÜSSEL {
+            let variable: String = format!("{}{}", PRÄFIX, schlüssel.to_uppercase());
+            if let Some(wert) = umgebung(&variable) {
+                einträge.push((variable, schlüssel.to_string(), wert.trim().to_string()));
+            }
+        }
+
+        einträge.sort_by_key(|(_, schlüssel, _)| schlüssel != "profil");
+        let mut konfiguration: Konfiguration = Konfiguration::voll();
+        for (herkunft, schlüssel, wert) in einträge {
+            konfiguration
+                .setzen(&schlüssel, &wert)
+                .map_err(|grund| Fehler::Argument(format!("{}: {}", herkunft, grund)))?;
+        }
+        Ok(konfiguration)
+    }
+}
+
+impl fmt::Display for Konfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.algorithmen.is_empty() {
+            writeln!(f, "algorithmen = alle")?;
+        } else {
+            writeln!(f, "algorithmen = {}", self.algorithmen.join(","))?;
+        }
+        writeln!(f, "groessen = {}", liste(&self.größen))?;
+        writeln!(f, "kleine_groessen = {}", liste(&self.kleine_größen))?;
+        writeln!(
+            f,
+            "verteilung_groessen = {}",
+            liste(&self.verteilung_größen)
+        )?;
+        writeln!(f, "threads = {}", self.threads)?;
+        writeln!(f, "pinnen = {}", self.pinnen)?;
+        writeln!(f, "anzahl = {}", self.anzahl)?;
+        writeln!(f, "zeit = {}", self.zeit.as_secs_f64())?;
+        writeln!(f, "aufwaermen = {}", self.aufwärmen.as_secs_f64())
+    }
+}
+
+fn liste(werte: &[usize]) -> String {
+    werte
+        .iter()
+        .map(|wert| wert.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn liste_lesen(wert: &str) -> impl Iterator<Item = &str> {
+    wert.split(',')
+        .map(str::trim)
+        .filter(|teil| !teil.is_empty())
+}
+
+fn zahl_lesen(wert: &str) -> Result<usize, String> {
+    wert.parse()
+        .map_err(|_| format!("erwartet eine Zahl, nicht {}", wert))
+}
+
+// Liste von Zahlen größer 0, für Größen und Threads
+fn größen_lesen(wert: &str) -> Result<Vec<usize>, String> {
+    liste_lesen(wert)
+        .map(|teil| match zahl_lesen(teil)? {
+            0 => Err("0 ist nicht erlaubt".to_string()),
+            zahl => Ok(zahl),
+        })
+        .collect()
+}
+
+fn sekunden_lesen(wert: &str) -> Result<Duration, String> {
+    match wert.parse::<f64>() {
+        Ok(sekunden) if sekunden.is_finite() && sekunden > 0.0 => {
+            Ok(Duration::from_secs_f64(sekunden))
+        }
+        _ => Err(format!("erwartet Sekunden größer 0, nicht {}", wert)),
+    }
+}
@@ -1,16 +1,43 @@
-#![feature(portable_simd)]
+// std::simd ist nur auf nightly verfügbar, ohne das Feature nightly-simd baut das Crate auf stable Rust
+#![cfg_attr(feature = "nightly-simd", feature(portable_simd))]
+// Matrixalgorithmen werden bewusst mit Indexschleifen geschrieben, da sie so der mathematischen Definition folgen
+#![allow(clippy::needless_range_loop)]
+// die Algorithmen bekommen wie bei BLAS alle Matrizen und Dimensionen einzeln übergeben
+#![allow(clippy::too_many_arguments)]
 
+pub mod abft;
+pub mod auswertung;
+pub mod cpu;
+pub mod fehler;
+pub mod gemm;
+pub mod generator;
+pub mod kommandozeile;
+pub mod konfiguration;
 pub mod matrix;
+pub mod multiplikator;
+pub mod numa;
+pub mod rechenpool;
+pub mod regression;
+pub mod roofline;
 pub mod test;
+pub mod topologie;
+pub mod verifikation;
+pub mod verteilung;
+pub mod zaehler;
+pub mod zahl;
+pub mod zerlegung;
 
 pub mod algorithmen {
     pub mod crossbeam;
+    pub mod gepack;
     pub mod manuell_sicher;
     pub mod manuell_unsicher;
     pub mod rayon;
     pub mod simd;
     pub mod simd_tiling;
     pub mod single;
+    pub mod strassen;
     pub mod tiling;
     pub mod unroll;
+    pub mod winograd;
 }
@@ -0,0 +1,94 @@
+use multiplikation::kommandozeile::{self, Messung, Optionen};
+use multiplikation::rechenpool;
+use multiplikation::regression::{self, Eintrag, Urteil, Vergleich};
+use std::{env, process};
+
+// Exit Code bei einer Regression gegenüber der Basislinie, 1 steht für Fehler und 2 für ungültige Argumente
+const REGRESSION: i32 = 3;
+
+fn main() {
+    let argumente: Vec<String> = env::args().skip(1).collect();
+
+    let optionen: Optionen = Optionen::lesen(&argumente).unwrap_or_else(|f| {
+        eprintln!("{}\n\n{}", f, kommandozeile::HILFE);
+        process::exit(2);
+    });
+
+    if optionen.hilfe {
+        print!("{}", kommandozeile::HILFE);
+        return;
+    }
+    if optionen.liste {
+        print!("{}", kommandozeile::liste());
+        return;
+    }
+
+    let ergebnis = kommandozeile::messen(&optionen);
+    // die Arbeiter der gemeinsamen Rechenpools beenden
+    rechenpool::alle_beenden();
+
+    let messungen: Vec<Messung> = ergebnis.unwrap_or_else(|f| beenden(f));
+    print!("{}", kommandozeile::ausgeben(&messungen, optionen.ausgabe));
+    // ohne Hardware Zähler, z.B. in Containern, sind die Messungen trotzdem gültig
+    if let Some(f) = messungen
+        .iter()
+        .find_map(|messung| messung.zähler.as_ref()?.fehler.as_ref())
+    {
+        eprintln!("Hinweis: {}", f);
+    }
+
+    // zuerst vergleichen, damit eine Basislinie mit demselben Namen nicht vorher überschrieben wird
+    let mut verschlechtert: bool = false;
+    if let Some(basislinie) = &optionen.vergleichen {
+        let gespeichert: Vec<Eintrag> =
+            regression::laden(&optionen.ergebnisse).unwrap_or_else(|f| beenden(f));
+        if !gespeichert
+            .iter()
+            .any(|eintrag| &eintrag.basislinie == basislinie)
+        {
+            eprintln!(
+                "Fehler: Basislinie {} nicht in {} gefunden",
+                basislinie,
+                optionen.ergebnisse.display()
+            );
+            process::exit(1);
+        }
+
+        let vergleiche: Vec<Vergleich> = regression::vergleichen(
+            &gespeichert,
+            basislinie,
+            &kommandozeile::einträge(&messungen, basislinie),
+            optionen.schwelle,
+            optionen.signifikanz,
+        );
+        eprint!(
+            "{}",
+            regression::bericht(&vergleiche, basislinie, optionen.schwelle)
+        );
+        verschlechtert = vergleiche
+            .iter()
+            .any(|vergleich| vergleich.urteil == Urteil::Regression);
+    }
+
+    if let Some(basislinie) = &optionen.speichern {
+        regression::speichern(
+            &optionen.ergebnisse,
+            &kommandozeile::einträge(&messungen, basislinie),
+        )
+        .unwrap_or_else(|f| beenden(f));
+        eprintln!(
+            "als Basislinie {} in {} gespeichert",
+            basislinie,
+            optionen.ergebnisse.display()
+        );
+    }
+
+    if verschlechtert {
+        process::exit(REGRESSION);
+    }
+}
+
+fn beenden(f: multiplikation::fehler::Fehler) -> ! {
+    eprintln!("Fehler: {}", f);
+    process::exit(1);
+}
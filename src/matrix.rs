@@ -1,15 +1,773 @@
-use rand::random_range;
+use crate::fehler::Fehler;
+use crate::zahl::{Komplex, Zahl};
+use std::{
+    fmt, fs,
+    ops::{Index, IndexMut},
+    path::Path,
+};
 
 /*
-    erstellt eine "2D Matrix mit Zufallswerten im Bereich [-1.0, 1,0]"
+    Matrix mit einem einzigen zusammenhängenden Speicherbereich im row-major Format.
+
+    Element (i, j) liegt an der Stelle i * stride + j. Der stride ist der Abstand zwischen zwei Zeilenanfängen
+    und normalerweise gleich der Anzahl der Spalten. Dadurch ist jede Zeile ein zusammenhängender Slice und
+    ein Zugriff auf b[k][j] benötigt keine zusätzliche Zeigerdereferenzierung mehr
+
+    Der Elementtyp ist standardmäßig f64, Matrix ohne Typangabe ist daher eine Matrix<f64>
 */
-pub fn zufallsmatrix_2d(n: usize) -> Vec<Vec<f64>> {
-    let mut matrix: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix<T = f64> {
+    pub zeilen: usize,
+    pub spalten: usize,
+    pub stride: usize,
+    pub daten: Vec<T>,
+}
+
+impl<T: Zahl> Matrix<T> {
+    /*
+        erstellt eine Matrix mit zeilen x spalten Nullen
+    */
+    pub fn neu(zeilen: usize, spalten: usize) -> Matrix<T> {
+        Matrix {
+            zeilen,
+            spalten,
+            stride: spalten,
+            daten: vec![T::NULL; zeilen * spalten],
+        }
+    }
+
+    // wie neu, gibt aber None zurück wenn der Speicher nicht reserviert werden kann
+    pub(crate) fn reservieren(zeilen: usize, spalten: usize) -> Option<Matrix<T>> {
+        let anzahl: usize = zeilen.checked_mul(spalten)?;
+        let mut daten: Vec<T> = Vec::new();
+        daten.try_reserve_exact(anzahl).ok()?;
+        daten.resize(anzahl, T::NULL);
+        Some(Matrix {
+            zeilen,
+            spalten,
+            stride: spalten,
+            daten,
+        })
+    }
+
+    /*
+        i-te Zeile als zusammenhängender Slice
+    */
+    pub fn zeile(&self, i: usize) -> &[T] {
+        let anfang: usize = i * self.stride;
+        &self.daten[anfang..anfang + self.spalten]
+    }
+
+    pub fn zeile_mut(&mut self, i: usize) -> &mut [T] {
+        let anfang: usize = i * self.stride;
+        &mut self.daten[anfang..anfang + self.spalten]
+    }
 
-    for i in 0..n {
-        for j in 0..n {
-            matrix[i][j] = random_range(-1.0..=1.0);
+    /*
+        erstellt eine neue Matrix mit vertauschten Zeilen und Spalten
+    */
+    pub fn transponiert(&self) -> Matrix<T> {
+        let mut ergebnis: Matrix<T> = Matrix::neu(self.spalten, self.zeilen);
+
+        for i in 0..self.zeilen {
+            for (j, &wert) in self.zeile(i).iter().enumerate() {
+                ergebnis[(j, i)] = wert;
+            }
         }
+        ergebnis
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        &self.daten[i * self.stride + j]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        &mut self.daten[i * self.stride + j]
+    }
+}
+
+/*
+    erstellt eine "2D Matrix mit Zufallswerten", der Bereich hängt vom Typ ab (siehe Zahl::zufall)
+*/
+pub fn zufallsmatrix_2d<T: Zahl>(n: usize) -> Matrix<T> {
+    zufallsmatrix(n, n)
+}
+
+/*
+    erstellt eine rechteckige Matrix mit zeilen x spalten Zufallswerten. Die Werte sind nicht reproduzierbar,
+    für wiederholbare Eingaben gibt es generator::Generator
+*/
+pub fn zufallsmatrix<T: Zahl>(zeilen: usize, spalten: usize) -> Matrix<T> {
+    let mut matrix: Matrix<T> = Matrix::neu(zeilen, spalten);
+
+    for wert in matrix.daten.iter_mut() {
+        *wert = T::zufall();
     }
     matrix
 }
+
+/*
+    Elementtypen die in Dateien gespeichert werden können
+
+    FELD: Typ in der Kopfzeile von Matrix Market (real, integer oder complex)
+    NPY: descr eines NumPy Arrays mit little-endian Werten
+    KENNUNG: Elementtyp im Kopf des Rohformats
+    KOMPONENTEN: Anzahl der Zahlen je Wert in Textformaten, 2 bei komplexen Zahlen
+    BYTES: Größe eines Wertes in den Binärformaten
+*/
+pub trait Dateiwert: Zahl {
+    const FELD: &'static str;
+    const NPY: &'static str;
+    const KENNUNG: u8;
+    const KOMPONENTEN: usize;
+    const BYTES: usize;
+
+    // Textdarstellung, die beim Lesen wieder genau denselben Wert ergibt
+    fn text(self) -> String;
+
+    // liest einen Wert aus genau KOMPONENTEN Zahlen
+    fn aus_text(teile: &[&str]) -> Option<Self>;
+
+    fn bytes(self, ziel: &mut Vec<u8>);
+
+    // liest einen Wert aus genau BYTES little-endian Bytes
+    fn aus_bytes(quelle: &[u8]) -> Self;
+}
+
+macro_rules! dateiwert {
+    ($typ:ty, $feld:expr, $npy:expr, $kennung:expr) => {
+        impl Dateiwert for $typ {
+            const FELD: &'static str = $feld;
+            const NPY: &'static str = $npy;
+            const KENNUNG: u8 = $kennung;
+            const KOMPONENTEN: usize = 1;
+            const BYTES: usize = size_of::<$typ>();
+
+            fn text(self) -> String {
+                format!("{:?}", self)
+            }
+
+            fn aus_text(teile: &[&str]) -> Option<$typ> {
+                teile[0].parse().ok()
+            }
+
+            fn bytes(self, ziel: &mut Vec<u8>) {
+                ziel.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn aus_bytes(quelle: &[u8]) -> $typ {
+                let mut feld: [u8; size_of::<$typ>()] = [0; size_of::<$typ>()];
+                feld.copy_from_slice(quelle);
+                <$typ>::from_le_bytes(feld)
+            }
+        }
+    };
+}
+
+/*
+    komplexe Zahlen werden als Real- und Imaginärteil hintereinander gespeichert
+*/
+macro_rules! komplexer_dateiwert {
+    ($typ:ty, $npy:expr, $kennung:expr) => {
+        impl Dateiwert for Komplex<$typ> {
+            const FELD: &'static str = "complex";
+            const NPY: &'static str = $npy;
+            const KENNUNG: u8 = $kennung;
+            const KOMPONENTEN: usize = 2;
+            const BYTES: usize = 2 * size_of::<$typ>();
+
+            fn text(self) -> String {
+                format!("{} {}", self.re.text(), self.im.text())
+            }
+
+            fn aus_text(teile: &[&str]) -> Option<Komplex<$typ>> {
+                Some(Komplex::neu(
+                    <$typ>::aus_text(&teile[..1])?,
+                    <$typ>::aus_text(&teile[1..])?,
+                ))
+            }
+
+            fn bytes(self, ziel: &mut Vec<u8>) {
+                self.re.bytes(ziel);
+                self.im.bytes(ziel);
+            }
+
+            fn aus_bytes(quelle: &[u8]) -> Komplex<$typ> {
+                let (re, im): (&[u8], &[u8]) = quelle.split_at(size_of::<$typ>());
+                Komplex::neu(<$typ>::aus_bytes(re), <$typ>::aus_bytes(im))
+            }
+        }
+    };
+}
+
+dateiwert!(f32, "real", "<f4", 1);
+dateiwert!(f64, "real", "<f8", 2);
+dateiwert!(i32, "integer", "<i4", 3);
+dateiwert!(i64, "integer", "<i8", 4);
+komplexer_dateiwert!(f32, "<c8", 5);
+komplexer_dateiwert!(f64, "<c16", 6);
+
+/*
+    Dateiformate zum Speichern einer Matrix. Beim Laden wird das Format am Anfang der Datei erkannt
+
+    MatrixMarketDicht: Matrix Market array, alle Werte spaltenweise
+    MatrixMarketKoordinaten: Matrix Market coordinate, nur Werte ungleich 0 mit 1-basierter Zeile und Spalte
+    Npy: NumPy .npy, Werte zeilenweise (C Reihenfolge)
+    NpyFortran: NumPy .npy, Werte spaltenweise (Fortran Reihenfolge)
+    Roh: Kopf aus "MATRIX", Version, Elementtyp, zeilen und spalten als u64, danach die Werte zeilenweise. Alle
+         Zahlen little-endian
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dateiformat {
+    MatrixMarketDicht,
+    MatrixMarketKoordinaten,
+    Npy,
+    NpyFortran,
+    Roh,
+}
+
+impl Dateiformat {
+    pub const ALLE: [Dateiformat; 5] = [
+        Dateiformat::MatrixMarketDicht,
+        Dateiformat::MatrixMarketKoordinaten,
+        Dateiformat::Npy,
+        Dateiformat::NpyFortran,
+        Dateiformat::Roh,
+    ];
+
+    // übliche Dateiendung
+    pub fn endung(self) -> &'static str {
+        match self {
+            Dateiformat::MatrixMarketDicht | Dateiformat::MatrixMarketKoordinaten => "mtx",
+            Dateiformat::Npy | Dateiformat::NpyFortran => "npy",
+            Dateiformat::Roh => "bin",
+        }
+    }
+}
+
+impl fmt::Display for Dateiformat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dateiformat::MatrixMarketDicht => write!(f, "matrix_market_dicht"),
+            Dateiformat::MatrixMarketKoordinaten => write!(f, "matrix_market_koordinaten"),
+            Dateiformat::Npy => write!(f, "npy"),
+            Dateiformat::NpyFortran => write!(f, "npy_fortran"),
+            Dateiformat::Roh => write!(f, "roh"),
+        }
+    }
+}
+
+const MATRIX_MARKET: &str = "Matrix Market";
+const NPY: &str = "NumPy";
+const ROH: &str = "Roh";
+
+// Kennungen am Anfang der Dateien
+const MATRIX_MARKET_KENNUNG: &[u8] = b"%%MatrixMarket";
+const NPY_KENNUNG: &[u8] = b"\x93NUMPY";
+const ROH_KENNUNG: &[u8] = b"MATRIX";
+const ROH_VERSION: u8 = 1;
+const ROH_KOPF: usize = 24;
+
+fn formatfehler(format: &'static str, grund: String) -> Fehler {
+    Fehler::Dateiformat { format, grund }
+}
+
+impl<T: Dateiwert> Matrix<T> {
+    /*
+        speichert die Matrix im gewählten Format in pfad
+    */
+    pub fn speichern(&self, pfad: &Path, format: Dateiformat) -> Result<(), Fehler> {
+        fs::write(pfad, self.kodieren(format))
+            .map_err(|f| Fehler::Datei(format!("{}: {}", pfad.display(), f)))
+    }
+
+    /*
+        lädt eine Matrix aus pfad, das Format wird am Inhalt erkannt
+    */
+    pub fn laden(pfad: &Path) -> Result<Matrix<T>, Fehler> {
+        let bytes: Vec<u8> =
+            fs::read(pfad).map_err(|f| Fehler::Datei(format!("{}: {}", pfad.display(), f)))?;
+        Matrix::dekodieren(&bytes)
+    }
+
+    /*
+        Inhalt einer Datei im gewählten Format
+    */
+    pub fn kodieren(&self, format: Dateiformat) -> Vec<u8> {
+        match format {
+            Dateiformat::MatrixMarketDicht => self.matrix_market_dicht().into_bytes(),
+            Dateiformat::MatrixMarketKoordinaten => self.matrix_market_koordinaten().into_bytes(),
+            Dateiformat::Npy => self.npy(false),
+            Dateiformat::NpyFortran => self.npy(true),
+            Dateiformat::Roh => self.roh(),
+        }
+    }
+
+    /*
+        liest eine Matrix aus dem Inhalt einer Datei in einem der Formate. Der Elementtyp der Datei muss zu T
+        passen, nur ganze Zahlen aus Matrix Market Dateien werden auch als Gleitkommazahlen gelesen
+    */
+    pub fn dekodieren(bytes: &[u8]) -> Result<Matrix<T>, Fehler> {
+        if bytes.starts_with(NPY_KENNUNG) {
+            Matrix::npy_lesen(bytes)
+        } else if bytes.starts_with(ROH_KENNUNG) {
+            Matrix::roh_lesen(bytes)
+        } else if bytes.len() >= MATRIX_MARKET_KENNUNG.len()
+            && bytes[..MATRIX_MARKET_KENNUNG.len()].eq_ignore_ascii_case(MATRIX_MARKET_KENNUNG)
+        {
+            let text: &str = str::from_utf8(bytes)
+                .map_err(|f| formatfehler(MATRIX_MARKET, format!("kein UTF-8 Text: {}", f)))?;
+            Matrix::matrix_market_lesen(text)
+        } else {
+            Err(Fehler::Dateiformat {
+                format: "unbekannt",
+                grund: "weder Matrix Market, NumPy noch Rohformat".to_string(),
+            })
+        }
+    }
+
+    fn matrix_market_dicht(&self) -> String {
+        let mut text: String = format!(
+            "%%MatrixMarket matrix array {} general\n{} {}\n",
+            T::FELD,
+            self.zeilen,
+            self.spalten
+        );
+        for j in 0..self.spalten {
+            for i in 0..self.zeilen {
+                text += &self[(i, j)].text();
+                text.push('\n');
+            }
+        }
+        text
+    }
+
+    fn matrix_market_koordinaten(&self) -> String {
+        let mut einträge: String = String::new();
+        let mut anzahl: usize = 0;
+        for i in 0..self.zeilen {
+            for (j, &wert) in self.zeile(i).iter().enumerate() {
+                if wert != T::NULL {
+                    einträge += &format!("{} {} {}\n", i + 1, j + 1, wert.text());
+                    anzahl += 1;
+                }
+            }
+        }
+        format!(
+            "%%MatrixMarket matrix coordinate {} general\n{} {} {}\n{}",
+            T::FELD,
+            self.zeilen,
+            self.spalten,
+            anzahl,
+            einträge
+        )
+    }
+
+    /*
+        Kopfzeile "%%MatrixMarket matrix <array|coordinate> <feld> <symmetrie>", danach Kommentare mit %, die
+        Größe und die Werte. Bei symmetric und skew-symmetric ist nur das untere Dreieck gespeichert, doppelte
+        Koordinaten werden aufsummiert
+    */
+    fn matrix_market_lesen(text: &str) -> Result<Matrix<T>, Fehler> {
+        let fehler = |nr: usize, grund: String| {
+            formatfehler(MATRIX_MARKET, format!("Zeile {}: {}", nr, grund))
+        };
+
+        let mut zeilen = text
+            .lines()
+            .map(str::trim)
+            .enumerate()
+            .map(|(nr, z)| (nr + 1, z));
+        let kopf: Vec<String> = zeilen
+            .next()
+            .map(|(_, z)| z.split_whitespace().map(str::to_lowercase).collect())
+            .unwrap_or_default();
+        let [_, objekt, anordnung, feld, symmetrie] = kopf.as_slice() else {
+            return Err(fehler(1, "unvollständige Kopfzeile".to_string()));
+        };
+
+        if objekt != "matrix" {
+            return Err(fehler(
+                1,
+                format!("Objekt {} wird nicht unterstützt", objekt),
+            ));
+        }
+        let koordinaten: bool = match anordnung.as_str() {
+            "array" => false,
+            "coordinate" => true,
+            _ => return Err(fehler(1, format!("unbekanntes Format {}", anordnung))),
+        };
+        let passend: bool = feld == T::FELD
+            || (feld == "integer" && T::FELD == "real")
+            || (feld == "pattern" && koordinaten);
+        if !passend {
+            return Err(fehler(
+                1,
+                format!("Feld {} passt nicht zu {}", feld, T::FELD),
+            ));
+        }
+        let spiegeln: Option<bool> = match symmetrie.as_str() {
+            "general" => None,
+            "symmetric" => Some(false),
+            "skew-symmetric" => Some(true),
+            _ => {
+                return Err(fehler(
+                    1,
+                    format!("Symmetrie {} wird nicht unterstützt", symmetrie),
+                ));
+            }
+        };
+
+        // Kommentare und leere Zeilen überspringen, jede weitere Zeile enthält Zahlen
+        let mut daten = zeilen.filter(|(_, z)| !z.is_empty() && !z.starts_with('%'));
+        let (nr, größe) = daten
+            .next()
+            .ok_or_else(|| fehler(1, "die Größe fehlt".to_string()))?;
+        let größe: Vec<usize> = größe
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map_err(|f| fehler(nr, format!("ungültige Größe: {}", f)))?;
+        let (zeilen, spalten, anzahl): (usize, usize, Option<usize>) =
+            match (koordinaten, größe.as_slice()) {
+                (false, &[zeilen, spalten]) => (zeilen, spalten, None),
+                (true, &[zeilen, spalten, anzahl]) => (zeilen, spalten, Some(anzahl)),
+                _ => return Err(fehler(nr, "falsche Anzahl an Größenangaben".to_string())),
+            };
+        if spiegeln.is_some() && zeilen != spalten {
+            return Err(fehler(
+                nr,
+                "symmetrische Matrizen müssen quadratisch sein".to_string(),
+            ));
+        }
+        // auch ohne Überlauf darf ein Vec höchstens isize::MAX Bytes groß sein
+        zeilen
+            .checked_mul(spalten)
+            .and_then(|anzahl| anzahl.checked_mul(size_of::<T>()))
+            .filter(|&bytes| bytes <= isize::MAX as usize)
+            .ok_or_else(|| fehler(nr, "die Matrix ist zu groß".to_string()))?;
+
+        // im dichten Format muss die Anzahl der Werte vor dem Anlegen der Matrix zur Größe passen
+        if anzahl.is_none() {
+            let erwartet: usize = match spiegeln {
+                None => zeilen * spalten,
+                Some(false) => zeilen * (zeilen + 1) / 2,
+                Some(true) => zeilen * zeilen.saturating_sub(1) / 2,
+            };
+            let vorhanden: usize = daten.clone().count();
+            if vorhanden != erwartet {
+                return Err(fehler(
+                    nr,
+                    format!(
+                        "{} Werte für {}x{} erwartet, die Datei enthält {}",
+                        erwartet, zeilen, spalten, vorhanden
+                    ),
+                ));
+            }
+        }
+
+        let komponenten: usize = if feld == "pattern" { 0 } else { T::KOMPONENTEN };
+        let wert_lesen = |nr: usize, teile: &[&str]| -> Result<T, Fehler> {
+            if komponenten == 0 {
+                return Ok(T::EINS);
+            }
+            T::aus_text(teile)
+                .ok_or_else(|| fehler(nr, format!("ungültiger Wert {}", teile.join(" "))))
+        };
+
+        // die Größe im Kopf bestimmt den Speicher, eine zu große Angabe wird gemeldet statt abzubrechen
+        let mut matrix: Matrix<T> = Matrix::reservieren(zeilen, spalten)
+            .ok_or_else(|| fehler(nr, "nicht genug Speicher für die Matrix".to_string()))?;
+        let mut eintragen = |i: usize, j: usize, wert: T| {
+            matrix[(i, j)] += wert;
+            match spiegeln {
+                Some(false) if i != j => matrix[(j, i)] += wert,
+                Some(true) if i != j => matrix[(j, i)] += T::NULL - wert,
+                _ => {}
+            }
+        };
+
+        match anzahl {
+            None => {
+                // spaltenweise, bei Symmetrie nur das untere Dreieck (ohne Diagonale bei skew-symmetric)
+                for j in 0..spalten {
+                    for i in 0..zeilen {
+                        let gespeichert: bool = match spiegeln {
+                            None => true,
+                            Some(false) => i >= j,
+                            Some(true) => i > j,
+                        };
+                        if !gespeichert {
+                            continue;
+                        }
+                        let (nr, zeile) = daten.next().ok_or_else(|| {
+                            fehler(text.lines().count(), "zu wenige Werte".to_string())
+                        })?;
+                        let teile: Vec<&str> = zeile.split_whitespace().collect();
+                        if teile.len() != komponenten {
+                            return Err(fehler(nr, format!("{} Zahlen erwartet", komponenten)));
+                        }
+                        eintragen(i, j, wert_lesen(nr, &teile)?);
+                    }
+                }
+            }
+            Some(anzahl) => {
+                for _ in 0..anzahl {
+                    let (nr, zeile) = daten.next().ok_or_else(|| {
+                        fehler(text.lines().count(), "zu wenige Einträge".to_string())
+                    })?;
+                    let teile: Vec<&str> = zeile.split_whitespace().collect();
+                    if teile.len() != 2 + komponenten {
+                        return Err(fehler(nr, format!("{} Zahlen erwartet", 2 + komponenten)));
+                    }
+                    let position = |teil: &str, grenze: usize| -> Result<usize, Fehler> {
+                        match teil.parse::<usize>() {
+                            Ok(x) if (1..=grenze).contains(&x) => Ok(x - 1),
+                            _ => Err(fehler(
+                                nr,
+                                format!("Position {} außerhalb von 1..={}", teil, grenze),
+                            )),
+                        }
+                    };
+                    let (i, j): (usize, usize) =
+                        (position(teile[0], zeilen)?, position(teile[1], spalten)?);
+                    if spiegeln.is_some() && i < j {
+                        return Err(fehler(nr, "Eintrag oberhalb der Diagonale".to_string()));
+                    }
+                    eintragen(i, j, wert_lesen(nr, &teile[2..])?);
+                }
+            }
+        }
+
+        if let Some((nr, _)) = daten.next() {
+            return Err(fehler(
+                nr,
+                "zusätzliche Daten nach dem letzten Wert".to_string(),
+            ));
+        }
+        Ok(matrix)
+    }
+
+    fn npy(&self, fortran: bool) -> Vec<u8> {
+        // Version 1.0: Kennung, Version, Länge des Kopfes als u16, Kopf mit Leerzeichen auf 64 Bytes aufgefüllt
+        let mut kopf: String = format!(
+            "{{'descr': '{}', 'fortran_order': {}, 'shape': ({}, {}), }}",
+            T::NPY,
+            if fortran { "True" } else { "False" },
+            self.zeilen,
+            self.spalten
+        );
+        let länge: usize = (NPY_KENNUNG.len() + 4 + kopf.len() + 1).next_multiple_of(64);
+        while NPY_KENNUNG.len() + 4 + kopf.len() + 1 < länge {
+            kopf.push(' ');
+        }
+        kopf.push('\n');
+
+        let mut bytes: Vec<u8> = Vec::with_capacity(länge + self.zeilen * self.spalten * T::BYTES);
+        bytes.extend_from_slice(NPY_KENNUNG);
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(kopf.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(kopf.as_bytes());
+
+        if fortran {
+            for j in 0..self.spalten {
+                for i in 0..self.zeilen {
+                    self[(i, j)].bytes(&mut bytes);
+                }
+            }
+        } else {
+            for i in 0..self.zeilen {
+                for &wert in self.zeile(i) {
+                    wert.bytes(&mut bytes);
+                }
+            }
+        }
+        bytes
+    }
+
+    /*
+        NumPy .npy der Versionen 1.0 bis 3.0 mit einem 2 dimensionalen Array, der Kopf ist ein Python dict mit
+        descr, fortran_order und shape
+    */
+    fn npy_lesen(bytes: &[u8]) -> Result<Matrix<T>, Fehler> {
+        let fehler = |grund: String| formatfehler(NPY, grund);
+
+        let version: u8 = *bytes
+            .get(6)
+            .ok_or_else(|| fehler("die Version fehlt".to_string()))?;
+        let (länge, anfang): (usize, usize) = match version {
+            1 => (
+                bytes
+                    .get(8..10)
+                    .map_or(0, |x| u16::from_le_bytes([x[0], x[1]]) as usize),
+                10,
+            ),
+            2 | 3 => (
+                bytes
+                    .get(8..12)
+                    .map_or(0, |x| u32::from_le_bytes([x[0], x[1], x[2], x[3]]) as usize),
+                12,
+            ),
+            _ => {
+                return Err(fehler(format!(
+                    "Version {} wird nicht unterstützt",
+                    version
+                )));
+            }
+        };
+        let kopf: &str = bytes
+            .get(anfang..anfang + länge)
+            .and_then(|kopf| str::from_utf8(kopf).ok())
+            .ok_or_else(|| fehler("der Kopf ist unvollständig".to_string()))?;
+
+        let descr: &str = npy_eintrag(kopf, "descr")
+            .map(|d| d.trim_matches(|z| z == '\'' || z == '"'))
+            .ok_or_else(|| fehler("descr fehlt".to_string()))?;
+        if descr.starts_with('>') {
+            return Err(fehler(format!(
+                "big-endian ({}) wird nicht unterstützt",
+                descr
+            )));
+        }
+        if descr != T::NPY {
+            return Err(fehler(format!(
+                "Elementtyp {} passt nicht zu {}",
+                descr,
+                T::NPY
+            )));
+        }
+        let fortran: bool = match npy_eintrag(kopf, "fortran_order") {
+            Some("True") => true,
+            Some("False") => false,
+            _ => return Err(fehler("fortran_order fehlt".to_string())),
+        };
+        let form: Vec<usize> = npy_eintrag(kopf, "shape")
+            .and_then(|form| {
+                form.trim_matches(|z| z == '(' || z == ')')
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|teil| !teil.is_empty())
+                    .map(|teil| teil.parse().ok())
+                    .collect()
+            })
+            .ok_or_else(|| fehler("shape fehlt".to_string()))?;
+        let &[zeilen, spalten] = form.as_slice() else {
+            return Err(fehler(format!("{} statt 2 Dimensionen", form.len())));
+        };
+
+        let daten: &[u8] = &bytes[anfang + länge..];
+        let erwartet: Option<usize> = zeilen
+            .checked_mul(spalten)
+            .and_then(|anzahl| anzahl.checked_mul(T::BYTES));
+        if erwartet != Some(daten.len()) {
+            return Err(fehler(format!(
+                "{} Bytes Daten für {}x{} Werte, erwartet wurden {}",
+                daten.len(),
+                zeilen,
+                spalten,
+                erwartet.map_or("mehr".to_string(), |e| e.to_string())
+            )));
+        }
+
+        let mut matrix: Matrix<T> = Matrix::neu(zeilen, spalten);
+        for (x, wert) in daten.chunks_exact(T::BYTES).enumerate() {
+            let (i, j): (usize, usize) = if fortran {
+                (x % zeilen, x / zeilen)
+            } else {
+                (x / spalten, x % spalten)
+            };
+            matrix[(i, j)] = T::aus_bytes(wert);
+        }
+        Ok(matrix)
+    }
+
+    fn roh(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> =
+            Vec::with_capacity(ROH_KOPF + self.zeilen * self.spalten * T::BYTES);
+        bytes.extend_from_slice(ROH_KENNUNG);
+        bytes.extend_from_slice(&[ROH_VERSION, T::KENNUNG]);
+        bytes.extend_from_slice(&(self.zeilen as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.spalten as u64).to_le_bytes());
+        for i in 0..self.zeilen {
+            for &wert in self.zeile(i) {
+                wert.bytes(&mut bytes);
+            }
+        }
+        bytes
+    }
+
+    fn roh_lesen(bytes: &[u8]) -> Result<Matrix<T>, Fehler> {
+        let fehler = |grund: String| formatfehler(ROH, grund);
+
+        if bytes.len() < ROH_KOPF {
+            return Err(fehler(format!(
+                "der Kopf hat nur {} von {} Bytes",
+                bytes.len(),
+                ROH_KOPF
+            )));
+        }
+        if bytes[6] != ROH_VERSION {
+            return Err(fehler(format!(
+                "Version {} wird nicht unterstützt",
+                bytes[6]
+            )));
+        }
+        if bytes[7] != T::KENNUNG {
+            return Err(fehler(format!(
+                "Elementtyp {} passt nicht zu {}",
+                bytes[7],
+                T::KENNUNG
+            )));
+        }
+        let zahl = |von: usize| -> Option<usize> {
+            let mut feld: [u8; 8] = [0; 8];
+            feld.copy_from_slice(&bytes[von..von + 8]);
+            usize::try_from(u64::from_le_bytes(feld)).ok()
+        };
+        let (zeilen, spalten): (usize, usize) = zahl(8)
+            .zip(zahl(16))
+            .ok_or_else(|| fehler("die Größe passt nicht in usize".to_string()))?;
+
+        let daten: &[u8] = &bytes[ROH_KOPF..];
+        let erwartet: Option<usize> = zeilen
+            .checked_mul(spalten)
+            .and_then(|anzahl| anzahl.checked_mul(T::BYTES));
+        if erwartet != Some(daten.len()) {
+            return Err(fehler(format!(
+                "{} Bytes Daten für {}x{} Werte, erwartet wurden {}",
+                daten.len(),
+                zeilen,
+                spalten,
+                erwartet.map_or("mehr".to_string(), |e| e.to_string())
+            )));
+        }
+
+        Ok(Matrix {
+            zeilen,
+            spalten,
+            stride: spalten,
+            daten: daten.chunks_exact(T::BYTES).map(T::aus_bytes).collect(),
+        })
+    }
+}
+
+/*
+    Wert zu schlüssel im Kopf einer .npy Datei, z.B. "'<f8'" für descr oder "(3, 4)" für shape
+*/
+fn npy_eintrag<'a>(kopf: &'a str, schlüssel: &str) -> Option<&'a str> {
+    let anfang: usize = kopf.find(&format!("'{}'", schlüssel))? + schlüssel.len() + 2;
+    let rest: &str = kopf[anfang..].trim_start().strip_prefix(':')?.trim_start();
+
+    let ende: usize = match rest.chars().next()? {
+        '(' => rest.find(')')? + 1,
+        anführung @ ('\'' | '"') => rest[1..].find(anführung)? + 2,
+        _ => rest.find([',', '}'])?,
+    };
+    Some(rest[..ende].trim())
+}
@@ -0,0 +1,156 @@
+use crate::algorithmen::{
+    crossbeam, gepack, manuell_sicher, manuell_unsicher, rayon, simd, simd_tiling, single,
+    strassen, tiling, unroll, winograd,
+};
+use crate::fehler::Fehler;
+use crate::matrix::Matrix;
+use crate::rechenpool::Ausführung;
+use crate::verifikation;
+use crate::verteilung::Verteilung;
+use crate::zahl::Zahl;
+use crate::zerlegung::Zerlegung;
+use core_affinity::CoreId;
+
+/*
+    Einstellungen die für alle Algorithmen gleich sind
+
+    threads: Anzahl der Threads die verwendet werden sollen
+    pinnen: Kerne auf die Thread z gepinnt wird (Thread z -> pinnen[z])
+    ausführung: Threads der manuellen Algorithmen jedesmal neu starten oder aus dem gemeinsamen Rechenpool nehmen
+    verteilung: wie die manuellen Algorithmen die Zeilen auf ihre Threads verteilen
+    zerlegung: ganze Zeilen oder Kacheln von c als Arbeitseinheit von tiling, simd und simd_tiling
+    verifikation: Anzahl der Wiederholungen von Freivalds nach jeder Multiplikation mit multiplizieren, None
+                  prüft nicht
+*/
+#[derive(Clone, Debug)]
+pub struct Einstellungen {
+    pub threads: usize,
+    pub pinnen: Vec<CoreId>,
+    pub ausführung: Ausführung,
+    pub verteilung: Verteilung,
+    pub zerlegung: Zerlegung,
+    pub verifikation: Option<usize>,
+}
+
+impl Einstellungen {
+    // standardmäßig wird der gemeinsame Rechenpool mit dynamischer Verteilung von je 4 Zeilen verwendet
+    pub fn neu(threads: usize, pinnen: Vec<CoreId>) -> Einstellungen {
+        Einstellungen {
+            threads,
+            pinnen,
+            ausführung: Ausführung::Rechenpool,
+            verteilung: Verteilung::default(),
+            zerlegung: Zerlegung::default(),
+            verifikation: None,
+        }
+    }
+
+    pub fn mit_ausführung(mut self, ausführung: Ausführung) -> Einstellungen {
+        self.ausführung = ausführung;
+        self
+    }
+
+    pub fn mit_verteilung(mut self, verteilung: Verteilung) -> Einstellungen {
+        self.verteilung = verteilung;
+        self
+    }
+
+    pub fn mit_zerlegung(mut self, zerlegung: Zerlegung) -> Einstellungen {
+        self.zerlegung = zerlegung;
+        self
+    }
+
+    pub fn mit_verifikation(mut self, wiederholungen: usize) -> Einstellungen {
+        self.verifikation = Some(wiederholungen);
+        self
+    }
+}
+
+/*
+    Eigenschaften eines Algorithmus, damit Tests und Benchmarks entscheiden können wie er aufgerufen wird
+
+    parallel: verwendet mehr als einen Thread (sonst wird nur pinnen[0] benutzt)
+    simd: verwendet explizit simd Instruktionen
+    unsicher: verwendet unsafe
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fähigkeiten {
+    pub parallel: bool,
+    pub simd: bool,
+    pub unsicher: bool,
+}
+
+/*
+    Gemeinsame Schnittstelle aller Strategien zur Matrixmultiplikation c = a * b
+
+    a ist eine m x k Matrix, b eine k x n Matrix und c eine m x n Matrix mit Elementen vom Typ T. Ohne
+    Typangabe ist dyn Multiplikator ein Multiplikator für f64
+*/
+pub trait Multiplikator<T: Zahl = f64>: Sync {
+    // eindeutiger Name, entspricht dem Modulnamen in algorithmen
+    fn name(&self) -> &'static str;
+
+    // lesbare Beschreibung, z.B. für die Namen der Benchmarkgruppen
+    fn beschreibung(&self) -> &'static str;
+
+    fn fähigkeiten(&self) -> Fähigkeiten;
+
+    fn ausführen(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler>;
+
+    // wie ausführen, prüft danach das Ergebnis mit Freivalds falls einstellungen.verifikation gesetzt ist
+    fn multiplizieren(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        c: &mut Matrix<T>,
+        m: usize,
+        k: usize,
+        n: usize,
+        einstellungen: &Einstellungen,
+    ) -> Result<(), Fehler> {
+        self.ausführen(a, b, c, m, k, n, einstellungen)?;
+        if let Some(wiederholungen) = einstellungen.verifikation {
+            verifikation::freivalds(a, b, c, m, k, n, wiederholungen)?;
+        }
+        Ok(())
+    }
+}
+
+/*
+    Register aller Algorithmen. Ein neuer Algorithmus muss nur hier eingetragen werden, damit er getestet und
+    gebenchmarkt wird
+
+    Strassen und Winograd werden mit den Standardeinstellungen für den jeweiligen Typ T eingetragen
+*/
+pub fn alle<T: Zahl>() -> Vec<&'static dyn Multiplikator<T>> {
+    vec![
+        &single::Single,
+        &manuell_sicher::ManuellSicher,
+        &manuell_unsicher::ManuellUnsicher,
+        &unroll::Unroll,
+        &tiling::Tiling,
+        &simd::Simd,
+        &simd_tiling::SimdTiling,
+        &rayon::Rayon,
+        &crossbeam::Crossbeam,
+        &gepack::Gepack,
+        &const { strassen::Strassen::<T>::standard() },
+        &const { winograd::Winograd::<T>::standard() },
+    ]
+}
+
+/*
+    sucht einen Algorithmus anhand seines Namens
+*/
+pub fn finden<T: Zahl>(name: &str) -> Option<&'static dyn Multiplikator<T>> {
+    alle().into_iter().find(|a| a.name() == name)
+}
@@ -0,0 +1,231 @@
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::rechenpool::{self, Ausführung};
+use crate::topologie;
+use crate::verteilung;
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
+use std::{
+    fmt, fs,
+    path::Path,
+    sync::{
+        OnceLock,
+        atomic::{AtomicPtr, Ordering},
+    },
+};
+
+// Größe einer Speicherseite, Seiten werden beim ersten Schreiben dem Knoten des schreibenden Threads zugeordnet
+const SEITE: usize = 4096;
+
+/*
+    NUMA Knoten aus /sys/devices/system/node/nodeN mit den ids der Prozessoren aus cpulist
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Knoten {
+    pub id: usize,
+    pub cpus: Vec<usize>,
+}
+
+/*
+    liest die Knoten aus einem Verzeichnis mit dem Aufbau von /sys/devices. Knoten ohne Prozessoren (nur Speicher)
+    werden übersprungen. Ohne NUMA Informationen gibt es einen einzigen Knoten 0 mit allen Prozessoren
+*/
+pub fn lesen_aus(wurzel: &Path) -> Vec<Knoten> {
+    let mut knoten: Vec<Knoten> = Vec::new();
+
+    if let Ok(einträge) = fs::read_dir(wurzel.join("system/node")) {
+        for eintrag in einträge.flatten() {
+            let name: String = eintrag.file_name().to_string_lossy().into_owned();
+            let Some(id) = name
+                .strip_prefix("node")
+                .and_then(|n| n.parse::<usize>().ok())
+            else {
+                continue;
+            };
+
+            let cpus: Vec<usize> = fs::read_to_string(eintrag.path().join("cpulist"))
+                .map(|text| topologie::liste_lesen(&text))
+                .unwrap_or_default();
+            if !cpus.is_empty() {
+                knoten.push(Knoten { id, cpus });
+            }
+        }
+    }
+
+    if knoten.is_empty() {
+        knoten.push(Knoten {
+            id: 0,
+            cpus: Vec::new(),
+        });
+    }
+    knoten.sort_by_key(|k| k.id);
+    knoten
+}
+
+static KNOTEN: OnceLock<Vec<Knoten>> = OnceLock::new();
+
+/*
+    die Knoten dieses Rechners, werden nur beim ersten Aufruf gelesen
+*/
+pub fn knoten() -> &'static [Knoten] {
+    KNOTEN.get_or_init(|| lesen_aus(Path::new("/sys/devices")))
+}
+
+/*
+    id des Knotens zu dem der Prozessor cpu gehört. Unbekannte Prozessoren gehören zum ersten Knoten
+*/
+pub fn knoten_von(knoten: &[Knoten], cpu: usize) -> usize {
+    knoten
+        .iter()
+        .find(|k| k.cpus.contains(&cpu))
+        .or(knoten.first())
+        .map_or(0, |k| k.id)
+}
+
+/*
+    legt fest welcher Thread die Speicherseiten einer neuen Matrix zuerst beschreibt. Linux legt eine Seite auf dem
+    Knoten des Threads an, der sie zuerst berührt
+
+    Hauptthread: alle Werte werden vom aufrufenden Thread geschrieben, alle Seiten landen auf einem Knoten
+    ErsteBerührung: Thread z schreibt die Zeilen verteilung::block(zeilen, threads, z), das sind die Zeilen die
+                    er bei StatischBlock, Stehlen und Knoten zuerst bearbeitet
+    Verschränkt: die Seiten werden reihum auf die Knoten der Threads verteilt, für Daten die alle Threads lesen
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Platzierung {
+    Hauptthread,
+    ErsteBerührung,
+    Verschränkt,
+}
+
+impl fmt::Display for Platzierung {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Platzierung::Hauptthread => write!(f, "hauptthread"),
+            Platzierung::ErsteBerührung => write!(f, "erste_berührung"),
+            Platzierung::Verschränkt => write!(f, "verschränkt"),
+        }
+    }
+}
+
+/*
+    erstellt eine zeilen x spalten Matrix mit den Werten wert(i, j). Bei ErsteBerührung und Verschränkt schreiben
+    die auf pinnen gepinnten Threads des gemeinsamen Rechenpools die Werte in den noch unberührten Speicher
+*/
+pub fn matrix<T, F>(
+    zeilen: usize,
+    spalten: usize,
+    pinnen: &[CoreId],
+    platzierung: Platzierung,
+    wert: F,
+) -> Result<Matrix<T>, Fehler>
+where
+    T: Zahl,
+    F: Fn(usize, usize) -> T + Sync,
+{
+    // zu große Matrizen werden als Fehler gemeldet statt mit einer panic oder einem Abbruch beim Reservieren
+    let zu_groß = || Fehler::Größe { zeilen, spalten };
+    let anzahl: usize = zeilen
+        .checked_mul(spalten)
+        .filter(|anzahl| {
+            anzahl
+                .checked_mul(size_of::<T>())
+                .is_some_and(|bytes| bytes <= isize::MAX as usize)
+        })
+        .ok_or_else(zu_groß)?;
+    let mut daten: Vec<T> = Vec::new();
+    daten.try_reserve_exact(anzahl).map_err(|_| zu_groß())?;
+
+    if platzierung == Platzierung::Hauptthread {
+        daten.extend((0..anzahl).map(|x| wert(x / spalten, x % spalten)));
+        return Ok(Matrix {
+            zeilen,
+            spalten,
+            stride: spalten,
+            daten,
+        });
+    }
+
+    let threads: usize = pinnen.len();
+    fehler::threads_prüfen(threads, pinnen)?;
+
+    // der Speicher ist reserviert aber noch nicht beschrieben
+    let zeiger: AtomicPtr<T> = AtomicPtr::new(daten.as_mut_ptr());
+
+    // Elemente je Seite und Elemente bis zur ersten Seitengrenze
+    let seite: usize = (SEITE / size_of::<T>().max(1)).max(1);
+    let versatz: usize =
+        ((SEITE - daten.as_ptr() as usize % SEITE) % SEITE / size_of::<T>().max(1)).min(anzahl);
+    let seiten: usize = 1 + (anzahl - versatz).div_ceil(seite);
+
+    // Knoten der Threads und die verschiedenen Knoten in der Reihenfolge ihres ersten Auftretens
+    let knoten_je_thread: Vec<usize> = pinnen
+        .iter()
+        .map(|kern| knoten_von(knoten(), kern.id))
+        .collect();
+    let mut verschiedene: Vec<usize> = Vec::new();
+    for &knoten in &knoten_je_thread {
+        if !verschiedene.contains(&knoten) {
+            verschiedene.push(knoten);
+        }
+    }
+
+    rechenpool::verteilen(threads, pinnen, Ausführung::Rechenpool, |z| {
+        let ziel: *mut T = zeiger.load(Ordering::Relaxed);
+
+        // jedes Element wird von genau einem Thread geschrieben
+        let schreiben = |von: usize, bis: usize| {
+            for x in von..bis {
+                unsafe { ziel.add(x).write(wert(x / spalten, x % spalten)) };
+            }
+        };
+
+        if platzierung == Platzierung::ErsteBerührung {
+            let bereich = verteilung::block(zeilen, threads, z);
+            schreiben(bereich.start * spalten, bereich.end * spalten);
+        } else {
+            // Seite s gehört zum Knoten verschiedene[s % Knoten], dort reihum zu den Threads dieses Knotens
+            let eigener: usize = knoten_je_thread[z];
+            let nachbarn: Vec<usize> = (0..threads)
+                .filter(|&t| knoten_je_thread[t] == eigener)
+                .collect();
+            let rang: usize = nachbarn.iter().position(|&t| t == z).unwrap_or(0);
+
+            for s in 0..seiten {
+                let knoten: usize = verschiedene[s % verschiedene.len()];
+                if knoten == eigener && (s / verschiedene.len()) % nachbarn.len() == rang {
+                    let von: usize = if s == 0 { 0 } else { versatz + (s - 1) * seite };
+                    let bis: usize = if s == 0 {
+                        versatz
+                    } else {
+                        (versatz + s * seite).min(anzahl)
+                    };
+                    schreiben(von, bis);
+                }
+            }
+        }
+        Ok(())
+    })?;
+
+    // SAFETY: verteilen kehrt erst nach allen Threads zurück und jedes der anzahl Elemente wurde geschrieben
+    unsafe { daten.set_len(anzahl) };
+
+    Ok(Matrix {
+        zeilen,
+        spalten,
+        stride: spalten,
+        daten,
+    })
+}
+
+/*
+    wie matrix::zufallsmatrix, aber die Werte werden nach platzierung von den Threads auf pinnen geschrieben
+*/
+pub fn zufallsmatrix<T: Zahl>(
+    zeilen: usize,
+    spalten: usize,
+    pinnen: &[CoreId],
+    platzierung: Platzierung,
+) -> Result<Matrix<T>, Fehler> {
+    matrix(zeilen, spalten, pinnen, platzierung, |_, _| T::zufall())
+}
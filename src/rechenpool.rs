@@ -0,0 +1,415 @@
+use crate::fehler::{self, Fehler};
+use crate::zaehler::{self, Werte};
+use core_affinity::CoreId;
+use std::{
+    fmt, mem,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Condvar, Mutex, PoisonError, mpsc},
+    thread,
+};
+
+/*
+    legt fest wie die manuellen Algorithmen ihre Threads bekommen
+
+    Spawnen: bei jedem Aufruf werden neue Threads gestartet und gepinnt
+    Rechenpool: die Threads eines gemeinsamen Rechenpools werden wiederverwendet und sind bereits gepinnt
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ausführung {
+    Spawnen,
+    Rechenpool,
+}
+
+impl fmt::Display for Ausführung {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ausführung::Spawnen => write!(f, "spawnen"),
+            Ausführung::Rechenpool => write!(f, "rechenpool"),
+        }
+    }
+}
+
+// Auftrag an einen Arbeiter
+type Auftrag = Box<dyn FnOnce() + Send + 'static>;
+
+/*
+    Threadpool dessen Arbeiter z einmal beim Erstellen auf kerne[z] gepinnt wird
+
+    Jeder Arbeiter hat eine eigene Warteschlange. Die Aufträge eines Aufrufs von verteilen werden unter einem
+    Mutex eingereiht, dadurch stehen sie in allen Warteschlangen in derselben Reihenfolge. Algorithmen die ihre
+    Threads mit einer Schranke synchronisieren (gepack) können sich so auch bei gleichzeitigen Aufrufen nicht
+    gegenseitig blockieren
+*/
+pub struct Rechenpool {
+    kerne: Vec<CoreId>,
+    warteschlangen: Mutex<Vec<mpsc::Sender<Auftrag>>>,
+    arbeiter: Mutex<Vec<thread::JoinHandle<()>>>,
+}
+
+impl Rechenpool {
+    /*
+        startet einen Arbeiter je Kern. Kann ein Arbeiter nicht gepinnt werden, wird der Pool wieder beendet
+    */
+    pub fn neu(kerne: Vec<CoreId>) -> Result<Rechenpool, Fehler> {
+        if kerne.is_empty() {
+            return Err(Fehler::KeineKerne);
+        }
+
+        let mut warteschlangen: Vec<mpsc::Sender<Auftrag>> = Vec::with_capacity(kerne.len());
+        let mut arbeiter: Vec<thread::JoinHandle<()>> = Vec::with_capacity(kerne.len());
+        let (bereit_sender, bereit) = mpsc::channel::<Result<(), Fehler>>();
+
+        for (z, &kern) in kerne.iter().enumerate() {
+            let (sender, empfänger) = mpsc::channel::<Auftrag>();
+            let bereit_sender: mpsc::Sender<Result<(), Fehler>> = bereit_sender.clone();
+
+            let handle: thread::JoinHandle<()> = thread::Builder::new()
+                .name(format!("rechenpool-{}", z))
+                .spawn(move || {
+                    let gepinnt: Result<(), Fehler> = fehler::pinnen(kern);
+                    let ok: bool = gepinnt.is_ok();
+                    let _ = bereit_sender.send(gepinnt);
+
+                    if ok {
+                        // läuft bis alle Sender der Warteschlange weg sind
+                        for auftrag in empfänger {
+                            auftrag();
+                        }
+                    }
+                })
+                .map_err(|f| Fehler::Threadpool(f.to_string()))?;
+
+            warteschlangen.push(sender);
+            arbeiter.push(handle);
+        }
+        drop(bereit_sender);
+
+        let pool: Rechenpool = Rechenpool {
+            kerne,
+            warteschlangen: Mutex::new(warteschlangen),
+            arbeiter: Mutex::new(arbeiter),
+        };
+
+        // erst zurückgeben, wenn alle Arbeiter gepinnt sind
+        for _ in 0..pool.kerne.len() {
+            bereit.recv().unwrap_or(Err(Fehler::ThreadPanik))?;
+        }
+        Ok(pool)
+    }
+
+    pub fn kerne(&self) -> &[CoreId] {
+        &self.kerne
+    }
+
+    /*
+        führt f(z) für z = 0..threads auf den Arbeitern 0..threads aus und wartet bis alle fertig sind. Wie bei
+        thread::scope darf f Daten des Aufrufers ausleihen. Gibt alle Ergebnisse oder den ersten Fehler zurück,
+        eine panic in f wird zu Fehler::ThreadPanik
+    */
+    pub fn verteilen<R, F>(&self, threads: usize, f: F) -> Result<Vec<R>, Fehler>
+    where
+        R: Send,
+        F: Fn(usize) -> Result<R, Fehler> + Sync,
+    {
+        fehler::threads_prüfen(threads, &self.kerne)?;
+
+        // Ergebnis von Arbeiter z
+        let plätze: Vec<Mutex<Option<Result<R, Fehler>>>> =
+            (0..threads).map(|_| Mutex::new(None)).collect();
+        let (fertig_sender, fertig) = mpsc::channel::<()>();
+        let mut eingereiht: usize = 0;
+
+        {
+            let warteschlangen = self
+                .warteschlangen
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if warteschlangen.is_empty() {
+                return Err(Fehler::Threadpool(
+                    "der Rechenpool wurde beendet".to_string(),
+                ));
+            }
+
+            for z in 0..threads {
+                let f: &F = &f;
+                let platz: &Mutex<Option<Result<R, Fehler>>> = &plätze[z];
+                let fertig_sender: mpsc::Sender<()> = fertig_sender.clone();
+
+                let auftrag: Box<dyn FnOnce() + Send + '_> = Box::new(move || {
+                    let ergebnis: Result<R, Fehler> =
+                        panic::catch_unwind(AssertUnwindSafe(|| f(z)))
+                            .unwrap_or(Err(Fehler::ThreadPanik));
+                    *platz.lock().unwrap_or_else(PoisonError::into_inner) = Some(ergebnis);
+                    let _ = fertig_sender.send(());
+                });
+
+                // SAFETY: der Auftrag leiht f und plätze nur aus. verteilen kehrt erst zurück, wenn jeder
+                // eingereihte Auftrag fertig gemeldet hat oder alle Sender von fertig verworfen wurden, d.h. jeder
+                // Auftrag ausgeführt oder ungenutzt gelöscht wurde. Danach greift kein Arbeiter mehr darauf zu
+                let auftrag: Auftrag =
+                    unsafe { mem::transmute::<Box<dyn FnOnce() + Send + '_>, Auftrag>(auftrag) };
+
+                // ein nicht eingereihter Auftrag wird hier sofort gelöscht
+                if warteschlangen[z].send(auftrag).is_ok() {
+                    eingereiht += 1;
+                }
+            }
+        }
+        drop(fertig_sender);
+
+        for _ in 0..eingereiht {
+            if fertig.recv().is_err() {
+                break;
+            }
+        }
+
+        // zuerst alle Ergebnisse einsammeln, dann den ersten Fehler zurückgeben
+        let mut ergebnisse: Vec<R> = Vec::with_capacity(threads);
+        let mut erster_fehler: Option<Fehler> = None;
+
+        for platz in plätze {
+            let ergebnis: Option<Result<R, Fehler>> =
+                platz.into_inner().unwrap_or_else(PoisonError::into_inner);
+            match ergebnis {
+                Some(Ok(wert)) => ergebnisse.push(wert),
+                Some(Err(f)) => {
+                    erster_fehler.get_or_insert(f);
+                }
+                // der Arbeiter existiert nicht mehr
+                None => {
+                    erster_fehler.get_or_insert(Fehler::ThreadPanik);
+                }
+            }
+        }
+
+        match erster_fehler {
+            Some(f) => Err(f),
+            None => Ok(ergebnisse),
+        }
+    }
+
+    /*
+        beendet alle Arbeiter nachdem die bereits eingereihten Aufträge fertig sind. Danach liefert verteilen
+        einen Fehler. Wird auch beim Löschen des Pools aufgerufen
+    */
+    pub fn beenden(&self) {
+        // ohne Sender beenden die Arbeiter ihre Schleife
+        self.warteschlangen
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+
+        let arbeiter: Vec<thread::JoinHandle<()>> =
+            mem::take(&mut *self.arbeiter.lock().unwrap_or_else(PoisonError::into_inner));
+        for handle in arbeiter {
+            // ein Arbeiter kann sich nicht selbst joinen
+            if handle.thread().id() != thread::current().id() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+impl Drop for Rechenpool {
+    fn drop(&mut self) {
+        self.beenden();
+    }
+}
+
+// gemeinsame Rechenpools aller Algorithmen. Das Erstellen ist teuer und soll nur einmal je Kernliste passieren
+type Pools = Vec<Arc<Rechenpool>>;
+static POOLS: Mutex<Pools> = Mutex::new(Vec::new());
+
+/*
+    liefert einen gemeinsamen Rechenpool, dessen Arbeiter z auf pinnen[z] gepinnt ist. Ein vorhandener Pool wird
+    auch verwendet, wenn pinnen nur der Anfang seiner Kernliste ist. Passen mehrere, wird der größte genommen,
+    damit der Anfang einer Kernliste unabhängig von der Reihenfolge der Aufrufe immer denselben Pool bekommt
+*/
+pub fn gemeinsam(pinnen: &[CoreId]) -> Result<Arc<Rechenpool>, Fehler> {
+    let mut pools = POOLS.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if let Some(pool) = pools
+        .iter()
+        .filter(|pool| pool.kerne().starts_with(pinnen))
+        .max_by_key(|pool| pool.kerne().len())
+    {
+        return Ok(Arc::clone(pool));
+    }
+
+    let pool: Arc<Rechenpool> = Arc::new(Rechenpool::neu(pinnen.to_vec())?);
+    pools.push(Arc::clone(&pool));
+    Ok(pool)
+}
+
+/*
+    beendet alle gemeinsamen Rechenpools, z.B. vor dem Ende des Programms. Ein späterer Aufruf von gemeinsam
+    erstellt wieder einen neuen Pool
+*/
+pub fn alle_beenden() {
+    let pools: Pools = mem::take(&mut *POOLS.lock().unwrap_or_else(PoisonError::into_inner));
+
+    for pool in pools {
+        pool.beenden();
+    }
+}
+
+/*
+    führt f(z) für z = 0..threads auf Threads aus, die auf pinnen[z] gepinnt sind, und gibt alle Ergebnisse oder
+    den ersten Fehler zurück. Gemeinsame Grundlage aller manuellen Algorithmen
+
+    Beim Spawnen wird f auch dann ausgeführt, wenn das pinnen fehlschlägt, der Fehler wird erst danach
+    zurückgegeben. So warten Threads an einer Schranke nie auf einen Thread der gar nicht rechnet
+
+    Läuft der Aufrufer in zaehler::messen, zählt jeder Thread die Hardware Zähler während f(z)
+*/
+pub fn verteilen<R, F>(
+    threads: usize,
+    pinnen: &[CoreId],
+    ausführung: Ausführung,
+    f: F,
+) -> Result<Vec<R>, Fehler>
+where
+    R: Send,
+    F: Fn(usize) -> Result<R, Fehler> + Sync,
+{
+    if !zaehler::aktiv() {
+        return ausführen(threads, pinnen, ausführung, f);
+    }
+
+    let gezählt: Vec<(R, Result<Werte, Fehler>)> = ausführen(threads, pinnen, ausführung, |z| {
+        let (ergebnis, werte): (Result<R, Fehler>, Result<Werte, Fehler>) =
+            zaehler::zählen(|| f(z));
+        ergebnis.map(|ergebnis| (ergebnis, werte))
+    })?;
+    Ok(gezählt
+        .into_iter()
+        .enumerate()
+        .map(|(z, (ergebnis, werte))| {
+            zaehler::erfassen(z, werte);
+            ergebnis
+        })
+        .collect())
+}
+
+fn ausführen<R, F>(
+    threads: usize,
+    pinnen: &[CoreId],
+    ausführung: Ausführung,
+    f: F,
+) -> Result<Vec<R>, Fehler>
+where
+    R: Send,
+    F: Fn(usize) -> Result<R, Fehler> + Sync,
+{
+    fehler::threads_prüfen(threads, pinnen)?;
+
+    match ausführung {
+        Ausführung::Rechenpool => gemeinsam(&pinnen[..threads])?.verteilen(threads, f),
+        Ausführung::Spawnen => thread::scope(|s| {
+            let f: &F = &f;
+
+            // Thread Handles fürs joinen sammeln
+            let sammeln: Vec<thread::ScopedJoinHandle<'_, Result<R, Fehler>>> = pinnen[..threads]
+                .iter()
+                .enumerate()
+                .map(|(z, &kern)| {
+                    s.spawn(move || {
+                        let gepinnt: Result<(), Fehler> = fehler::pinnen(kern);
+                        let ergebnis: Result<R, Fehler> = f(z);
+                        gepinnt.and(ergebnis)
+                    })
+                })
+                .collect();
+
+            // zuerst alle Threads joinen, damit bei einem Fehler kein Thread mehr läuft
+            let rückgaben: Vec<Result<R, Fehler>> =
+                sammeln.into_iter().map(fehler::joinen).collect();
+            rückgaben.into_iter().collect()
+        }),
+    }
+}
+
+/*
+    Barriere für die Threads von verteilen, die abgebrochen werden kann. Endet ein Thread mit einer panic bevor
+    er die Barriere erreicht, würden die anderen mit std::sync::Barrier für immer warten. Hier weckt der Wächter
+    des Threads alle wartenden Threads und warten gibt Fehler::ThreadPanik zurück
+*/
+pub struct Schranke {
+    threads: usize,
+    zustand: Mutex<Zustand>,
+    bedingung: Condvar,
+}
+
+struct Zustand {
+    // Threads die im aktuellen Durchgang warten
+    wartend: usize,
+    durchgang: usize,
+    abgebrochen: bool,
+}
+
+impl Schranke {
+    pub fn neu(threads: usize) -> Schranke {
+        Schranke {
+            threads,
+            zustand: Mutex::new(Zustand {
+                wartend: 0,
+                durchgang: 0,
+                abgebrochen: false,
+            }),
+            bedingung: Condvar::new(),
+        }
+    }
+
+    // wartet bis alle Threads angekommen sind oder die Schranke abgebrochen wurde
+    pub fn warten(&self) -> Result<(), Fehler> {
+        let mut zustand = self.zustand.lock().unwrap_or_else(PoisonError::into_inner);
+        if zustand.abgebrochen {
+            return Err(Fehler::ThreadPanik);
+        }
+
+        let durchgang: usize = zustand.durchgang;
+        zustand.wartend += 1;
+        if zustand.wartend == self.threads {
+            zustand.wartend = 0;
+            zustand.durchgang += 1;
+            self.bedingung.notify_all();
+            return Ok(());
+        }
+
+        // ein abgeschlossener Durchgang zählt auch, wenn danach abgebrochen wurde
+        while zustand.durchgang == durchgang && !zustand.abgebrochen {
+            zustand = self
+                .bedingung
+                .wait(zustand)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        if zustand.durchgang == durchgang {
+            return Err(Fehler::ThreadPanik);
+        }
+        Ok(())
+    }
+
+    // alle wartenden und später ankommenden Threads bekommen einen Fehler
+    pub fn abbrechen(&self) {
+        self.zustand
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .abgebrochen = true;
+        self.bedingung.notify_all();
+    }
+
+    // bricht die Schranke ab, wenn der Thread mit einer panic endet solange der Wächter lebt
+    pub fn wächter(&self) -> Wächter<'_> {
+        Wächter(self)
+    }
+}
+
+pub struct Wächter<'a>(&'a Schranke);
+
+impl Drop for Wächter<'_> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.abbrechen();
+        }
+    }
+}
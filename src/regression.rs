@@ -0,0 +1,451 @@
+use crate::fehler::Fehler;
+use std::{f64::consts::PI, fmt, fs, io, path::Path, process::Command, time::Duration};
+
+// Standarddatei der gespeicherten Ergebnisse
+pub const DATEI: &str = "ergebnisse.tsv";
+
+const KOPF: &str = "basislinie\talgorithmus\tm\tk\tn\tthreads\tprozessor\tcommit\tzeiten_ns";
+
+/*
+    alle Messungen einer Konfiguration unter dem Namen einer Basislinie. Die Zeiten werden einzeln gespeichert,
+    damit ein Vergleich ihre Streuung kennt
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Eintrag {
+    pub basislinie: String,
+    pub algorithmus: String,
+    pub m: usize,
+    pub k: usize,
+    pub n: usize,
+    pub threads: usize,
+    pub prozessor: String,
+    pub commit: String,
+    pub zeiten: Vec<Duration>,
+}
+
+impl Eintrag {
+    // gleicher Algorithmus mit gleicher Größe und Anzahl an Threads auf demselben Prozessormodell
+    pub fn gleiche_konfiguration(&self, anderer: &Eintrag) -> bool {
+        self.algorithmus == anderer.algorithmus
+            && (self.m, self.k, self.n, self.threads)
+                == (anderer.m, anderer.k, anderer.n, anderer.threads)
+            && self.prozessor == anderer.prozessor
+    }
+
+    // Mittelwert und Stichprobenvarianz der Zeiten in Sekunden
+    fn statistik(&self) -> (f64, f64) {
+        let anzahl: f64 = self.zeiten.len() as f64;
+        let mittel: f64 = self.zeiten.iter().map(Duration::as_secs_f64).sum::<f64>() / anzahl;
+        let varianz: f64 = self
+            .zeiten
+            .iter()
+            .map(|zeit| (zeit.as_secs_f64() - mittel).powi(2))
+            .sum::<f64>()
+            / (anzahl - 1.0);
+        (mittel, varianz)
+    }
+}
+
+/*
+    kurzer Hash des aktuellen git Commits, "unbekannt" außerhalb eines Repositories oder ohne git
+*/
+pub fn commit() -> String {
+    Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|ausgabe| ausgabe.status.success())
+        .map(|ausgabe| String::from_utf8_lossy(&ausgabe.stdout).trim().to_string())
+        .unwrap_or_else(|| "unbekannt".to_string())
+}
+
+/*
+    liest alle Einträge einer Ergebnisdatei, eine fehlende Datei enthält keine Einträge
+*/
+pub fn laden(pfad: &Path) -> Result<Vec<Eintrag>, Fehler> {
+    match fs::read_to_string(pfad) {
+        Ok(text) => lesen(&text),
+        Err(f) if f.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(f) => Err(Fehler::Datei(format!("{}: {}", pfad.display(), f))),
+    }
+}
+
+/*
+    fügt einträge der Ergebnisdatei hinzu. Einträge derselben Basislinie mit gleicher Konfiguration werden
+    ersetzt, so kann eine Basislinie neu gemessen werden
+*/
+pub fn speichern(pfad: &Path, einträge: &[Eintrag]) -> Result<(), Fehler> {
+    // wie sie nach dem Schreiben gelesen werden, sonst würden Einträge mit Tabulatoren nie ersetzt
+    let einträge: Vec<Eintrag> = lesen(&text(einträge))?;
+    let mut alle: Vec<Eintrag> = laden(pfad)?;
+    alle.retain(|alt| {
+        !einträge
+            .iter()
+            .any(|neu| neu.basislinie == alt.basislinie && neu.gleiche_konfiguration(alt))
+    });
+    alle.extend(einträge);
+
+    fs::write(pfad, text(&alle)).map_err(|f| Fehler::Datei(format!("{}: {}", pfad.display(), f)))
+}
+
+/*
+    Ergebnisdatei als Text: Kopfzeile und eine durch Tabulatoren getrennte Zeile je Eintrag, die Zeiten in
+    Nanosekunden durch Leerzeichen getrennt. Tabulatoren und Zeilenumbrüche in Namen werden zu Leerzeichen
+*/
+pub fn text(einträge: &[Eintrag]) -> String {
+    let feld = |wert: &str| wert.replace(['\t', '\n', '\r'], " ");
+    let mut text: String = format!("{}\n", KOPF);
+
+    for eintrag in einträge {
+        let zeiten: Vec<String> = eintrag
+            .zeiten
+            .iter()
+            .map(|zeit| zeit.as_nanos().to_string())
+            .collect();
+        text += &format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            feld(&eintrag.basislinie),
+            feld(&eintrag.algorithmus),
+            eintrag.m,
+            eintrag.k,
+            eintrag.n,
+            eintrag.threads,
+            feld(&eintrag.prozessor),
+            feld(&eintrag.commit),
+            zeiten.join(" ")
+        );
+    }
+    text
+}
+
+pub fn lesen(text: &str) -> Result<Vec<Eintrag>, Fehler> {
+    let fehler = |nummer: usize, grund: &str| Fehler::Dateiformat {
+        format: "Ergebnisse",
+        grund: format!("Zeile {}: {}", nummer + 1, grund),
+    };
+    let mut zeilen = text.lines().enumerate();
+
+    match zeilen.next() {
+        Some((_, KOPF)) => {}
+        _ => return Err(fehler(0, "unbekannte Kopfzeile")),
+    }
+
+    let mut einträge: Vec<Eintrag> = Vec::new();
+    for (nummer, zeile) in zeilen.filter(|(_, zeile)| !zeile.is_empty()) {
+        let felder: Vec<&str> = zeile.split('\t').collect();
+        let &[
+            basislinie,
+            algorithmus,
+            m,
+            k,
+            n,
+            threads,
+            prozessor,
+            commit,
+            zeiten,
+        ] = felder.as_slice()
+        else {
+            return Err(fehler(nummer, "erwartet 9 Felder"));
+        };
+        let zahl = |wert: &str| -> Result<usize, Fehler> {
+            wert.parse()
+                .map_err(|_| fehler(nummer, &format!("keine Zahl: {}", wert)))
+        };
+        let zeiten: Vec<Duration> = zeiten
+            .split_whitespace()
+            .map(|zeit| {
+                zeit.parse()
+                    .map(Duration::from_nanos)
+                    .map_err(|_| fehler(nummer, &format!("keine Zeit: {}", zeit)))
+            })
+            .collect::<Result<_, _>>()?;
+        if zeiten.is_empty() {
+            return Err(fehler(nummer, "keine Zeiten"));
+        }
+
+        einträge.push(Eintrag {
+            basislinie: basislinie.to_string(),
+            algorithmus: algorithmus.to_string(),
+            m: zahl(m)?,
+            k: zahl(k)?,
+            n: zahl(n)?,
+            threads: zahl(threads)?,
+            prozessor: prozessor.to_string(),
+            commit: commit.to_string(),
+            zeiten,
+        });
+    }
+    Ok(einträge)
+}
+
+/*
+    Ergebnis des Vergleichs einer Konfiguration mit der Basislinie
+
+    Schneller: signifikant schneller
+    Unverändert: kein signifikanter Unterschied oder weniger als 2 Zeiten in einer der Messungen
+    Langsamer: signifikant langsamer, aber höchstens um die Schwelle
+    Regression: signifikant und um mehr als die Schwelle langsamer
+    OhneBasis: die Basislinie enthält diese Konfiguration nicht
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Urteil {
+    Schneller,
+    Unverändert,
+    Langsamer,
+    Regression,
+    OhneBasis,
+}
+
+impl fmt::Display for Urteil {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Urteil::Schneller => write!(f, "schneller"),
+            Urteil::Unverändert => write!(f, "unverändert"),
+            Urteil::Langsamer => write!(f, "langsamer"),
+            Urteil::Regression => write!(f, "REGRESSION"),
+            Urteil::OhneBasis => write!(f, "ohne Basislinie"),
+        }
+    }
+}
+
+/*
+    Vergleich einer neuen Messung mit der Basislinie. änderung ist mittel neu / mittel basis - 1, p der
+    einseitige p-Wert des t-Tests nach Welch dafür, dass neu langsamer ist
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vergleich {
+    pub neu: Eintrag,
+    pub basis: Option<Eintrag>,
+    pub änderung: Option<f64>,
+    pub p: Option<f64>,
+    pub urteil: Urteil,
+}
+
+/*
+    vergleicht jeden Eintrag aus neu mit dem Eintrag gleicher Konfiguration der Basislinie namens basislinie.
+    Ein Unterschied ist signifikant, wenn sein p-Wert unter signifikanz liegt (z.B. 0.05). Eine Regression muss
+    außerdem um mehr als schwelle (z.B. 0.05 für 5 %) langsamer sein
+*/
+pub fn vergleichen(
+    gespeichert: &[Eintrag],
+    basislinie: &str,
+    neu: &[Eintrag],
+    schwelle: f64,
+    signifikanz: f64,
+) -> Vec<Vergleich> {
+    neu.iter()
+        .map(|neu| {
+            let Some(basis) = gespeichert
+                .iter()
+                .find(|basis| basis.basislinie == basislinie && basis.gleiche_konfiguration(neu))
+            else {
+                return Vergleich {
+                    neu: neu.clone(),
+                    basis: None,
+                    änderung: None,
+                    p: None,
+                    urteil: Urteil::OhneBasis,
+                };
+            };
+
+            let (mittel_basis, varianz_basis): (f64, f64) = basis.statistik();
+            let (mittel_neu, varianz_neu): (f64, f64) = neu.statistik();
+            let änderung: f64 = mittel_neu / mittel_basis - 1.0;
+            let p: Option<f64> = (basis.zeiten.len() >= 2 && neu.zeiten.len() >= 2).then(|| {
+                welch(
+                    (mittel_neu, varianz_neu, neu.zeiten.len()),
+                    (mittel_basis, varianz_basis, basis.zeiten.len()),
+                )
+            });
+
+            let urteil: Urteil = match p {
+                Some(p) if p < signifikanz && änderung > schwelle => Urteil::Regression,
+                Some(p) if p < signifikanz => Urteil::Langsamer,
+                Some(p) if 1.0 - p < signifikanz => Urteil::Schneller,
+                _ => Urteil::Unverändert,
+            };
+            Vergleich {
+                neu: neu.clone(),
+                basis: Some(basis.clone()),
+                änderung: Some(änderung),
+                p,
+                urteil,
+            }
+        })
+        .collect()
+}
+
+/*
+    einseitiger t-Test nach Welch für (Mittelwert, Varianz, Anzahl) zweier Stichproben: Wahrscheinlichkeit
+    eines mindestens so großen Unterschieds x - y, wenn beide denselben Mittelwert haben
+*/
+pub fn welch(x: (f64, f64, usize), y: (f64, f64, usize)) -> f64 {
+    let (mittel_x, varianz_x, anzahl_x) = x;
+    let (mittel_y, varianz_y, anzahl_y) = y;
+    let fehler_x: f64 = varianz_x / anzahl_x as f64;
+    let fehler_y: f64 = varianz_y / anzahl_y as f64;
+    let differenz: f64 = mittel_x - mittel_y;
+
+    // ohne Streuung ist jeder Unterschied sicher
+    if fehler_x + fehler_y == 0.0 {
+        return match differenz.partial_cmp(&0.0) {
+            Some(std::cmp::Ordering::Greater) => 0.0,
+            Some(std::cmp::Ordering::Less) => 1.0,
+            _ => 0.5,
+        };
+    }
+
+    let t: f64 = differenz / (fehler_x + fehler_y).sqrt();
+    let freiheitsgrade: f64 = (fehler_x + fehler_y).powi(2)
+        / (fehler_x.powi(2) / (anzahl_x - 1) as f64 + fehler_y.powi(2) / (anzahl_y - 1) as f64);
+    student_t_oben(t, freiheitsgrade)
+}
+
+/*
+    P(T > t) der t-Verteilung mit freiheitsgrade Freiheitsgraden über die regularisierte unvollständige
+    Betafunktion
+*/
+pub fn student_t_oben(t: f64, freiheitsgrade: f64) -> f64 {
+    let hälfte: f64 = 0.5
+        * beta_regularisiert(
+            freiheitsgrade / 2.0,
+            0.5,
+            freiheitsgrade / (freiheitsgrade + t * t),
+        );
+    if t > 0.0 { hälfte } else { 1.0 - hälfte }
+}
+
+// ln(Gamma(x)) nach Lanczos mit g = 7
+fn ln_gamma(x: f64) -> f64 {
+    const KOEFFIZIENTEN: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Spiegelung
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x: f64 = x - 1.0;
+    let summe: f64 = KOEFFIZIENTEN[1..]
+        .iter()
+        .enumerate()
+        .fold(KOEFFIZIENTEN[0], |summe, (i, &c)| {
+            summe + c / (x + i as f64 + 1.0)
+        });
+    let t: f64 = x + 7.5;
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + summe.ln()
+}
+
+// I_x(a, b), der Kettenbruch konvergiert für x < (a + 1) / (a + b + 2), sonst über I_x(a, b) = 1 - I_1-x(b, a)
+fn beta_regularisiert(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let vorfaktor: f64 =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        vorfaktor * kettenbruch(a, b, x) / a
+    } else {
+        1.0 - vorfaktor * kettenbruch(b, a, 1.0 - x) / b
+    }
+}
+
+// Kettenbruch der unvollständigen Betafunktion mit dem Verfahren von Lentz
+fn kettenbruch(a: f64, b: f64, x: f64) -> f64 {
+    const KLEIN: f64 = 1e-300;
+    let begrenzen = |wert: f64| if wert.abs() < KLEIN { KLEIN } else { wert };
+
+    let mut c: f64 = 1.0;
+    let mut d: f64 = 1.0 / begrenzen(1.0 - (a + b) * x / (a + 1.0));
+    let mut ergebnis: f64 = d;
+    for schritt in 1..300 {
+        let m: f64 = schritt as f64;
+        // gerader und ungerader Schritt
+        for zähler in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 / begrenzen(1.0 + zähler * d);
+            c = begrenzen(1.0 + zähler / c);
+            ergebnis *= d * c;
+        }
+        if (d * c - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    ergebnis
+}
+
+/*
+    lesbarer Bericht aller Vergleiche mit einer Zeile je Konfiguration und einer Zusammenfassung
+*/
+pub fn bericht(vergleiche: &[Vergleich], basislinie: &str, schwelle: f64) -> String {
+    let breite: usize = vergleiche
+        .iter()
+        .map(|vergleich| vergleich.neu.algorithmus.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("algorithmus".len());
+    let millisekunden = |eintrag: &Eintrag| eintrag.statistik().0 * 1e3;
+
+    let mut text: String = format!(
+        "Vergleich mit Basislinie {} (Schwelle {} %)\n",
+        basislinie,
+        schwelle * 100.0
+    );
+    text += &format!(
+        "{:<breite$} {:>16} {:>7} {:>12} {:>12} {:>9} {:>8}  urteil\n",
+        "algorithmus", "m x k x n", "threads", "basis [ms]", "neu [ms]", "änderung", "p"
+    );
+    for vergleich in vergleiche {
+        let neu: &Eintrag = &vergleich.neu;
+        text += &format!(
+            "{:<breite$} {:>16} {:>7} {:>12} {:>12.3} {:>9} {:>8}  {}\n",
+            neu.algorithmus,
+            format!("{}x{}x{}", neu.m, neu.k, neu.n),
+            neu.threads,
+            vergleich
+                .basis
+                .as_ref()
+                .map_or("-".to_string(), |basis| format!(
+                    "{:.3}",
+                    millisekunden(basis)
+                )),
+            millisekunden(neu),
+            vergleich
+                .änderung
+                .map_or("-".to_string(), |änderung| format!(
+                    "{:+.1} %",
+                    änderung * 100.0
+                )),
+            vergleich.p.map_or("-".to_string(), |p| format!("{:.4}", p)),
+            vergleich.urteil
+        );
+    }
+
+    let regressionen: usize = vergleiche
+        .iter()
+        .filter(|vergleich| vergleich.urteil == Urteil::Regression)
+        .count();
+    if regressionen == 0 {
+        text += "keine Regression\n";
+    } else {
+        text += &format!(
+            "{} von {} Konfigurationen mit Regression\n",
+            regressionen,
+            vergleiche.len()
+        );
+    }
+    text
+}
@@ -0,0 +1,479 @@
+use crate::algorithmen::gepack::Blockgrößen;
+use crate::auswertung::{self, Ergebnis};
+use crate::cpu::{self, SimdPfad};
+use crate::fehler::Fehler;
+use crate::rechenpool::{self, Ausführung};
+use crate::topologie;
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
+use std::{
+    hint::black_box,
+    sync::Barrier,
+    time::{Duration, Instant},
+};
+
+// unabhängige Ketten von fused multiply-add, damit die Latenz der Befehle nicht die Leistung begrenzt
+const KETTEN: usize = 12;
+
+// Runden der Ketten je Thread für Maschine::messen, bei avx512 etwa 10^9 Gleitkommaoperationen
+pub const RUNDEN: usize = 1 << 22;
+
+// Messungen der Mikrobenchmarks, die schnellste zählt
+const WIEDERHOLUNGEN: usize = 5;
+
+// Cache Größe, wenn sysfs keine meldet
+const CACHE: usize = 32 << 20;
+
+/*
+    gemessene Grenzen eines Rechners für threads Threads: spitze in FLOP/s für f64 mit dem simd Pfad pfad und
+    bandbreite des Hauptspeichers in Bytes/s
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Maschine {
+    pub spitze: f64,
+    pub bandbreite: f64,
+    pub threads: usize,
+    pub pfad: SimdPfad,
+}
+
+impl Maschine {
+    /*
+        misst beide Grenzen mit den Threads auf pinnen[..threads]. Die Bandbreite wird mit dem Vierfachen des
+        größten Caches gemessen (64 MiB bis 1 GiB), damit die Daten aus dem Hauptspeicher kommen
+    */
+    pub fn messen(pinnen: &[CoreId], threads: usize) -> Result<Maschine, Fehler> {
+        let bytes: usize = (4 * cache()).clamp(64 << 20, 1 << 30);
+        Ok(Maschine {
+            spitze: spitze_messen(pinnen, threads, RUNDEN)?,
+            bandbreite: bandbreite_messen(pinnen, threads, bytes)?,
+            threads,
+            pfad: cpu::pfad(),
+        })
+    }
+
+    // arithmetische Intensität in FLOP/Byte, ab der die Rechenleistung statt der Bandbreite begrenzt
+    pub fn knick(&self) -> f64 {
+        self.spitze / self.bandbreite
+    }
+
+    // erreichbare FLOP/s bei der arithmetischen Intensität intensität
+    pub fn dach(&self, intensität: f64) -> f64 {
+        self.spitze.min(self.bandbreite * intensität)
+    }
+}
+
+/*
+    Spitzenleistung in FLOP/s: jeder Thread rechnet runden mal KETTEN unabhängige Register mit fused multiply-add
+    (ohne FMA Multiplikation und Addition) und dem simd Pfad von cpu::pfad()
+*/
+pub fn spitze_messen(pinnen: &[CoreId], threads: usize, runden: usize) -> Result<f64, Fehler> {
+    let pfad: SimdPfad = cpu::pfad();
+    let operationen: f64 = (2 * KETTEN * pfad.breite() * runden * threads) as f64;
+
+    let zeit: Duration = parallel_messen(
+        pinnen,
+        threads,
+        |_| (),
+        |_| {
+            let start: Instant = Instant::now();
+            black_box(ketten(pfad, black_box(runden)));
+            start.elapsed()
+        },
+    )?;
+    Ok(operationen / zeit.as_secs_f64())
+}
+
+/*
+    Bandbreite in Bytes/s mit der Triade von STREAM a[i] = b[i] + s * c[i] über insgesamt etwa bytes Bytes.
+    Jeder Thread legt seine Felder nach dem pinnen selbst an, sie liegen so auf seinem NUMA Knoten. Gezählt
+    werden wie bei STREAM 24 Bytes je Element
+*/
+pub fn bandbreite_messen(pinnen: &[CoreId], threads: usize, bytes: usize) -> Result<f64, Fehler> {
+    let länge: usize = (bytes / 3 / size_of::<f64>() / threads.max(1)).max(1024);
+
+    let zeit: Duration = parallel_messen(
+        pinnen,
+        threads,
+        |_| (vec![0.0; länge], vec![1.0; länge], vec![2.0; länge]),
+        |(a, b, c): &mut (Vec<f64>, Vec<f64>, Vec<f64>)| {
+            let faktor: f64 = black_box(3.0);
+
+            let start: Instant = Instant::now();
+            for ((a, &b), &c) in a.iter_mut().zip(b.iter()).zip(c.iter()) {
+                *a = b + faktor * c;
+            }
+            black_box(a);
+            start.elapsed()
+        },
+    )?;
+    Ok((3 * size_of::<f64>() * länge * threads) as f64 / zeit.as_secs_f64())
+}
+
+/*
+    jeder Thread bereitet mit vorbereiten(z) seine Daten vor, danach wird messen WIEDERHOLUNGEN mal auf allen
+    Threads gleichzeitig ausgeführt. Zurück kommt die kürzeste Zeit, in der alle Threads fertig waren
+*/
+fn parallel_messen<D, V, F>(
+    pinnen: &[CoreId],
+    threads: usize,
+    vorbereiten: V,
+    messen: F,
+) -> Result<Duration, Fehler>
+where
+    V: Fn(usize) -> D + Sync,
+    F: Fn(&mut D) -> Duration + Sync,
+{
+    let start: Barrier = Barrier::new(threads);
+
+    let zeiten: Vec<Vec<Duration>> =
+        rechenpool::verteilen(threads, pinnen, Ausführung::Spawnen, |z| {
+            let mut daten: D = vorbereiten(z);
+            Ok((0..WIEDERHOLUNGEN)
+                .map(|_| {
+                    start.wait();
+                    messen(&mut daten)
+                })
+                .collect())
+        })?;
+
+    Ok((0..WIEDERHOLUNGEN)
+        .map(|w| {
+            zeiten
+                .iter()
+                .map(|zeiten| zeiten[w])
+                .max()
+                .unwrap_or_default()
+        })
+        .min()
+        .unwrap_or_default())
+}
+
+/*
+    Ketten mit der Registerbreite des Pfads, die Varianten mit avx werden wie bei simd mit den entsprechenden
+    Prozessorfeatures übersetzt. Gibt die Summe aller Register zurück, damit nichts wegoptimiert wird
+*/
+fn ketten(pfad: SimdPfad, runden: usize) -> f64 {
+    match pfad {
+        // cpu::pfad() liefert nur vom Prozessor unterstützte Pfade
+        #[cfg(target_arch = "x86_64")]
+        SimdPfad::Avx2Fma => unsafe { ketten_avx2(runden) },
+        #[cfg(target_arch = "x86_64")]
+        SimdPfad::Avx512 => unsafe { ketten_avx512(runden) },
+        _ => ketten_allgemein::<2, false>(runden),
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,fma")]
+fn ketten_avx2(runden: usize) -> f64 {
+    ketten_allgemein::<4, true>(runden)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f,fma")]
+fn ketten_avx512(runden: usize) -> f64 {
+    ketten_allgemein::<8, true>(runden)
+}
+
+#[inline(always)]
+fn ketten_allgemein<const L: usize, const FMA: bool>(runden: usize) -> f64 {
+    let mut summen: [<f64 as Zahl>::Vektor<L>; KETTEN] = [f64::splat(1.0); KETTEN];
+    let faktor: <f64 as Zahl>::Vektor<L> = f64::splat(black_box(0.999_999_9));
+    let summand: <f64 as Zahl>::Vektor<L> = f64::splat(black_box(1e-7));
+
+    for _ in 0..runden {
+        for summe in summen.iter_mut() {
+            *summe = if FMA {
+                f64::vektor_mul_add(*summe, faktor, summand)
+            } else {
+                *summe * faktor + summand
+            };
+        }
+    }
+
+    let mut werte: [f64; L] = [0.0; L];
+    let mut ergebnis: f64 = 0.0;
+    for summe in summen {
+        f64::speichern(summe, &mut werte);
+        ergebnis += werte.iter().sum::<f64>();
+    }
+    ergebnis
+}
+
+/*
+    Zugriffsmuster eines Algorithmus auf den Hauptspeicher, daraus folgt die übertragene Datenmenge
+
+    Zeilenweise: jede Zeile von c liest eine Zeile von a und ganz b, so arbeiten single, simd, tiling,
+                 simd_tiling und die anderen zeilenweisen Algorithmen (tiling blockt nur innerhalb der Zeile).
+                 Passt b in den Cache, wird es nur einmal gelesen
+    Blöcke: wie gepack nach dem BLIS Schema, b wird einmal gelesen, a einmal je nc Spalten und c einmal je kc
+            Summanden gelesen und geschrieben
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Zugriff {
+    Zeilenweise,
+    Blöcke { mc: usize, kc: usize, nc: usize },
+}
+
+impl Zugriff {
+    // Zugriffsmuster der registrierten Algorithmen, Strassen und Winograd werden als zeilenweise genähert
+    pub fn von(algorithmus: &str) -> Zugriff {
+        match algorithmus {
+            "gepack" => {
+                let blöcke: Blockgrößen = Blockgrößen::default();
+                Zugriff::Blöcke {
+                    mc: blöcke.mc,
+                    kc: blöcke.kc,
+                    nc: blöcke.nc,
+                }
+            }
+            _ => Zugriff::Zeilenweise,
+        }
+    }
+
+    /*
+        Bytes aus dem und in den Hauptspeicher für c = a * b mit f64 und einem Cache von cache Bytes. Passen
+        alle drei Matrizen in den Cache, wird jede nur einmal übertragen
+    */
+    pub fn bytes(self, m: usize, k: usize, n: usize, cache: usize) -> f64 {
+        let (m, k, n): (f64, f64, f64) = (m as f64, k as f64, n as f64);
+        let wert: f64 = size_of::<f64>() as f64;
+        let einmal: f64 = m * k + k * n + m * n;
+
+        if einmal * wert <= cache as f64 {
+            return einmal * wert;
+        }
+        let werte: f64 = match self {
+            Zugriff::Zeilenweise if k * n * wert <= cache as f64 => einmal,
+            Zugriff::Zeilenweise => m * k + m * k * n + m * n,
+            Zugriff::Blöcke { kc, nc, .. } => {
+                m * k * (n / nc as f64).ceil() + k * n + 2.0 * m * n * (k / kc as f64).ceil()
+            }
+        };
+        werte * wert
+    }
+
+    // arithmetische Intensität in FLOP/Byte
+    pub fn intensität(self, m: usize, k: usize, n: usize, cache: usize) -> f64 {
+        auswertung::operationen(m, k, n) as f64 / self.bytes(m, k, n, cache)
+    }
+}
+
+/*
+    ein gemessener Benchmark im Roofline Modell, leistung in FLOP/s
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Punkt {
+    pub ergebnis: Ergebnis,
+    pub intensität: f64,
+    pub leistung: f64,
+}
+
+/*
+    Punkte aller Ergebnisse mit der Intensität aus dem Zugriffsmuster ihres Algorithmus
+*/
+pub fn punkte(ergebnisse: &[Ergebnis], cache: usize) -> Vec<Punkt> {
+    ergebnisse
+        .iter()
+        .map(|ergebnis| Punkt {
+            ergebnis: ergebnis.clone(),
+            intensität: Zugriff::von(&ergebnis.algorithmus)
+                .intensität(ergebnis.m, ergebnis.k, ergebnis.n, cache),
+            leistung: ergebnis.gflops() * 1e9,
+        })
+        .collect()
+}
+
+// Größe des größten Caches für punkte, ohne Angabe in sysfs 32 MiB
+pub fn cache() -> usize {
+    topologie::größter_cache().unwrap_or(CACHE)
+}
+
+/*
+    CSV mit einem Punkt je Zeile: Intensität, erreichte und erreichbare GFLOP/s, der Anteil davon und ob der
+    Punkt links vom Knick (speicher) oder rechts davon (rechnen) liegt. Die Grenzen der Maschine stehen in jeder
+    Zeile, damit die Datei für sich allein ausgewertet werden kann
+*/
+pub fn csv(maschine: &Maschine, punkte: &[Punkt]) -> String {
+    let mut text: String = String::from(
+        "algorithmus,threads,m,k,n,intensität_flop_pro_byte,gflops,dach_gflops,anteil,begrenzt,\
+         spitze_gflops,bandbreite_gbs\n",
+    );
+
+    for punkt in punkte {
+        let dach: f64 = maschine.dach(punkt.intensität);
+        text += &format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            punkt.ergebnis.algorithmus,
+            punkt.ergebnis.threads,
+            punkt.ergebnis.m,
+            punkt.ergebnis.k,
+            punkt.ergebnis.n,
+            punkt.intensität,
+            punkt.leistung / 1e9,
+            dach / 1e9,
+            punkt.leistung / dach,
+            if punkt.intensität < maschine.knick() {
+                "speicher"
+            } else {
+                "rechnen"
+            },
+            maschine.spitze / 1e9,
+            maschine.bandbreite / 1e9
+        );
+    }
+    text
+}
+
+// Farben der Algorithmen im Diagramm, danach wiederholt
+const FARBEN: [&str; 8] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+];
+
+/*
+    Roofline Diagramm als SVG mit logarithmischen Achsen: das Dach der Maschine und alle Punkte, eine Farbe je
+    Algorithmus
+*/
+pub fn svg(maschine: &Maschine, punkte: &[Punkt]) -> String {
+    const BREITE: f64 = 800.0;
+    const HÖHE: f64 = 560.0;
+    const RAND: f64 = 70.0;
+
+    // Achsen in ganzen Zehnerpotenzen um Knick, Spitze und alle Punkte
+    let intensitäten = punkte.iter().map(|punkt| punkt.intensität);
+    let x_min: f64 = intensitäten
+        .clone()
+        .fold(maschine.knick() / 10.0, f64::min)
+        .log10()
+        .floor();
+    let x_max: f64 = intensitäten
+        .fold(maschine.knick() * 10.0, f64::max)
+        .log10()
+        .ceil();
+    let leistungen = punkte
+        .iter()
+        .map(|punkt| punkt.leistung / 1e9)
+        .filter(|&leistung| leistung > 0.0);
+    let y_min: f64 = leistungen
+        .clone()
+        .fold(maschine.dach(10f64.powf(x_min)) / 1e9, f64::min)
+        .log10()
+        .floor();
+    let y_max: f64 = leistungen
+        .fold(maschine.spitze / 1e9, f64::max)
+        .log10()
+        .floor()
+        + 1.0;
+
+    let x = |intensität: f64| {
+        RAND + (intensität.log10() - x_min) / (x_max - x_min) * (BREITE - 2.0 * RAND)
+    };
+    let y = |gflops: f64| {
+        HÖHE - RAND - (gflops.log10() - y_min) / (y_max - y_min) * (HÖHE - 2.0 * RAND)
+    };
+
+    let mut text: String = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{BREITE}\" height=\"{HÖHE}\" \
+         font-family=\"sans-serif\" font-size=\"12\">\n\
+         <rect width=\"{BREITE}\" height=\"{HÖHE}\" fill=\"white\"/>\n"
+    );
+
+    // Gitter und Beschriftung der Zehnerpotenzen
+    for potenz in x_min as i32..=x_max as i32 {
+        let position: f64 = x(10f64.powi(potenz));
+        text += &format!(
+            "<line x1=\"{position:.1}\" y1=\"{:.1}\" x2=\"{position:.1}\" y2=\"{:.1}\" stroke=\"#ddd\"/>\n\
+             <text x=\"{position:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>\n",
+            RAND,
+            HÖHE - RAND,
+            HÖHE - RAND + 18.0,
+            10f64.powi(potenz)
+        );
+    }
+    for potenz in y_min as i32..=y_max as i32 {
+        let position: f64 = y(10f64.powi(potenz));
+        text += &format!(
+            "<line x1=\"{:.1}\" y1=\"{position:.1}\" x2=\"{:.1}\" y2=\"{position:.1}\" stroke=\"#ddd\"/>\n\
+             <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\n",
+            RAND,
+            BREITE - RAND,
+            RAND - 6.0,
+            position + 4.0,
+            10f64.powi(potenz)
+        );
+    }
+    text += &format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">arithmetische Intensität [FLOP/Byte]</text>\n\
+         <text x=\"18\" y=\"{:.1}\" text-anchor=\"middle\" transform=\"rotate(-90 18 {:.1})\">GFLOP/s</text>\n",
+        BREITE / 2.0,
+        HÖHE - 20.0,
+        HÖHE / 2.0,
+        HÖHE / 2.0
+    );
+
+    // Dach: Bandbreite bis zum Knick, danach die Spitzenleistung
+    let links: f64 = 10f64.powf(x_min);
+    let rechts: f64 = 10f64.powf(x_max);
+    text += &format!(
+        "<polyline fill=\"none\" stroke=\"black\" stroke-width=\"2\" points=\"{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\"/>\n",
+        x(links),
+        y(maschine.dach(links) / 1e9),
+        x(maschine.knick()),
+        y(maschine.spitze / 1e9),
+        x(rechts),
+        y(maschine.spitze / 1e9)
+    );
+    text += &format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\">{:.1} GFLOP/s ({}, {} Threads), {:.1} GB/s</text>\n",
+        RAND + 6.0,
+        RAND - 10.0,
+        maschine.spitze / 1e9,
+        maschine.pfad,
+        maschine.threads,
+        maschine.bandbreite / 1e9
+    );
+
+    // Punkte mit Legende, Algorithmen in der Reihenfolge ihres ersten Auftretens
+    let mut algorithmen: Vec<&str> = Vec::new();
+    for punkt in punkte {
+        let name: &str = &punkt.ergebnis.algorithmus;
+        let index: usize = match algorithmen.iter().position(|&a| a == name) {
+            Some(index) => index,
+            None => {
+                algorithmen.push(name);
+                algorithmen.len() - 1
+            }
+        };
+        if punkt.leistung > 0.0 {
+            text += &format!(
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"4\" fill=\"{}\"><title>{} {} Threads {}x{}x{}: \
+                 {:.2} GFLOP/s</title></circle>\n",
+                x(punkt.intensität),
+                y(punkt.leistung / 1e9),
+                FARBEN[index % FARBEN.len()],
+                name,
+                punkt.ergebnis.threads,
+                punkt.ergebnis.m,
+                punkt.ergebnis.k,
+                punkt.ergebnis.n,
+                punkt.leistung / 1e9
+            );
+        }
+    }
+    for (index, name) in algorithmen.iter().enumerate() {
+        let zeile: f64 = RAND + 10.0 + 16.0 * index as f64;
+        text += &format!(
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"4\" fill=\"{}\"/>\n\
+             <text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n",
+            BREITE - RAND - 120.0,
+            zeile,
+            FARBEN[index % FARBEN.len()],
+            BREITE - RAND - 110.0,
+            zeile + 4.0,
+            name
+        );
+    }
+
+    text += "</svg>\n";
+    text
+}
@@ -1,25 +1,33 @@
 #[cfg(test)]
 mod tests {
-    use crate::matrix::zufallsmatrix_2d;
-    use core_affinity::{CoreId, get_core_ids, set_for_current};
-    use rayon::ThreadPoolBuilder;
-    use std::process;
-
-    use crate::algorithmen::crossbeam;
-    use crate::algorithmen::manuell_sicher;
-    use crate::algorithmen::manuell_unsicher;
-    use crate::algorithmen::rayon as mein_rayon;
-    use crate::algorithmen::simd;
-    use crate::algorithmen::simd_tiling;
-    use crate::algorithmen::single;
-    use crate::algorithmen::tiling;
-    use crate::algorithmen::unroll;
-
-    fn vergleich(a: &Vec<Vec<f64>>, b: &Vec<Vec<f64>>, n: usize) -> bool {
-        let genauigkeit = 1e-10;
-        for i in 0..n {
+    use crate::abft::{self, Abft, Befund};
+    use crate::algorithmen::{gepack, simd, simd_tiling, single, strassen, tiling, winograd};
+    use crate::auswertung::{self, Effizienz, Ergebnis};
+    use crate::cpu::{self, SimdPfad};
+    use crate::fehler::{Fehler, kerne as alle_kerne};
+    use crate::gemm::{Transponierung, gemm};
+    use crate::generator::{Generator, Matrixart};
+    use crate::kommandozeile::{self, Ausgabe, Messung, Optionen, Prüfung};
+    use crate::konfiguration::{Konfiguration, Threadauswahl};
+    use crate::matrix::{Dateiformat, Dateiwert, Matrix, zufallsmatrix};
+    use crate::multiplikator::{Einstellungen, Fähigkeiten, Multiplikator, alle, finden};
+    use crate::numa::{self, Platzierung};
+    use crate::rechenpool::{self, Ausführung, Rechenpool, Schranke};
+    use crate::regression::{self, Eintrag, Urteil};
+    use crate::roofline::{self, Maschine, Punkt, Zugriff};
+    use crate::topologie::{self, Kernart, Strategie, Topologie};
+    use crate::verifikation;
+    use crate::verteilung::{Planer, Verteilung};
+    use crate::zaehler::{self, Ereignis, Erfassung, Werte};
+    use crate::zahl::{Komplex, Zahl};
+    use crate::zerlegung::{Kachel, Zerlegung};
+    use core_affinity::CoreId;
+
+    // die erlaubte Abweichung hängt vom Typ ab, ganze Zahlen müssen exakt übereinstimmen
+    fn vergleich<T: Zahl>(a: &Matrix<T>, b: &Matrix<T>, m: usize, n: usize) -> bool {
+        for i in 0..m {
             for j in 0..n {
-                if (a[i][j] - b[i][j]).abs() > genauigkeit {
+                if a[(i, j)].abstand(b[(i, j)]) > T::GENAUIGKEIT {
                     return false;
                 }
             }
@@ -27,128 +35,2507 @@ mod tests {
         true
     }
 
+    // Es soll mit einer geraden und ungeraden Anzahl an Threads getestet werden
+    fn thread_anzahlen(kerne: &[CoreId]) -> Vec<usize> {
+        if kerne.len() >= 5 {
+            vec![4, 5]
+        } else if kerne.len() >= 2 {
+            vec![kerne.len() - 1, kerne.len()]
+        } else {
+            vec![1]
+        }
+    }
+
+    /*
+        Startwert für reproduzierbare Zufallsmatrizen. Ein fehlgeschlagener Test gibt seinen Startwert aus und
+        lässt sich mit MULTIPLIKATION_SEED=<startwert> cargo test genau wiederholen
+    */
+    fn startwert() -> u64 {
+        std::env::var("MULTIPLIKATION_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or_else(rand::random)
+    }
+
+    /*
+        multipliziert eine zufällige m x k mit einer zufälligen k x n Matrix mit allen registrierten Algorithmen
+        und vergleicht das Ergebnis mit single Thread
+    */
+    fn alle_prüfen<T: Zahl>(m: usize, k: usize, n: usize, thread: usize, kerne: &[CoreId]) {
+        // Matrizen initialisieren, b mit dem nächsten Startwert
+        let seed: u64 = startwert();
+        let a: Matrix<T> = Generator::neu(seed, Matrixart::Standard)
+            .matrix(m, k)
+            .unwrap();
+        let b: Matrix<T> = Generator::neu(seed.wrapping_add(1), Matrixart::Standard)
+            .matrix(k, n)
+            .unwrap();
+        let mut c: Matrix<T> = Matrix::neu(m, n);
+
+        // single Thread als Basis für Vergleich
+        single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+        let einstellungen: Einstellungen = Einstellungen::neu(thread, kerne.to_vec());
+
+        for algorithmus in alle::<T>() {
+            let mut ergebnis: Matrix<T> = Matrix::neu(m, n);
+            algorithmus
+                .ausführen(&a, &b, &mut ergebnis, m, k, n, &einstellungen)
+                .unwrap_or_else(|f| panic!("{} ist fehlgeschlagen: {}", algorithmus.name(), f));
+            assert!(
+                vergleich(&c, &ergebnis, m, n),
+                "{}.rs ist falsch für threads = {}, m = {}, k = {}, n = {}, MULTIPLIKATION_SEED={}",
+                algorithmus.name(),
+                thread,
+                m,
+                k,
+                n,
+                seed
+            );
+        }
+    }
+
     #[test]
     fn testen() {
         // testen mit geraden und ungerade Matritzen
         let groessen: Vec<usize> = vec![4, 5, 12, 13, 30, 33, 68, 71, 126, 131, 256, 271, 300];
 
-        let kerne: Vec<CoreId> = get_core_ids().unwrap();
-
-        // Es soll mit einer geraden und ungeraden Anzahl an Threads getestet werden
-        let threads: Vec<usize>;
-        if kerne.len() >= 5 {
-            threads = vec![4, 5];
-        } else if kerne.len() >= 2 {
-            threads = vec![kerne.len() - 1, kerne.len()];
-        } else {
-            threads = vec![1];
-        }
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
 
         let mut i: u32 = 1;
-        for thread in threads {
+        for thread in thread_anzahlen(&kerne) {
             println!("Testen von Threads = {}", thread);
             for &n in &groessen {
                 println!("Test {}/{}", i, groessen.len());
-                i = i + 1;
+                i += 1;
 
-                // Matrizen initialisieren
-                let a: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-                let b: Vec<Vec<f64>> = zufallsmatrix_2d(n);
-                let mut c: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
+                alle_prüfen::<f64>(n, n, n, thread, &kerne);
+            }
+            i = 1;
+        }
 
-                // single Thread als Basis für Vergleich
-                single::ausführen(&a, &b, &mut c, n, &kerne[0]);
+        println!("\nAlle Funktionen sind korrekt");
+    }
+
+    #[test]
+    fn rechteckig() {
+        // schmale (m >> n) und breite (n >> m) Formen sowie sehr kleine innere Dimension k. Die Spaltenanzahlen
+        // sind so gewählt, dass die simd Reste (n % 4) und die Reste beim loop unrolling (k % 4) vorkommen
+        let formen: Vec<(usize, usize, usize)> = vec![
+            (1, 1, 1),
+            (1, 7, 33),
+            (33, 7, 1),
+            (3, 64, 301),
+            (301, 64, 3),
+            (100, 1, 99),
+            (13, 300, 5),
+            (2, 129, 2),
+            (250, 6, 250),
+        ];
+
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+
+        for thread in thread_anzahlen(&kerne) {
+            for &(m, k, n) in &formen {
+                println!(
+                    "Testen von Threads = {}, m = {}, k = {}, n = {}",
+                    thread, m, k, n
+                );
+                alle_prüfen::<f64>(m, k, n, thread, &kerne);
+            }
+        }
+
+        println!("\nAlle Funktionen sind für rechteckige Matrizen korrekt");
+    }
+
+    #[test]
+    fn register() {
+        // jeder Name darf nur einmal vorkommen und muss über finden() auffindbar sein
+        for algorithmus in alle::<f64>() {
+            let anzahl: usize = alle::<f64>()
+                .iter()
+                .filter(|a| a.name() == algorithmus.name())
+                .count();
+            assert_eq!(anzahl, 1, "{} ist mehrfach registriert", algorithmus.name());
+            assert!(finden::<f64>(algorithmus.name()).is_some());
+        }
+        assert!(finden::<f64>("gibt_es_nicht").is_none());
+    }
+
+    #[test]
+    fn fehlerbehandlung() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+
+        let a: Matrix = zufallsmatrix(5, 3);
+        let b: Matrix = zufallsmatrix(3, 4);
+
+        for algorithmus in alle() {
+            // c hat eine falsche Größe
+            let mut c: Matrix = Matrix::neu(4, 5);
+            let einstellungen: Einstellungen = Einstellungen::neu(1, kerne.clone());
+            assert_eq!(
+                algorithmus.ausführen(&a, &b, &mut c, 5, 3, 4, &einstellungen),
+                Err(Fehler::Dimension {
+                    matrix: "c",
+                    erwartet: (5, 4),
+                    tatsächlich: (4, 5)
+                }),
+                "{}",
+                algorithmus.name()
+            );
 
-                let mut ergebnis: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
-                manuell_sicher::ausführen(&a, &b, &mut ergebnis, n, thread, &kerne);
+            // die inneren Dimensionen von a und b passen nicht zusammen
+            let mut c: Matrix = Matrix::neu(5, 4);
+            assert!(matches!(
+                algorithmus.ausführen(&a, &b, &mut c, 5, 4, 4, &einstellungen),
+                Err(Fehler::Dimension { matrix: "a", .. })
+            ));
+
+            // leere Liste der Kerne
+            let leer: Einstellungen = Einstellungen::neu(1, Vec::new());
+            assert_eq!(
+                algorithmus.ausführen(&a, &b, &mut c, 5, 3, 4, &leer),
+                Err(Fehler::KeineKerne),
+                "{}",
+                algorithmus.name()
+            );
+
+            // mehr Threads als Kerne, single verwendet immer nur einen Kern
+            if algorithmus.fähigkeiten().parallel {
+                let zu_viele: Einstellungen = Einstellungen::neu(kerne.len() + 1, kerne.clone());
+                assert_eq!(
+                    algorithmus.ausführen(&a, &b, &mut c, 5, 3, 4, &zu_viele),
+                    Err(Fehler::ZuVieleThreads {
+                        threads: kerne.len() + 1,
+                        kerne: kerne.len()
+                    }),
+                    "{}",
+                    algorithmus.name()
+                );
+
+                let keine: Einstellungen = Einstellungen::neu(0, kerne.clone());
+                assert_eq!(
+                    algorithmus.ausführen(&a, &b, &mut c, 5, 3, 4, &keine),
+                    Err(Fehler::KeineThreads),
+                    "{}",
+                    algorithmus.name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn gemm_alpha_beta_transponiert() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+        let einstellungen: Einstellungen = Einstellungen::neu(1, kerne.clone());
+
+        let (m, k, n): (usize, usize, usize) = (7, 5, 9);
+        let varianten: [Transponierung; 2] = [Transponierung::Nein, Transponierung::Ja];
+
+        for algorithmus in alle::<f64>() {
+            for trans_a in varianten {
+                for trans_b in varianten {
+                    for (alpha, beta) in
+                        [(1.0, 0.0), (2.5, 0.0), (1.0, 1.0), (-0.5, 3.0), (0.0, 2.0)]
+                    {
+                        // a und b so erzeugen, dass op(a) m x k und op(b) k x n ist
+                        let a: Matrix = match trans_a {
+                            Transponierung::Nein => zufallsmatrix(m, k),
+                            Transponierung::Ja => zufallsmatrix(k, m),
+                        };
+                        let b: Matrix = match trans_b {
+                            Transponierung::Nein => zufallsmatrix(k, n),
+                            Transponierung::Ja => zufallsmatrix(n, k),
+                        };
+                        let op_a: Matrix = match trans_a {
+                            Transponierung::Nein => a.clone(),
+                            Transponierung::Ja => a.transponiert(),
+                        };
+                        let op_b: Matrix = match trans_b {
+                            Transponierung::Nein => b.clone(),
+                            Transponierung::Ja => b.transponiert(),
+                        };
+
+                        // bei beta = 0 darf c nicht gelesen werden, NaN darf also nicht im Ergebnis auftauchen
+                        let mut c: Matrix = zufallsmatrix(m, n);
+                        if beta == 0.0 {
+                            c.daten.fill(f64::NAN);
+                        }
+
+                        // Erwartung mit single Thread berechnen
+                        let mut produkt: Matrix = Matrix::neu(m, n);
+                        single::ausführen(&op_a, &op_b, &mut produkt, m, k, n, &kerne[0]).unwrap();
+                        let mut erwartet: Matrix = Matrix::neu(m, n);
+                        for i in 0..m {
+                            for j in 0..n {
+                                let alt: f64 = if beta == 0.0 { 0.0 } else { beta * c[(i, j)] };
+                                erwartet[(i, j)] = alpha * produkt[(i, j)] + alt;
+                            }
+                        }
+
+                        gemm(
+                            trans_a,
+                            trans_b,
+                            alpha,
+                            &a,
+                            &b,
+                            beta,
+                            &mut c,
+                            algorithmus,
+                            &einstellungen,
+                        )
+                        .unwrap();
+                        assert!(
+                            vergleich(&erwartet, &c, m, n),
+                            "gemm mit {} ist falsch für {:?}, {:?}, alpha = {}, beta = {}",
+                            algorithmus.name(),
+                            trans_a,
+                            trans_b,
+                            alpha,
+                            beta
+                        );
+                    }
+                }
+            }
+        }
+
+        // op(a) und op(b) passen nicht zusammen
+        let a: Matrix = zufallsmatrix(3, 4);
+        let b: Matrix = zufallsmatrix(3, 2);
+        let mut c: Matrix = Matrix::neu(3, 2);
+        assert!(matches!(
+            gemm(
+                Transponierung::Nein,
+                Transponierung::Nein,
+                1.0,
+                &a,
+                &b,
+                0.0,
+                &mut c,
+                alle()[0],
+                &einstellungen
+            ),
+            Err(Fehler::Dimension { matrix: "b", .. })
+        ));
+    }
+
+    #[test]
+    fn gepack_blockgrenzen() {
+        // kleine Blockgrößen, damit die Ränder aller fünf Schleifen (auch nc und kc) mehrfach vorkommen
+        let blöcke: gepack::Blockgrößen = gepack::Blockgrößen {
+            mc: 7,
+            kc: 5,
+            nc: 9,
+        };
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+
+        for thread in thread_anzahlen(&kerne) {
+            for &(m, k, n) in &[(1, 1, 1), (13, 11, 17), (24, 10, 16), (50, 3, 41)] {
+                let a: Matrix = zufallsmatrix(m, k);
+                let b: Matrix = zufallsmatrix(k, n);
+                let mut c: Matrix = Matrix::neu(m, n);
+                single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+                // mit Werten vorbelegt, da c beim ersten kc Block überschrieben werden muss
+                let mut ergebnis: Matrix = zufallsmatrix(m, n);
+                gepack::ausführen_mit_blöcken(
+                    &a,
+                    &b,
+                    &mut ergebnis,
+                    m,
+                    k,
+                    n,
+                    thread,
+                    &kerne,
+                    blöcke,
+                    Ausführung::Spawnen,
+                    Verteilung::default(),
+                )
+                .unwrap();
                 assert!(
-                    vergleich(&c, &ergebnis, n),
-                    "manuell_sicher.rs ist falsch für threads = {}, n = {}",
+                    vergleich(&c, &ergebnis, m, n),
+                    "gepack.rs ist falsch für threads = {}, m = {}, k = {}, n = {}",
                     thread,
+                    m,
+                    k,
                     n
                 );
+            }
+        }
+    }
+
+    #[test]
+    fn strassen_winograd() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
 
-                let mut ergebnis: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
-                manuell_unsicher::ausführen(&a, &b, &mut ergebnis, n, thread, &kerne);
+        // kleine Grenze damit mehrere Rekursionsebenen mit ungeraden Größen entstehen
+        let varianten: [&dyn Multiplikator; 4] = [
+            &strassen::Strassen::neu(4, &tiling::Tiling),
+            &strassen::Strassen::neu(7, &simd_tiling::SimdTiling),
+            &winograd::Winograd::neu(4, &tiling::Tiling),
+            &winograd::Winograd::neu(5, &gepack::Gepack),
+        ];
+
+        // mehrere Threads dürfen auf denselben Kern gepinnt werden, so wird die parallele Verteilung der
+        // Teilprodukte auch auf Rechnern mit wenigen Kernen getestet
+        let pinnen: Vec<CoreId> = vec![kerne[0]; 15];
+
+        for algorithmus in varianten {
+            for thread in [1, 3, 7, 15] {
+                let einstellungen: Einstellungen = Einstellungen::neu(thread, pinnen.clone());
+
+                for &(m, k, n) in &[
+                    (33, 33, 33),
+                    (64, 64, 64),
+                    (37, 21, 45),
+                    (9, 50, 17),
+                    (3, 40, 40),
+                ] {
+                    let a: Matrix = zufallsmatrix(m, k);
+                    let b: Matrix = zufallsmatrix(k, n);
+                    let mut c: Matrix = Matrix::neu(m, n);
+                    single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+                    let mut ergebnis: Matrix = Matrix::neu(m, n);
+                    algorithmus
+                        .ausführen(&a, &b, &mut ergebnis, m, k, n, &einstellungen)
+                        .unwrap();
+                    assert!(
+                        vergleich(&c, &ergebnis, m, n),
+                        "{} ist falsch für threads = {}, m = {}, k = {}, n = {}",
+                        algorithmus.name(),
+                        thread,
+                        m,
+                        k,
+                        n
+                    );
+                }
+            }
+        }
+
+        // die Teilprodukte rechnen mit den Einstellungen des Aufrufers, nur threads und pinnen je Gruppe
+        let einstellungen: Einstellungen = Einstellungen::neu(7, vec![kerne[0]; 7])
+            .mit_ausführung(Ausführung::Spawnen)
+            .mit_verteilung(Verteilung::StatischZyklisch { zeilen: 3 })
+            .mit_zerlegung(Zerlegung::Kacheln {
+                zeilen: 4,
+                spalten: 8,
+                k_teile: 2,
+            })
+            .mit_verifikation(2);
+        let (m, k, n): (usize, usize, usize) = (37, 21, 45);
+        let a: Matrix = zufallsmatrix(m, k);
+        let b: Matrix = zufallsmatrix(k, n);
+        let mut c: Matrix = Matrix::neu(m, n);
+        single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+        let varianten: [&dyn Multiplikator; 2] = [
+            &strassen::Strassen::neu(4, &AUFZEICHNUNG),
+            &winograd::Winograd::neu(4, &AUFZEICHNUNG),
+        ];
+        for algorithmus in varianten {
+            AUFZEICHNUNG.gesehen.lock().unwrap().clear();
+            let mut ergebnis: Matrix = Matrix::neu(m, n);
+            algorithmus
+                .ausführen(&a, &b, &mut ergebnis, m, k, n, &einstellungen)
+                .unwrap();
+            assert!(vergleich(&c, &ergebnis, m, n), "{}", algorithmus.name());
+
+            let gesehen: Vec<Einstellungen> = AUFZEICHNUNG.gesehen.lock().unwrap().clone();
+            assert!(!gesehen.is_empty());
+            for basis in gesehen {
+                assert_eq!(basis.threads, 1, "{}", algorithmus.name());
+                assert_eq!(basis.ausführung, einstellungen.ausführung);
+                assert_eq!(basis.verteilung, einstellungen.verteilung);
+                assert_eq!(basis.zerlegung, einstellungen.zerlegung);
+                assert_eq!(basis.verifikation, einstellungen.verifikation);
+            }
+        }
+    }
+
+    /*
+        Basis Algorithmus der die Einstellungen jedes Aufrufs aufzeichnet und dann mit tiling rechnet
+    */
+    struct Aufzeichnung {
+        gesehen: std::sync::Mutex<Vec<Einstellungen>>,
+    }
+
+    static AUFZEICHNUNG: Aufzeichnung = Aufzeichnung {
+        gesehen: std::sync::Mutex::new(Vec::new()),
+    };
+
+    impl Multiplikator for Aufzeichnung {
+        fn name(&self) -> &'static str {
+            "aufzeichnung"
+        }
+
+        fn beschreibung(&self) -> &'static str {
+            "Einstellungen aufzeichnen"
+        }
+
+        fn fähigkeiten(&self) -> Fähigkeiten {
+            Multiplikator::<f64>::fähigkeiten(&tiling::Tiling)
+        }
+
+        fn ausführen(
+            &self,
+            a: &Matrix,
+            b: &Matrix,
+            c: &mut Matrix,
+            m: usize,
+            k: usize,
+            n: usize,
+            einstellungen: &Einstellungen,
+        ) -> Result<(), Fehler> {
+            self.gesehen.lock().unwrap().push(einstellungen.clone());
+            tiling::Tiling.ausführen(a, b, c, m, k, n, einstellungen)
+        }
+    }
+
+    #[test]
+    fn simd_pfade() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+        let pfade: Vec<SimdPfad> = cpu::unterstützt();
+
+        // der gewählte Pfad ist der breiteste unterstützte
+        assert_eq!(Some(&cpu::pfad()), pfade.last());
+        assert!(pfade.contains(&SimdPfad::Sse2));
+        // avx512 setzt alle Features von avx2 voraus
+        assert!(!pfade.contains(&SimdPfad::Avx512) || pfade.contains(&SimdPfad::Avx2Fma));
+
+        for pfad in [SimdPfad::Sse2, SimdPfad::Avx2Fma, SimdPfad::Avx512] {
+            for &(m, k, n) in &[(1, 1, 1), (5, 3, 7), (17, 19, 23), (9, 33, 40)] {
+                let a: Matrix = zufallsmatrix(m, k);
+                let b: Matrix = zufallsmatrix(k, n);
+                let mut c: Matrix = Matrix::neu(m, n);
+                single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+                let mut ergebnis1: Matrix = Matrix::neu(m, n);
+                let mut ergebnis2: Matrix = Matrix::neu(m, n);
+                let simd1: Result<(), Fehler> = simd::ausführen_mit_pfad(
+                    &a,
+                    &b,
+                    &mut ergebnis1,
+                    m,
+                    k,
+                    n,
+                    1,
+                    &kerne,
+                    pfad,
+                    Ausführung::Spawnen,
+                    Verteilung::default(),
+                    Zerlegung::default(),
+                );
+                let simd2: Result<(), Fehler> = simd_tiling::ausführen_mit_pfad(
+                    &a,
+                    &b,
+                    &mut ergebnis2,
+                    m,
+                    k,
+                    n,
+                    1,
+                    &kerne,
+                    pfad,
+                    Ausführung::Spawnen,
+                    Verteilung::default(),
+                    Zerlegung::default(),
+                );
+
+                // nicht unterstützte Pfade werden abgelehnt statt mit einer ungültigen Instruktion abzustürzen
+                if !pfade.contains(&pfad) {
+                    assert_eq!(simd1, Err(Fehler::SimdPfad(pfad)));
+                    assert_eq!(simd2, Err(Fehler::SimdPfad(pfad)));
+                    continue;
+                }
+                simd1.unwrap();
+                simd2.unwrap();
                 assert!(
-                    vergleich(&c, &ergebnis, n),
-                    "manuell_unsicher.rs ist falsch für threads = {}, n = {}",
-                    thread,
+                    vergleich(&c, &ergebnis1, m, n) && vergleich(&c, &ergebnis2, m, n),
+                    "simd Pfad {} ist falsch für m = {}, k = {}, n = {}",
+                    pfad,
+                    m,
+                    k,
                     n
                 );
+            }
+        }
+
+        /*
+            simd_tiling mit 16 f32 je Register (avx512): (1 + 2^-12)^2 - (1 + 2^-11) ist mit fused multiply-add
+            2^-24, ohne FMA wird das Produkt vorher gerundet und das Ergebnis ist 0. So ist erkennbar, welche
+            Spalten ein Register berechnet hat und welche im skalaren Rest gelandet sind
+        */
+        let x: f32 = 1.0 + 2f32.powi(-12);
+        for breite in [16, 32, 37] {
+            let b: Matrix<f32> = Matrix {
+                zeilen: 1,
+                spalten: breite,
+                stride: breite,
+                daten: vec![x; breite],
+            };
+            let mut zeile: Vec<f32> = vec![-(1.0 + 2f32.powi(-11)); breite];
+            simd_tiling::zeile_allgemein::<f32, 16, true>(&[x], &b, &mut zeile, 0..breite, 0..1);
+
+            // nur die Spalten nach dem letzten vollen Register rechnen skalar
+            let register: usize = breite / 16 * 16;
+            for (j, &wert) in zeile.iter().enumerate() {
+                let erwartet: f32 = if j < register { 2f32.powi(-24) } else { 0.0 };
+                assert_eq!(wert, erwartet, "Spalte {} bei Breite {}", j, breite);
+            }
+        }
+    }
 
-                // Bibliothek Crossbeam testen
-                ergebnis = vec![vec![0.0; n]; n];
-                crossbeam::ausführen(&a, &b, &mut ergebnis, n, thread, &kerne);
+    /*
+        alle Algorithmen, Strassen und Winograd mit mehreren Rekursionsebenen und alle simd Pfade für den Typ T
+    */
+    fn typ_prüfen<T: Zahl>(kerne: &[CoreId]) {
+        for thread in thread_anzahlen(kerne) {
+            for &(m, k, n) in &[(1, 1, 1), (13, 7, 29), (33, 33, 33), (71, 40, 18)] {
+                alle_prüfen::<T>(m, k, n, thread, kerne);
+            }
+        }
+
+        let rekursiv: [&dyn Multiplikator<T>; 2] = [
+            &strassen::Strassen::neu(4, &tiling::Tiling),
+            &winograd::Winograd::neu(5, &gepack::Gepack),
+        ];
+        let einstellungen: Einstellungen = Einstellungen::neu(3, vec![kerne[0]; 3]);
+
+        for &(m, k, n) in &[(33, 33, 33), (37, 21, 45)] {
+            let a: Matrix<T> = zufallsmatrix(m, k);
+            let b: Matrix<T> = zufallsmatrix(k, n);
+            let mut c: Matrix<T> = Matrix::neu(m, n);
+            single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+            for algorithmus in rekursiv {
+                let mut ergebnis: Matrix<T> = Matrix::neu(m, n);
+                algorithmus
+                    .ausführen(&a, &b, &mut ergebnis, m, k, n, &einstellungen)
+                    .unwrap();
                 assert!(
-                    vergleich(&c, &ergebnis, n),
-                    "crossbeam.rs ist falsch für threads = {}, n = {}",
-                    thread,
+                    vergleich(&c, &ergebnis, m, n),
+                    "{} ist falsch für {}, m = {}, k = {}, n = {}",
+                    algorithmus.name(),
+                    std::any::type_name::<T>(),
+                    m,
+                    k,
                     n
                 );
+            }
 
-                // Bibliothek Rayon testen
-                ergebnis = vec![vec![0.0; n]; n];
-                let kopie: Vec<CoreId> = kerne.clone();
-                let pool: rayon::ThreadPool = ThreadPoolBuilder::new()
-                    .num_threads(thread)
-                    .start_handler(move |id| {
-                        set_for_current(kopie[id]);
-                    })
-                    .build()
-                    .unwrap_or_else(|f| {
-                        println!("Fehler beim erstellen des Threadpools: {}", f);
-                        process::exit(1)
-                    });
-                pool.install(|| {
-                    mein_rayon::ausführen(&a, &b, &mut ergebnis, n);
-                });
+            // jeder Pfad hat für jeden Typ eine andere Anzahl an Werten je Register
+            for pfad in cpu::unterstützt() {
+                let mut ergebnis1: Matrix<T> = Matrix::neu(m, n);
+                let mut ergebnis2: Matrix<T> = Matrix::neu(m, n);
+                simd::ausführen_mit_pfad(
+                    &a,
+                    &b,
+                    &mut ergebnis1,
+                    m,
+                    k,
+                    n,
+                    1,
+                    kerne,
+                    pfad,
+                    Ausführung::Rechenpool,
+                    Verteilung::default(),
+                    Zerlegung::default(),
+                )
+                .unwrap();
+                simd_tiling::ausführen_mit_pfad(
+                    &a,
+                    &b,
+                    &mut ergebnis2,
+                    m,
+                    k,
+                    n,
+                    1,
+                    kerne,
+                    pfad,
+                    Ausführung::Rechenpool,
+                    Verteilung::default(),
+                    Zerlegung::default(),
+                )
+                .unwrap();
                 assert!(
-                    vergleich(&c, &ergebnis, n),
-                    "rayon.rs ist falsch für threads = {}, n = {}",
-                    thread,
+                    vergleich(&c, &ergebnis1, m, n) && vergleich(&c, &ergebnis2, m, n),
+                    "simd Pfad {} ist falsch für {}, m = {}, k = {}, n = {}",
+                    pfad,
+                    std::any::type_name::<T>(),
+                    m,
+                    k,
                     n
                 );
+            }
+        }
+    }
+
+    #[test]
+    fn elementtypen() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+
+        typ_prüfen::<f32>(&kerne);
+        typ_prüfen::<i32>(&kerne);
+        typ_prüfen::<i64>(&kerne);
+        typ_prüfen::<Komplex<f32>>(&kerne);
+        typ_prüfen::<Komplex<f64>>(&kerne);
+
+        // ganze Zahlen werden exakt verglichen
+        let a: Matrix<i64> = Matrix {
+            zeilen: 2,
+            spalten: 2,
+            stride: 2,
+            daten: vec![1, 2, 3, 4],
+        };
+        let mut c: Matrix<i64> = Matrix::neu(2, 2);
+        gemm(
+            Transponierung::Nein,
+            Transponierung::Ja,
+            2,
+            &a,
+            &a,
+            1,
+            &mut c,
+            &gepack::Gepack,
+            &Einstellungen::neu(1, kerne.clone()),
+        )
+        .unwrap();
+        assert_eq!(c.daten, vec![10, 22, 22, 50]);
+
+        // (1 + 2i) * (3 - i) = 5 + 5i
+        let x: Matrix<Komplex<f64>> = Matrix {
+            zeilen: 1,
+            spalten: 1,
+            stride: 1,
+            daten: vec![Komplex::neu(1.0, 2.0)],
+        };
+        let y: Matrix<Komplex<f64>> = Matrix {
+            zeilen: 1,
+            spalten: 1,
+            stride: 1,
+            daten: vec![Komplex::neu(3.0, -1.0)],
+        };
+        let mut z: Matrix<Komplex<f64>> = Matrix::neu(1, 1);
+        simd::ausführen(
+            &x,
+            &y,
+            &mut z,
+            1,
+            1,
+            1,
+            1,
+            &kerne,
+            Ausführung::Rechenpool,
+            Verteilung::default(),
+            Zerlegung::default(),
+        )
+        .unwrap();
+        assert_eq!(z[(0, 0)], Komplex::neu(5.0, 5.0));
+    }
+
+    #[test]
+    fn rechenpool_wiederverwenden() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+
+        // mehrere Arbeiter auf demselben Kern, damit auch auf Rechnern mit wenigen Kernen verteilt wird
+        let pinnen: Vec<CoreId> = vec![kerne[0]; 4];
+
+        // jeder Algorithmus muss mit neu gestarteten Threads und mit dem Rechenpool dasselbe Ergebnis liefern
+        let (m, k, n): (usize, usize, usize) = (37, 29, 41);
+        let a: Matrix = zufallsmatrix(m, k);
+        let b: Matrix = zufallsmatrix(k, n);
+        let mut c: Matrix = Matrix::neu(m, n);
+        single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+        for algorithmus in alle::<f64>() {
+            for ausführung in [Ausführung::Spawnen, Ausführung::Rechenpool] {
+                // mehrere Aufrufe hintereinander verwenden dieselben Arbeiter
+                for _ in 0..3 {
+                    let einstellungen: Einstellungen =
+                        Einstellungen::neu(4, pinnen.clone()).mit_ausführung(ausführung);
+                    let mut ergebnis: Matrix = Matrix::neu(m, n);
+                    algorithmus
+                        .ausführen(&a, &b, &mut ergebnis, m, k, n, &einstellungen)
+                        .unwrap();
+                    assert!(
+                        vergleich(&c, &ergebnis, m, n),
+                        "{} ist falsch mit {}",
+                        algorithmus.name(),
+                        ausführung
+                    );
+                }
+            }
+        }
+
+        // ein Pool für den Anfang der Kernliste wird wiederverwendet
+        let pool1 = rechenpool::gemeinsam(&pinnen).unwrap();
+        let pool2 = rechenpool::gemeinsam(&pinnen[..2]).unwrap();
+        assert!(std::sync::Arc::ptr_eq(&pool1, &pool2));
 
-                ergebnis = vec![vec![0.0; n]; n];
-                simd::ausführen(&a, &b, &mut ergebnis, n, thread, &kerne);
+        // Ergebnisse kommen in der Reihenfolge der Arbeiter zurück, eine panic wird zum Fehler
+        let pool: Rechenpool = Rechenpool::neu(pinnen.clone()).unwrap();
+        assert_eq!(
+            pool.verteilen(4, |z| Ok(z * 10)).unwrap(),
+            vec![0, 10, 20, 30]
+        );
+        assert_eq!(
+            pool.verteilen(3, |z| if z == 1 { panic!("Test") } else { Ok(z) }),
+            Err(Fehler::ThreadPanik)
+        );
+        assert_eq!(
+            pool.verteilen(5, Ok),
+            Err(Fehler::ZuVieleThreads {
+                threads: 5,
+                kerne: 4
+            })
+        );
+
+        // nach dem Beenden werden keine Aufträge mehr angenommen
+        pool.beenden();
+        assert!(matches!(pool.verteilen(1, Ok), Err(Fehler::Threadpool(_))));
+    }
+
+    #[test]
+    fn verteilungen() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+        let pinnen: Vec<CoreId> = vec![kerne[0]; 4];
+
+        let verteilungen: [Verteilung; 9] = [
+            Verteilung::StatischBlock,
+            Verteilung::StatischZyklisch { zeilen: 1 },
+            Verteilung::StatischZyklisch { zeilen: 3 },
+            Verteilung::Dynamisch { zeilen: 0 },
+            Verteilung::Dynamisch { zeilen: 5 },
+            Verteilung::Geführt { minimum: 1 },
+            Verteilung::Geführt { minimum: 4 },
+            Verteilung::Stehlen { zeilen: 2 },
+            Verteilung::Knoten { zeilen: 3 },
+        ];
+
+        // jede Zeile muss genau einmal vergeben werden, auch bei mehr Threads als Zeilen
+        for verteilung in verteilungen {
+            for anzahl in [0, 1, 3, 17, 100] {
+                for threads in [1, 3, 8] {
+                    let planer: Planer = Planer::neu(verteilung, anzahl, &vec![kerne[0]; threads]);
+                    let bereiche: Vec<Vec<std::ops::Range<usize>>> = std::thread::scope(|s| {
+                        let sammeln: Vec<_> = (0..threads)
+                            .map(|z| {
+                                let planer: &Planer = &planer;
+                                s.spawn(move || std::iter::from_fn(|| planer.nächster(z)).collect())
+                            })
+                            .collect();
+                        sammeln.into_iter().map(|h| h.join().unwrap()).collect()
+                    });
+
+                    let mut vergeben: Vec<usize> = vec![0; anzahl];
+                    for bereich in bereiche.into_iter().flatten() {
+                        assert!(!bereich.is_empty());
+                        for i in bereich {
+                            vergeben[i] += 1;
+                        }
+                    }
+                    assert!(
+                        vergeben.iter().all(|&x| x == 1),
+                        "{} verteilt {} Zeilen auf {} Threads falsch",
+                        verteilung,
+                        anzahl,
+                        threads
+                    );
+                }
+            }
+        }
+
+        // nach dem Zurücksetzen wird wieder alles verteilt
+        let planer: Planer = Planer::neu(Verteilung::Stehlen { zeilen: 4 }, 10, &pinnen[..2]);
+        while planer.nächster(0).is_some() {}
+        assert_eq!(planer.nächster(1), None);
+        planer.zurücksetzen();
+        assert_eq!(planer.nächster(1), Some(5..9));
+
+        // jeder Algorithmus muss mit jeder Verteilung richtig rechnen
+        let (m, k, n): (usize, usize, usize) = (23, 19, 21);
+        let a: Matrix = zufallsmatrix(m, k);
+        let b: Matrix = zufallsmatrix(k, n);
+        let mut c: Matrix = Matrix::neu(m, n);
+        single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+        for algorithmus in alle::<f64>() {
+            for verteilung in verteilungen {
+                let einstellungen: Einstellungen =
+                    Einstellungen::neu(4, pinnen.clone()).mit_verteilung(verteilung);
+                let mut ergebnis: Matrix = Matrix::neu(m, n);
+                algorithmus
+                    .ausführen(&a, &b, &mut ergebnis, m, k, n, &einstellungen)
+                    .unwrap();
                 assert!(
-                    vergleich(&c, &ergebnis, n),
-                    "simd.rs ist falsch für threads = {}, n = {}",
-                    thread,
-                    n
+                    vergleich(&c, &ergebnis, m, n),
+                    "{} ist falsch mit {}",
+                    algorithmus.name(),
+                    verteilung
                 );
+            }
+        }
+    }
+
+    #[test]
+    fn kacheln() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+        let pinnen: Vec<CoreId> = vec![kerne[0]; 8];
 
-                ergebnis = vec![vec![0.0; n]; n];
-                tiling::ausführen(&a, &b, &mut ergebnis, n, thread, &kerne);
+        let zerlegungen: [Zerlegung; 5] = [
+            Zerlegung::Zeilen,
+            Zerlegung::Kacheln {
+                zeilen: 1,
+                spalten: 16,
+                k_teile: 1,
+            },
+            Zerlegung::Kacheln {
+                zeilen: 2,
+                spalten: 7,
+                k_teile: 3,
+            },
+            Zerlegung::Kacheln {
+                zeilen: 0,
+                spalten: 0,
+                k_teile: 0,
+            },
+            Zerlegung::Kacheln {
+                zeilen: 64,
+                spalten: 64,
+                k_teile: 100,
+            },
+        ];
+
+        // jedes (i, j, p) liegt in genau einer Kachel
+        for zerlegung in zerlegungen {
+            for (m, k, n) in [(0, 3, 4), (3, 0, 4), (5, 9, 11), (2, 2, 33)] {
+                let mut abgedeckt: Vec<usize> = vec![0; m * k * n];
+                for kachel in zerlegung.kacheln(m, k, n) {
+                    let Kachel {
+                        zeilen,
+                        spalten,
+                        tiefe,
+                    } = kachel;
+                    for i in zeilen {
+                        for j in spalten.clone() {
+                            for p in tiefe.clone() {
+                                abgedeckt[(i * n + j) * k + p] += 1;
+                            }
+                        }
+                    }
+                }
                 assert!(
-                    vergleich(&c, &ergebnis, n),
-                    "tiling.rs ist falsch für threads = {}, n = {}",
-                    thread,
-                    n
+                    abgedeckt.iter().all(|&x| x == 1),
+                    "{} ist unvollständig",
+                    zerlegung
+                );
+            }
+        }
+
+        // weniger Zeilen als Threads, breite und schmale Formen und k = 0
+        let formen: [(usize, usize, usize); 5] = [
+            (1, 37, 300),
+            (3, 50, 129),
+            (2, 1, 17),
+            (6, 0, 9),
+            (40, 3, 2),
+        ];
+
+        for (m, k, n) in formen {
+            let a: Matrix = zufallsmatrix(m, k);
+            let b: Matrix = zufallsmatrix(k, n);
+            let mut c: Matrix = Matrix::neu(m, n);
+            single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+            for algorithmus in alle::<f64>() {
+                for zerlegung in zerlegungen {
+                    for verteilung in [Verteilung::default(), Verteilung::Stehlen { zeilen: 1 }] {
+                        let einstellungen: Einstellungen = Einstellungen::neu(8, pinnen.clone())
+                            .mit_zerlegung(zerlegung)
+                            .mit_verteilung(verteilung);
+
+                        // das Ergebnis wird vollständig überschrieben
+                        let mut ergebnis: Matrix = Matrix::neu(m, n);
+                        ergebnis.daten.fill(7.0);
+                        algorithmus
+                            .ausführen(&a, &b, &mut ergebnis, m, k, n, &einstellungen)
+                            .unwrap();
+                        assert!(
+                            vergleich(&c, &ergebnis, m, n),
+                            "{} ist falsch mit {} bei {}x{}x{}",
+                            algorithmus.name(),
+                            zerlegung,
+                            m,
+                            k,
+                            n
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn topologie_strategien() {
+        assert_eq!(
+            topologie::liste_lesen("0-3,8,10-11\n"),
+            vec![0, 1, 2, 3, 8, 10, 11]
+        );
+        assert_eq!(topologie::liste_lesen(""), Vec::<usize>::new());
+
+        // nachgebauter Ausschnitt von /sys/devices eines Hybrid Prozessors: 2 P-Kerne mit SMT und eigenem L2,
+        // 4 E-Kerne mit gemeinsamem L2 und ein ausgeschalteter Prozessor
+        let wurzel: std::path::PathBuf =
+            std::env::temp_dir().join(format!("multiplikation_topologie_{}", std::process::id()));
+        let schreiben = |datei: String, inhalt: &str| {
+            let pfad: std::path::PathBuf = wurzel.join(datei);
+            std::fs::create_dir_all(pfad.parent().unwrap()).unwrap();
+            std::fs::write(pfad, inhalt).unwrap();
+        };
+
+        // (cpu, core_id, Geschwister, L2)
+        let prozessoren: [(usize, usize, &str, usize); 8] = [
+            (0, 0, "0-1", 0),
+            (1, 0, "0-1", 0),
+            (2, 4, "2-3", 1),
+            (3, 4, "2-3", 1),
+            (4, 8, "4", 2),
+            (5, 9, "5", 2),
+            (6, 10, "6", 2),
+            (7, 11, "7", 2),
+        ];
+        for (cpu, kern, geschwister, l2) in prozessoren {
+            let cpu_pfad: String = format!("system/cpu/cpu{}", cpu);
+            schreiben(format!("{}/topology/core_id", cpu_pfad), &kern.to_string());
+            // -1 wie auf manchen virtualisierten Systemen
+            let paket: &str = if cpu % 2 == 0 { "0" } else { "-1" };
+            schreiben(format!("{}/topology/physical_package_id", cpu_pfad), paket);
+            schreiben(
+                format!("{}/topology/thread_siblings_list", cpu_pfad),
+                geschwister,
+            );
+            schreiben(format!("{}/cache/index2/level", cpu_pfad), "2");
+            schreiben(format!("{}/cache/index2/id", cpu_pfad), &l2.to_string());
+            schreiben(format!("{}/cache/index3/level", cpu_pfad), "3");
+            schreiben(format!("{}/cache/index3/id", cpu_pfad), "0");
+        }
+        schreiben("system/cpu/cpu8/online".to_string(), "0");
+        schreiben("cpu_core/cpus".to_string(), "0-3");
+        schreiben("cpu_atom/cpus".to_string(), "4-7");
+
+        let erwartet: [(Strategie, Vec<usize>); 5] = [
+            (Strategie::Aufzählung, vec![0, 1, 2, 3, 4, 5, 6, 7]),
+            (Strategie::PhysischZuerst, vec![0, 2, 4, 5, 6, 7, 1, 3]),
+            (Strategie::NurLeistung, vec![0, 2, 1, 3]),
+            (Strategie::NurEffizienz, vec![4, 5, 6, 7]),
+            (Strategie::JeL2, vec![0, 2, 4]),
+        ];
+
+        let topologie: Topologie = Topologie::lesen_aus(&wurzel).unwrap();
+        assert_eq!(topologie.prozessoren.len(), 8);
+        assert_eq!(topologie.prozessoren[3].smt, 1);
+        assert!(topologie.prozessoren.iter().all(|p| p.paket == 0));
+        assert_eq!(topologie.prozessoren[5].art, Kernart::Effizienz);
+        assert_eq!(topologie.prozessoren[5].l3, Some(0));
+        for (strategie, reihenfolge) in &erwartet {
+            assert_eq!(
+                &topologie.reihenfolge(*strategie),
+                reihenfolge,
+                "{}",
+                strategie
+            );
+        }
+
+        // ohne cpu_atom werden die E-Kerne über eine kleinere cpu_capacity erkannt
+        std::fs::remove_dir_all(wurzel.join("cpu_atom")).unwrap();
+        for (cpu, _, _, _) in prozessoren {
+            let kapazität: &str = if cpu < 4 { "1024" } else { "600" };
+            schreiben(format!("system/cpu/cpu{}/cpu_capacity", cpu), kapazität);
+        }
+        let topologie: Topologie = Topologie::lesen_aus(&wurzel).unwrap();
+        for (strategie, reihenfolge) in &erwartet {
+            assert_eq!(
+                &topologie.reihenfolge(*strategie),
+                reihenfolge,
+                "{}",
+                strategie
+            );
+        }
+
+        std::fs::remove_dir_all(&wurzel).unwrap();
+        assert!(matches!(
+            Topologie::lesen_aus(&wurzel),
+            Err(Fehler::Topologie(_))
+        ));
+
+        // auf diesem Rechner liefert jede Strategie nur erlaubte Kerne, jeden höchstens einmal
+        let erlaubt: Vec<CoreId> = alle_kerne().unwrap();
+        if let Ok(topologie) = Topologie::lesen() {
+            for strategie in Strategie::ALLE {
+                match topologie.pinnen(strategie) {
+                    Ok(kerne) => {
+                        assert!(kerne.iter().all(|kern| erlaubt.contains(kern)));
+                        let mut ids: Vec<usize> = kerne.iter().map(|kern| kern.id).collect();
+                        ids.sort();
+                        ids.dedup();
+                        assert_eq!(ids.len(), kerne.len());
+                    }
+                    // z.B. keine Effizienzkerne vorhanden
+                    Err(f) => assert_eq!(f, Fehler::KeineKerne),
+                }
+            }
+        }
+        assert_eq!(topologie::kerne(Strategie::Aufzählung).unwrap(), erlaubt);
+    }
+
+    #[test]
+    fn numa_platzierung() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+
+        // nachgebauter Ausschnitt von /sys/devices mit zwei Knoten und einem Knoten nur mit Speicher
+        let wurzel: std::path::PathBuf =
+            std::env::temp_dir().join(format!("multiplikation_numa_{}", std::process::id()));
+        for (knoten, cpus) in [(0, "0-3"), (1, "4-7"), (2, "")] {
+            let pfad: std::path::PathBuf = wurzel.join(format!("system/node/node{}", knoten));
+            std::fs::create_dir_all(&pfad).unwrap();
+            std::fs::write(pfad.join("cpulist"), cpus).unwrap();
+        }
+        // zu große Matrizen: Überlauf der Anzahl, mehr als isize::MAX Bytes, mehr als der Adressraum
+        for platzierung in [Platzierung::Hauptthread, Platzierung::ErsteBerührung] {
+            for (zeilen, spalten) in [(usize::MAX, 2), (1 << 31, 1 << 31), (1 << 28, 1 << 30)] {
+                assert_eq!(
+                    numa::matrix::<f64, _>(zeilen, spalten, &kerne[..1], platzierung, |_, _| 0.0),
+                    Err(Fehler::Größe { zeilen, spalten })
                 );
+            }
+        }
+
+        let knoten: Vec<numa::Knoten> = numa::lesen_aus(&wurzel);
+        assert_eq!(knoten.len(), 2);
+        assert_eq!(numa::knoten_von(&knoten, 5), 1);
+        assert_eq!(numa::knoten_von(&knoten, 2), 0);
+        std::fs::remove_dir_all(&wurzel).unwrap();
+
+        // ohne NUMA Informationen gibt es genau einen Knoten
+        let einzeln: Vec<numa::Knoten> = numa::lesen_aus(&wurzel);
+        assert_eq!(einzeln.len(), 1);
+        assert_eq!(numa::knoten_von(&einzeln, 42), 0);
+        assert!(!numa::knoten().is_empty());
+
+        // bei Knoten stiehlt ein Thread nur von Threads auf seinem eigenen Knoten
+        let planer: Planer =
+            Planer::mit_knoten(Verteilung::Knoten { zeilen: 3 }, 40, vec![0, 0, 1, 1]);
+        let mut zeilen: Vec<usize> = std::iter::from_fn(|| planer.nächster(0))
+            .flatten()
+            .collect();
+        zeilen.sort();
+        assert_eq!(zeilen, (0..20).collect::<Vec<usize>>());
+        assert_eq!(planer.nächster(1), None);
+        let zeilen: usize = std::iter::from_fn(|| planer.nächster(3))
+            .map(|b| b.len())
+            .sum();
+        assert_eq!(zeilen, 20);
+
+        // jede Platzierung liefert dieselbe Matrix, auch über mehrere Seiten und bei leeren Matrizen
+        for threads in [1, 3] {
+            let pinnen: Vec<CoreId> = vec![kerne[0]; threads];
+            for (zeilen, spalten) in [(0, 5), (5, 0), (7, 3), (1000, 9)] {
+                let wert = |i: usize, j: usize| (i * spalten + j) as f64;
+                let erwartet: Matrix =
+                    numa::matrix(zeilen, spalten, &pinnen, Platzierung::Hauptthread, wert).unwrap();
+                assert_eq!(erwartet.daten.len(), zeilen * spalten);
+
+                for platzierung in [Platzierung::ErsteBerührung, Platzierung::Verschränkt] {
+                    let matrix: Matrix =
+                        numa::matrix(zeilen, spalten, &pinnen, platzierung, wert).unwrap();
+                    assert_eq!(matrix, erwartet, "{}", platzierung);
+                }
+            }
+        }
+        assert_eq!(
+            numa::zufallsmatrix::<f64>(2, 2, &[], Platzierung::ErsteBerührung),
+            Err(Fehler::KeineKerne)
+        );
+
+        // Multiplikation mit verteilt angelegten Matrizen und Verteilung nach Knoten
+        let pinnen: Vec<CoreId> = vec![kerne[0]; 4];
+        let (m, k, n): (usize, usize, usize) = (45, 17, 33);
+        let a: Matrix = numa::zufallsmatrix(m, k, &pinnen, Platzierung::ErsteBerührung).unwrap();
+        let b: Matrix = numa::zufallsmatrix(k, n, &pinnen, Platzierung::Verschränkt).unwrap();
+        let mut c: Matrix = Matrix::neu(m, n);
+        single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+        let einstellungen: Einstellungen =
+            Einstellungen::neu(4, pinnen.clone()).mit_verteilung(Verteilung::Knoten { zeilen: 2 });
+        for algorithmus in alle::<f64>() {
+            let mut ergebnis: Matrix =
+                numa::matrix(m, n, &pinnen, Platzierung::ErsteBerührung, |_, _| 0.0).unwrap();
+            algorithmus
+                .ausführen(&a, &b, &mut ergebnis, m, k, n, &einstellungen)
+                .unwrap();
+            assert!(
+                vergleich(&c, &ergebnis, m, n),
+                "{} ist falsch",
+                algorithmus.name()
+            );
+        }
+    }
+
+    /*
+        richtige Ergebnisse aller Algorithmen müssen die Prüfung mit Freivalds bestehen, ein einzelner falscher
+        Wert muss gefunden werden
+    */
+    fn freivalds_prüfen<T: Zahl>(kerne: &[CoreId]) {
+        let einstellungen: Einstellungen =
+            Einstellungen::neu(4, vec![kerne[0]; 4]).mit_verifikation(10);
+
+        // große k summieren die Rundungsfehler auf
+        for &(m, k, n) in &[(1, 1, 1), (37, 21, 45), (64, 500, 32)] {
+            let a: Matrix<T> = zufallsmatrix(m, k);
+            let b: Matrix<T> = zufallsmatrix(k, n);
 
-                ergebnis = vec![vec![0.0; n]; n];
-                simd_tiling::ausführen(&a, &b, &mut ergebnis, n, thread, &kerne);
+            for algorithmus in alle::<T>() {
+                let mut c: Matrix<T> = Matrix::neu(m, n);
+                algorithmus
+                    .multiplizieren(&a, &b, &mut c, m, k, n, &einstellungen)
+                    .unwrap_or_else(|f| {
+                        panic!(
+                            "{} für {}: {}",
+                            algorithmus.name(),
+                            std::any::type_name::<T>(),
+                            f
+                        )
+                    });
+
+                // bei 30 Wiederholungen bleibt der Fehler höchstens mit Wahrscheinlichkeit 2^-30 unentdeckt
+                let (i, j): (usize, usize) = (m / 2, n - 1);
+                c[(i, j)] += T::EINS;
+                let ergebnis: Result<(), Fehler> = verifikation::freivalds(&a, &b, &c, m, k, n, 30);
                 assert!(
-                    vergleich(&c, &ergebnis, n),
-                    "simd_tiling.rs ist falsch für threads = {}, n = {}",
-                    thread,
-                    n
+                    matches!(ergebnis, Err(Fehler::Verifikation { zeile, .. }) if zeile == i),
+                    "{} für {}: falscher Wert nicht gefunden, {:?}",
+                    algorithmus.name(),
+                    std::any::type_name::<T>(),
+                    ergebnis
                 );
+            }
+        }
+    }
+
+    #[test]
+    fn freivalds() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+
+        freivalds_prüfen::<f64>(&kerne);
+        freivalds_prüfen::<f32>(&kerne);
+        freivalds_prüfen::<i64>(&kerne);
+        freivalds_prüfen::<Komplex<f64>>(&kerne);
+
+        let (m, k, n): (usize, usize, usize) = (20, 30, 10);
+        let a: Matrix = zufallsmatrix(m, k);
+        let b: Matrix = zufallsmatrix(k, n);
+        let mut c: Matrix = Matrix::neu(m, n);
+        single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+        // falsche Dimensionen werden wie bei den Algorithmen gemeldet
+        assert!(matches!(
+            verifikation::freivalds(&a, &b, &c, m, k, n + 1, 5),
+            Err(Fehler::Dimension { matrix: "b", .. })
+        ));
+
+        // NaN wird immer als Abweichung erkannt, ohne Wiederholungen wird nichts geprüft
+        c[(3, 4)] = f64::NAN;
+        assert!(matches!(
+            verifikation::freivalds(&a, &b, &c, m, k, n, 30),
+            Err(Fehler::Verifikation { zeile: 3, .. })
+        ));
+        assert_eq!(verifikation::freivalds(&a, &b, &c, m, k, n, 0), Ok(()));
+
+        // gemm prüft das Produkt, wenn die Einstellungen eine Verifikation verlangen
+        let einstellungen: Einstellungen =
+            Einstellungen::neu(1, vec![kerne[0]]).mit_verifikation(10);
+        let mut ergebnis: Matrix = Matrix::neu(m, n);
+        gemm(
+            Transponierung::Nein,
+            Transponierung::Nein,
+            2.0,
+            &a,
+            &b,
+            0.0,
+            &mut ergebnis,
+            finden("gepack").unwrap(),
+            &einstellungen,
+        )
+        .unwrap();
+    }
+
+    /*
+        Basis Algorithmus mit Fehlerinjektion: nach der Berechnung wird in c an jeder Stelle (zeile, spalte, bit)
+        ein Bit gekippt, wie bei einem Speicherfehler während der Multiplikation
+    */
+    struct Störung {
+        basis: &'static dyn Multiplikator,
+        stellen: &'static [(usize, usize, u32)],
+    }
+
+    impl Multiplikator for Störung {
+        fn name(&self) -> &'static str {
+            "störung"
+        }
+
+        fn beschreibung(&self) -> &'static str {
+            "Fehlerinjektion"
+        }
+
+        fn fähigkeiten(&self) -> Fähigkeiten {
+            self.basis.fähigkeiten()
+        }
 
-                ergebnis = vec![vec![0.0; n]; n];
-                unroll::ausführen(&a, &b, &mut ergebnis, n, thread, &kerne);
+        fn ausführen(
+            &self,
+            a: &Matrix,
+            b: &Matrix,
+            c: &mut Matrix,
+            m: usize,
+            k: usize,
+            n: usize,
+            einstellungen: &Einstellungen,
+        ) -> Result<(), Fehler> {
+            self.basis.ausführen(a, b, c, m, k, n, einstellungen)?;
+            for &(i, j, bit) in self.stellen {
+                c[(i, j)] = f64::from_bits(c[(i, j)].to_bits() ^ (1 << bit));
+            }
+            Ok(())
+        }
+    }
+
+    // ohne Fehler müssen alle Algorithmen die Prüfsummen bestehen und dasselbe Ergebnis wie single liefern
+    fn abft_prüfen<T: Zahl>(kerne: &[CoreId]) {
+        let einstellungen: Einstellungen = Einstellungen::neu(4, vec![kerne[0]; 4]);
+
+        for &(m, k, n) in &[(1, 1, 1), (37, 21, 45), (64, 300, 32)] {
+            let a: Matrix<T> = zufallsmatrix(m, k);
+            let b: Matrix<T> = zufallsmatrix(k, n);
+            let mut c: Matrix<T> = Matrix::neu(m, n);
+            single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+
+            for algorithmus in alle::<T>() {
+                let mut ergebnis: Matrix<T> = Matrix::neu(m, n);
+                let befund: Result<Befund, Fehler> =
+                    abft::ausführen(algorithmus, &a, &b, &mut ergebnis, m, k, n, &einstellungen);
+                assert_eq!(
+                    befund,
+                    Ok(Befund::Fehlerfrei),
+                    "{} für {}",
+                    algorithmus.name(),
+                    std::any::type_name::<T>()
+                );
                 assert!(
-                    vergleich(&c, &ergebnis, n),
-                    "unroll.rs ist falsch für threads = {}, n = {}",
-                    thread,
-                    n
+                    vergleich(&c, &ergebnis, m, n),
+                    "{} ist falsch",
+                    algorithmus.name()
                 );
             }
-            i = 1;
         }
+    }
 
-        println!("\nAlle Funktionen sind korrekt");
+    #[test]
+    fn abft() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+
+        abft_prüfen::<f64>(&kerne);
+        abft_prüfen::<f32>(&kerne);
+        abft_prüfen::<i64>(&kerne);
+        abft_prüfen::<Komplex<f64>>(&kerne);
+
+        // das erweiterte Ergebnis ist (m + 1) x (n + 1), Zeile m und Spalte n sind die Prüfsummen
+        let (m, k, n): (usize, usize, usize) = (23, 31, 19);
+        let a: Matrix = zufallsmatrix(m, k);
+        let b: Matrix = zufallsmatrix(k, n);
+        let mut c: Matrix = Matrix::neu(m, n);
+        single::ausführen(&a, &b, &mut c, m, k, n, &kerne[0]).unwrap();
+        let einstellungen: Einstellungen = Einstellungen::neu(4, vec![kerne[0]; 4]);
+
+        let fälle: [(Störung, Result<Befund, Fehler>); 6] = [
+            // Bit im Exponenten und im obersten Bit des Exponenten, der Wert wird aus der Zeilensumme korrigiert
+            (
+                Störung {
+                    basis: &tiling::Tiling,
+                    stellen: &[(5, 7, 52)],
+                },
+                Ok(Befund::Korrigiert {
+                    zeile: 5,
+                    spalte: 7,
+                }),
+            ),
+            (
+                Störung {
+                    basis: &gepack::Gepack,
+                    stellen: &[(0, 18, 62)],
+                },
+                Ok(Befund::Korrigiert {
+                    zeile: 0,
+                    spalte: 18,
+                }),
+            ),
+            // falsche Prüfsummen in Spalte n = 19 bzw. Zeile m = 23 lassen c unverändert
+            (
+                Störung {
+                    basis: &simd::Simd,
+                    stellen: &[(11, 19, 55)],
+                },
+                Ok(Befund::Prüfsumme {
+                    zeile: Some(11),
+                    spalte: None,
+                }),
+            ),
+            (
+                Störung {
+                    basis: &simd_tiling::SimdTiling,
+                    stellen: &[(23, 3, 55)],
+                },
+                Ok(Befund::Prüfsumme {
+                    zeile: None,
+                    spalte: Some(3),
+                }),
+            ),
+            // mehrere falsche Werte werden gefunden, aber nicht korrigiert
+            (
+                Störung {
+                    basis: &tiling::Tiling,
+                    stellen: &[(1, 2, 60), (4, 6, 53)],
+                },
+                Err(Fehler::Abft {
+                    zeilen: vec![1, 4],
+                    spalten: vec![2, 6],
+                }),
+            ),
+            (
+                Störung {
+                    basis: &strassen::STANDARD,
+                    stellen: &[(8, 8, 54), (8, 9, 54)],
+                },
+                Err(Fehler::Abft {
+                    zeilen: vec![8],
+                    spalten: vec![8, 9],
+                }),
+            ),
+        ];
+
+        for (störung, erwartet) in fälle {
+            let mut ergebnis: Matrix = Matrix::neu(m, n);
+            let befund: Result<Befund, Fehler> =
+                abft::ausführen(&störung, &a, &b, &mut ergebnis, m, k, n, &einstellungen);
+            assert_eq!(befund, erwartet, "{:?}", störung.stellen);
+            if befund.is_ok() {
+                assert!(vergleich(&c, &ergebnis, m, n), "{:?}", störung.stellen);
+            }
+        }
+
+        // als Multiplikator um einen Basis Algorithmus, nur nicht korrigierbare Fehler werden gemeldet
+        let mut ergebnis: Matrix = Matrix::neu(m, n);
+        Abft::neu(&Störung {
+            basis: &winograd::STANDARD,
+            stellen: &[(20, 10, 52)],
+        })
+        .ausführen(&a, &b, &mut ergebnis, m, k, n, &einstellungen)
+        .unwrap();
+        assert!(vergleich(&c, &ergebnis, m, n));
+        assert!(matches!(
+            Abft::neu(&Störung {
+                basis: &single::Single,
+                stellen: &[(2, 2, 52), (3, 3, 52)],
+            })
+            .ausführen(&a, &b, &mut ergebnis, m, k, n, &einstellungen),
+            Err(Fehler::Abft { .. })
+        ));
+        assert!(matches!(
+            abft::ausführen(
+                &single::Single,
+                &a,
+                &b,
+                &mut ergebnis,
+                m,
+                k,
+                n + 1,
+                &einstellungen
+            ),
+            Err(Fehler::Dimension { matrix: "b", .. })
+        ));
+    }
+
+    // jede Matrix muss nach dem Speichern in jedem Format unverändert wieder geladen werden
+    fn dateien_prüfen<T: Dateiwert>(ordner: &std::path::Path) {
+        for (zeilen, spalten) in [(0, 3), (1, 1), (7, 5), (20, 33)] {
+            let mut matrix: Matrix<T> = zufallsmatrix(zeilen, spalten);
+            // Nullen damit das Koordinatenformat Werte auslässt
+            for wert in matrix.daten.iter_mut().step_by(3) {
+                *wert = T::NULL;
+            }
+
+            for format in Dateiformat::ALLE {
+                let name: String = format!("{}_{}x{}", format, zeilen, spalten);
+                assert_eq!(
+                    Matrix::dekodieren(&matrix.kodieren(format)).as_ref(),
+                    Ok(&matrix),
+                    "{} für {}",
+                    name,
+                    std::any::type_name::<T>()
+                );
+
+                let pfad: std::path::PathBuf = ordner.join(format!("{}.{}", name, format.endung()));
+                matrix.speichern(&pfad, format).unwrap();
+                assert_eq!(Matrix::<T>::laden(&pfad).as_ref(), Ok(&matrix), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn dateien() {
+        let ordner: std::path::PathBuf =
+            std::env::temp_dir().join(format!("multiplikation_dateien_{}", std::process::id()));
+        std::fs::create_dir_all(&ordner).unwrap();
+
+        dateien_prüfen::<f64>(&ordner);
+        dateien_prüfen::<f32>(&ordner);
+        dateien_prüfen::<i32>(&ordner);
+        dateien_prüfen::<i64>(&ordner);
+        dateien_prüfen::<Komplex<f32>>(&ordner);
+        dateien_prüfen::<Komplex<f64>>(&ordner);
+
+        // Extremwerte bleiben in Text- und Binärformaten exakt erhalten
+        let extrem: Matrix = Matrix {
+            zeilen: 2,
+            spalten: 3,
+            stride: 3,
+            daten: vec![
+                f64::MAX,
+                f64::MIN_POSITIVE,
+                -1e-300,
+                f64::INFINITY,
+                0.1,
+                -2.5e17,
+            ],
+        };
+        for format in Dateiformat::ALLE {
+            assert_eq!(
+                Matrix::dekodieren(&extrem.kodieren(format)).as_ref(),
+                Ok(&extrem),
+                "{}",
+                format
+            );
+        }
+        let fehlend: Result<Matrix, Fehler> = Matrix::laden(&ordner.join("fehlt.mtx"));
+        assert!(matches!(fehlend, Err(Fehler::Datei(_))));
+        std::fs::remove_dir_all(&ordner).unwrap();
+
+        // der Kopf einer .npy Datei endet mit \n auf einer Grenze von 64 Bytes
+        let npy: Vec<u8> = extrem.kodieren(Dateiformat::Npy);
+        let kopf: usize = 10 + u16::from_le_bytes([npy[8], npy[9]]) as usize;
+        assert_eq!((kopf % 64, npy[kopf - 1]), (0, b'\n'));
+
+        // Fortran Reihenfolge von NumPy: die Werte 1..6 spaltenweise
+        let mut fortran: Vec<u8> = b"\x93NUMPY\x01\x00".to_vec();
+        let text: &str = "{'descr': '<i8', 'fortran_order': True, 'shape': (2, 3), }\n";
+        fortran.extend_from_slice(&(text.len() as u16).to_le_bytes());
+        fortran.extend_from_slice(text.as_bytes());
+        for wert in 1..=6_i64 {
+            fortran.extend_from_slice(&wert.to_le_bytes());
+        }
+        let erwartet: Matrix<i64> = Matrix {
+            zeilen: 2,
+            spalten: 3,
+            stride: 3,
+            daten: vec![1, 3, 5, 2, 4, 6],
+        };
+        assert_eq!(Matrix::dekodieren(&fortran), Ok(erwartet));
+
+        // symmetrisch im Koordinatenformat mit Kommentaren, ganze Zahlen werden als f64 gelesen
+        let symmetrisch: &str = "%%MatrixMarket matrix coordinate integer symmetric\n% Kommentar\n\n3 3 4\n\
+                                 1 1 2\n2 1 -1\n3 2 5\n3 2 1\n";
+        let erwartet: Matrix = Matrix {
+            zeilen: 3,
+            spalten: 3,
+            stride: 3,
+            daten: vec![2.0, -1.0, 0.0, -1.0, 0.0, 6.0, 0.0, 6.0, 0.0],
+        };
+        assert_eq!(Matrix::dekodieren(symmetrisch.as_bytes()), Ok(erwartet));
+
+        // schiefsymmetrisch dicht: nur das untere Dreieck ohne Diagonale, spaltenweise
+        let schief: &str = "%%matrixmarket matrix array real skew-symmetric\n3 3\n1.5\n2\n-4\n";
+        let erwartet: Matrix = Matrix {
+            zeilen: 3,
+            spalten: 3,
+            stride: 3,
+            daten: vec![0.0, -1.5, -2.0, 1.5, 0.0, 4.0, 2.0, -4.0, 0.0],
+        };
+        assert_eq!(Matrix::dekodieren(schief.as_bytes()), Ok(erwartet));
+
+        // pattern: jeder Eintrag ist 1
+        let muster: &str = "%%MatrixMarket matrix coordinate pattern general\n2 2 2\n1 2\n2 1\n";
+        assert_eq!(
+            Matrix::<i32>::dekodieren(muster.as_bytes()).map(|m| m.daten),
+            Ok(vec![0, 1, 1, 0])
+        );
+
+        // fehlerhafte Dateien werden mit dem Format gemeldet
+        let kaputt: [(&[u8], &str); 16] = [
+            (b"hallo", "unbekannt"),
+            (
+                b"%%MatrixMarket matrix array real\n1 1\n1\n",
+                "Matrix Market",
+            ),
+            (
+                b"%%MatrixMarket matrix array complex general\n1 1\n1 2\n",
+                "Matrix Market",
+            ),
+            (
+                b"%%MatrixMarket matrix array real hermitian\n1 1\n1\n",
+                "Matrix Market",
+            ),
+            (
+                b"%%MatrixMarket matrix array real general\n2 2\n1\n2\n3\n",
+                "Matrix Market",
+            ),
+            (
+                b"%%MatrixMarket matrix array real general\n1 1\n1\n2\n",
+                "Matrix Market",
+            ),
+            (
+                b"%%MatrixMarket matrix array real general\n1 1\neins\n",
+                "Matrix Market",
+            ),
+            (
+                b"%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1.0\n",
+                "Matrix Market",
+            ),
+            (
+                b"%%MatrixMarket matrix coordinate real symmetric\n2 2 1\n1 2 1.0\n",
+                "Matrix Market",
+            ),
+            // Größen für die der Speicher nicht reicht oder die mit den Bytes über isize::MAX liegen
+            (
+                b"%%MatrixMarket matrix coordinate real general\n100000000 100000000 1\n1 1 1.0\n",
+                "Matrix Market",
+            ),
+            (
+                b"%%MatrixMarket matrix coordinate real general\n4294967296 536870912 0\n",
+                "Matrix Market",
+            ),
+            (
+                b"%%MatrixMarket matrix array real symmetric\n100000 100000\n1\n",
+                "Matrix Market",
+            ),
+            (b"\x93NUMPY\x01\x00", "NumPy"),
+            (&extrem.kodieren(Dateiformat::Npy)[..100], "NumPy"),
+            (b"MATRIX\x01\x02", "Roh"),
+            (&extrem.kodieren(Dateiformat::Roh)[..40], "Roh"),
+        ];
+        for (bytes, format) in kaputt {
+            let ergebnis: Result<Matrix, Fehler> = Matrix::dekodieren(bytes);
+            assert!(
+                matches!(&ergebnis, Err(Fehler::Dateiformat { format: f, .. }) if *f == format),
+                "{:?} für {}",
+                ergebnis,
+                String::from_utf8_lossy(bytes)
+            );
+        }
+
+        // der Elementtyp der Datei muss passen
+        let f32_matrix: Matrix<f32> = zufallsmatrix(2, 2);
+        for format in [Dateiformat::Npy, Dateiformat::Roh] {
+            assert!(matches!(
+                Matrix::<f64>::dekodieren(&f32_matrix.kodieren(format)),
+                Err(Fehler::Dateiformat { .. })
+            ));
+        }
+        let mut big_endian: Vec<u8> = extrem.kodieren(Dateiformat::Npy);
+        let stelle: usize = big_endian.windows(3).position(|x| x == b"<f8").unwrap();
+        big_endian[stelle] = b'>';
+        let ergebnis: Result<Matrix, Fehler> = Matrix::dekodieren(&big_endian);
+        assert!(
+            matches!(&ergebnis, Err(Fehler::Dateiformat { grund, .. }) if grund.contains("big-endian")),
+            "{:?}",
+            ergebnis
+        );
+    }
+
+    #[test]
+    fn generator() {
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+        let seed: u64 = startwert();
+        let (m, n): (usize, usize) = (150, 120);
+
+        // derselbe Startwert ergibt unabhängig von Threads und Platzierung dieselbe Matrix
+        let arten: [Matrixart; 10] = [
+            Matrixart::Standard,
+            Matrixart::Gleichverteilt { von: 2.0, bis: 5.0 },
+            Matrixart::Normal {
+                mittelwert: 1.0,
+                standardabweichung: 3.0,
+            },
+            Matrixart::Ganzzahlig { von: -3, bis: 7 },
+            Matrixart::Dünn { dichte: 0.1 },
+            Matrixart::Symmetrisch,
+            Matrixart::DiagonalDominant,
+            Matrixart::Einheit,
+            Matrixart::Orthogonal,
+            Matrixart::Konditioniert { kondition: 1e6 },
+        ];
+        for art in arten {
+            let generator: Generator = Generator::neu(seed, art);
+            let (zeilen, spalten): (usize, usize) = if art == Matrixart::Symmetrisch {
+                (n, n)
+            } else {
+                (m, n)
+            };
+            let matrix: Matrix = generator.matrix(zeilen, spalten).unwrap();
+            assert_eq!(
+                generator.matrix(zeilen, spalten),
+                Ok(matrix.clone()),
+                "{}",
+                art
+            );
+            for platzierung in [Platzierung::ErsteBerührung, Platzierung::Verschränkt] {
+                let parallel: Matrix = generator
+                    .matrix_parallel(zeilen, spalten, &[kerne[0]; 3], platzierung)
+                    .unwrap();
+                assert_eq!(parallel, matrix, "{} mit {}", art, platzierung);
+            }
+            if art != Matrixart::Einheit {
+                assert_ne!(
+                    Generator::neu(seed.wrapping_add(1), art).matrix(zeilen, spalten),
+                    Ok(matrix),
+                    "{}",
+                    art
+                );
+            }
+        }
+
+        // Verteilungen: Bereich, Mittelwert und Standardabweichung über m * n Werte
+        let statistik = |art: Matrixart| -> (f64, f64, f64, f64) {
+            let werte: Vec<f64> = Generator::neu(seed, art).matrix(m, n).unwrap().daten;
+            let mittelwert: f64 = werte.iter().sum::<f64>() / werte.len() as f64;
+            let varianz: f64 =
+                werte.iter().map(|x| (x - mittelwert).powi(2)).sum::<f64>() / werte.len() as f64;
+            let minimum: f64 = werte.iter().copied().fold(f64::INFINITY, f64::min);
+            let maximum: f64 = werte.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            (minimum, maximum, mittelwert, varianz.sqrt())
+        };
+        let (minimum, maximum, mittelwert, _) = statistik(arten[1]);
+        assert!(minimum >= 2.0 && maximum < 5.0 && (mittelwert - 3.5).abs() < 0.05);
+        let (_, _, mittelwert, abweichung) = statistik(arten[2]);
+        assert!((mittelwert - 1.0).abs() < 0.1 && (abweichung - 3.0).abs() < 0.1);
+        let (minimum, maximum, mittelwert, _) = statistik(arten[3]);
+        assert!(minimum == -3.0 && maximum == 7.0 && (mittelwert - 2.0).abs() < 0.1);
+        let ganz: Matrix<i64> = Generator::neu(seed, arten[3]).matrix(m, n).unwrap();
+        assert!(ganz.daten.iter().all(|x| (-3..=7).contains(x)));
+        let dünn: Matrix = Generator::neu(seed, arten[4]).matrix(m, n).unwrap();
+        let dichte: f64 = dünn.daten.iter().filter(|&&x| x != 0.0).count() as f64 / (m * n) as f64;
+        assert!((dichte - 0.1).abs() < 0.02, "Dichte {}", dichte);
+
+        // Standard entspricht den Bereichen von Zahl::zufall, komplexe Zahlen haben einen Imaginärteil
+        let standard: Matrix<i32> = Generator::neu(seed, Matrixart::Standard)
+            .matrix(m, n)
+            .unwrap();
+        assert!(standard.daten.iter().all(|x| (-10..=10).contains(x)));
+        let komplex: Matrix<Komplex<f64>> = Generator::neu(seed, Matrixart::Standard)
+            .matrix(m, n)
+            .unwrap();
+        assert!(komplex.daten.iter().any(|x| x.im != 0.0));
+        assert!(
+            komplex
+                .daten
+                .iter()
+                .all(|x| (-1.0..1.0).contains(&x.re) && (-1.0..1.0).contains(&x.im))
+        );
+
+        // Struktur der Matrizen
+        let symmetrisch: Matrix = Generator::neu(seed, Matrixart::Symmetrisch)
+            .matrix(n, n)
+            .unwrap();
+        assert_eq!(symmetrisch.transponiert(), symmetrisch);
+        assert!(matches!(
+            Generator::neu(seed, Matrixart::Symmetrisch).matrix::<f64>(m, n),
+            Err(Fehler::Dimension { .. })
+        ));
+
+        let dominant: Matrix = Generator::neu(seed, Matrixart::DiagonalDominant)
+            .matrix(m, n)
+            .unwrap();
+        for i in 0..n {
+            let rest: f64 = (0..n)
+                .filter(|&j| j != i)
+                .map(|j| dominant[(i, j)].abs())
+                .sum();
+            assert!(dominant[(i, i)] > rest);
+        }
+
+        let einheit: Matrix<Komplex<f32>> = Generator::neu(seed, Matrixart::Einheit)
+            .matrix(4, 6)
+            .unwrap();
+        for i in 0..4 {
+            for j in 0..6 {
+                let erwartet: Komplex<f32> = if i == j { Komplex::EINS } else { Komplex::NULL };
+                assert_eq!(einheit[(i, j)], erwartet);
+            }
+        }
+
+        // Q^T * Q = I bei orthonormalen Spalten, Q * Q^T = I bei orthonormalen Zeilen
+        let gram = |x: &Matrix, y: &Matrix| -> Matrix {
+            let mut ergebnis: Matrix = Matrix::neu(x.zeilen, y.spalten);
+            single::ausführen(
+                x,
+                y,
+                &mut ergebnis,
+                x.zeilen,
+                x.spalten,
+                y.spalten,
+                &kerne[0],
+            )
+            .unwrap();
+            ergebnis
+        };
+        let einheit =
+            |n: usize| -> Matrix { Generator::neu(0, Matrixart::Einheit).matrix(n, n).unwrap() };
+        for (zeilen, spalten) in [(40, 40), (50, 20), (20, 50)] {
+            let q: Matrix = Generator::neu(seed, Matrixart::Orthogonal)
+                .matrix(zeilen, spalten)
+                .unwrap();
+            let produkt: Matrix = if zeilen >= spalten {
+                gram(&q.transponiert(), &q)
+            } else {
+                gram(&q, &q.transponiert())
+            };
+            let r: usize = zeilen.min(spalten);
+            assert!(
+                vergleich(&produkt, &einheit(r), r, r),
+                "{}x{}",
+                zeilen,
+                spalten
+            );
+        }
+
+        // Kondition 1 ist orthogonal, sonst ist die Summe der Quadrate aller Werte die der Singulärwerte
+        let q: Matrix = Generator::neu(seed, Matrixart::Konditioniert { kondition: 0.5 })
+            .matrix(30, 30)
+            .unwrap();
+        assert!(vergleich(
+            &gram(&q.transponiert(), &q),
+            &einheit(30),
+            30,
+            30
+        ));
+        let kondition: f64 = 1e8;
+        let a: Matrix = Generator::neu(seed, Matrixart::Konditioniert { kondition })
+            .matrix(40, 30)
+            .unwrap();
+        let frobenius: f64 = a.daten.iter().map(|x| x * x).sum();
+        let singulär: f64 = (0..30)
+            .map(|t| kondition.powf(-(t as f64) / 29.0).powi(2))
+            .sum();
+        assert!(
+            (frobenius - singulär).abs() < 1e-10,
+            "{} {}",
+            frobenius,
+            singulär
+        );
+
+        // auch die vorberechneten Arten melden zu große Matrizen als Fehler
+        for art in [
+            Matrixart::Orthogonal,
+            Matrixart::Konditioniert { kondition: 10.0 },
+        ] {
+            for (zeilen, spalten) in [(usize::MAX, 2), (1 << 31, 1 << 31), (1 << 28, 1 << 30)] {
+                assert_eq!(
+                    Generator::neu(seed, art).matrix::<f64>(zeilen, spalten),
+                    Err(Fehler::Größe { zeilen, spalten })
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn kommandozeile() {
+        let lesen = |argumente: &[&str]| -> Result<Optionen, Fehler> {
+            let argumente: Vec<String> = argumente.iter().map(|a| a.to_string()).collect();
+            Optionen::lesen(&argumente)
+        };
+
+        assert_eq!(lesen(&[]), Ok(Optionen::default()));
+        let optionen: Optionen = lesen(&[
+            "-a",
+            "single,tiling",
+            "--algorithmus=winograd",
+            "-g",
+            "20,30x40x50",
+            "--größe=7",
+            "-t",
+            "2",
+            "--pinnen",
+            "aufzählung",
+            "-s",
+            "17",
+            "--wiederholungen=4",
+            "--format=csv",
+            "--prüfen",
+            "0",
+        ])
+        .unwrap();
+        assert_eq!(optionen.algorithmen, ["single", "tiling", "winograd"]);
+        assert_eq!(optionen.größen, [(20, 20, 20), (30, 40, 50), (7, 7, 7)]);
+        assert_eq!(optionen.threads, Some(2));
+        assert_eq!(optionen.pinnen, Strategie::Aufzählung);
+        assert_eq!(optionen.seed, Some(17));
+        assert_eq!(optionen.wiederholungen, 4);
+        assert_eq!(optionen.ausgabe, Ausgabe::Csv);
+        assert_eq!(optionen.prüfen, 0);
+        assert!(lesen(&["--liste"]).unwrap().liste);
+        assert!(lesen(&["-h"]).unwrap().hilfe);
+
+        for falsch in [
+            &["--unbekannt"][..],
+            &["-a", "unbekannt"],
+            &["-g", "3x4"],
+            &["-g", "zehn"],
+            &["-t"],
+            &["-p", "alle"],
+            &["-f", "xml"],
+            &["-w", "0"],
+            // "=" nur bei langen Optionen
+            &["-w=4"],
+            &["--datei-a", "a.npy"],
+        ] {
+            assert!(
+                matches!(lesen(falsch), Err(Fehler::Argument(_))),
+                "{:?}",
+                falsch
+            );
+        }
+
+        // Messung mit dem Generator, das Ergebnis wird mit Freivalds geprüft
+        let mut optionen: Optionen = lesen(&[
+            "-a",
+            "single,tiling",
+            "-g",
+            "20x30x10",
+            "-t",
+            "1",
+            "-p",
+            "aufzählung",
+            "-w",
+            "2",
+            "--prüfen",
+            "5",
+        ])
+        .unwrap();
+        optionen.seed = Some(startwert());
+        let messungen: Vec<Messung> = kommandozeile::messen(&optionen).unwrap();
+        assert_eq!(messungen.len(), 2);
+        for (messung, name) in messungen.iter().zip(["single", "tiling"]) {
+            assert_eq!(messung.algorithmus, name);
+            assert_eq!((messung.m, messung.k, messung.n), (20, 30, 10));
+            assert_eq!(messung.seed, optionen.seed);
+            assert_eq!(messung.prüfung, Prüfung::Richtig);
+            assert!(messung.beste <= messung.mittel && messung.gflops() > 0.0);
+        }
+
+        // Eingaben aus Dateien
+        let ordner: std::path::PathBuf = std::env::temp_dir().join(format!(
+            "multiplikation_kommandozeile_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&ordner).unwrap();
+        let (datei_a, datei_b) = (ordner.join("a.npy"), ordner.join("b.mtx"));
+        zufallsmatrix::<f64>(9, 6)
+            .speichern(&datei_a, Dateiformat::Npy)
+            .unwrap();
+        zufallsmatrix::<f64>(6, 11)
+            .speichern(&datei_b, Dateiformat::MatrixMarketDicht)
+            .unwrap();
+        optionen.datei_a = Some(datei_a);
+        optionen.datei_b = Some(datei_b);
+        optionen.wiederholungen = 1;
+        let dateien: Vec<Messung> = kommandozeile::messen(&optionen).unwrap();
+        std::fs::remove_dir_all(&ordner).unwrap();
+        assert!(
+            dateien
+                .iter()
+                .all(|messung| (messung.m, messung.k, messung.n) == (9, 6, 11)
+                    && messung.seed.is_none()
+                    && messung.prüfung == Prüfung::Richtig)
+        );
+
+        // kleine Eingaben mit einem Ergebnis über dem Adressraum sind ein Fehler statt eines Abbruchs
+        let mut zu_groß: Optionen = optionen.clone();
+        (zu_groß.datei_a, zu_groß.datei_b) = (None, None);
+        zu_groß.größen = vec![(1 << 23, 1, 1 << 23)];
+        assert_eq!(
+            kommandozeile::messen(&zu_groß).unwrap_err(),
+            Fehler::Größe {
+                zeilen: 1 << 23,
+                spalten: 1 << 23
+            }
+        );
+
+        // Ausgabeformate
+        let tabelle: String = kommandozeile::ausgeben(&messungen, Ausgabe::Tabelle);
+        assert!(tabelle.starts_with(&format!("seed: {}\n", optionen.seed.unwrap())));
+        assert_eq!(tabelle.lines().count(), 4);
+        assert!(tabelle.lines().nth(3).unwrap().starts_with("tiling"));
+        let csv: String = kommandozeile::ausgeben(&dateien, Ausgabe::Csv);
+        let zeilen: Vec<&str> = csv.lines().collect();
+        assert_eq!(zeilen.len(), 3);
+        assert!(zeilen[1].starts_with("single,9,6,11,1,,") && zeilen[1].ends_with(",richtig"));
+        let json: String = kommandozeile::ausgeben(&messungen, Ausgabe::Json);
+        assert!(json.starts_with("[\n") && json.ends_with("]\n"));
+        assert!(json.contains("\"algorithmus\": \"single\", \"m\": 20, \"k\": 30, \"n\": 10"));
+        assert_eq!(json.matches("\"prüfung\": \"richtig\"").count(), 2);
+        assert_eq!(kommandozeile::ausgeben(&[], Ausgabe::Json), "[\n\n]\n");
+    }
+
+    #[test]
+    fn auswertung() {
+        assert_eq!(auswertung::operationen(3, 4, 5), 120);
+        assert_eq!(
+            auswertung::gflops(1000, 1000, 1000, std::time::Duration::from_millis(500)),
+            4.0
+        );
+
+        let ergebnis = |algorithmus: &str, threads: usize, n: usize, millisekunden: u64| Ergebnis {
+            algorithmus: algorithmus.to_string(),
+            threads,
+            m: n,
+            k: n,
+            n,
+            zeit: std::time::Duration::from_millis(millisekunden),
+        };
+        let ergebnisse: Vec<Ergebnis> = vec![
+            ergebnis("single", 1, 100, 80),
+            ergebnis("tiling", 2, 100, 40),
+            ergebnis("tiling", 4, 100, 25),
+            ergebnis("single", 1, 200, 640),
+            ergebnis("tiling", 4, 200, 100),
+            ergebnis("tiling", 4, 300, 300),
+        ];
+        let effizienzen: Vec<Effizienz> = auswertung::effizienz(&ergebnisse, "single");
+        assert_eq!(effizienzen.len(), ergebnisse.len());
+        let erwartet: [Option<(f64, f64)>; 6] = [
+            Some((1.0, 1.0)),
+            Some((2.0, 1.0)),
+            Some((3.2, 0.8)),
+            Some((1.0, 1.0)),
+            Some((6.4, 1.6)),
+            // ohne Referenz für diese Größe
+            None,
+        ];
+        for (effizienz, erwartet) in effizienzen.iter().zip(erwartet) {
+            let tatsächlich: Option<(f64, f64)> = effizienz.speedup.zip(effizienz.effizienz);
+            assert_eq!(tatsächlich.is_some(), erwartet.is_some());
+            if let (Some((speedup, effizienz)), Some((s, e))) = (tatsächlich, erwartet) {
+                assert!((speedup - s).abs() < 1e-12 && (effizienz - e).abs() < 1e-12);
+            }
+        }
+
+        let csv: String = auswertung::csv(&effizienzen);
+        let zeilen: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            zeilen[0],
+            "algorithmus,threads,m,k,n,zeit_ms,gflops,speedup,effizienz"
+        );
+        assert_eq!(zeilen[2], "tiling,2,100,100,100,40,0.05,2,1");
+        assert_eq!(zeilen[6], "tiling,4,300,300,300,300,0.18,,");
+    }
+
+    #[test]
+    fn konfiguration() {
+        use std::time::Duration;
+
+        assert_eq!(Konfiguration::aus_text(""), Ok(Konfiguration::voll()));
+        assert_eq!(
+            Konfiguration::aus_quellen(None, |_| None),
+            Ok(Konfiguration::voll())
+        );
+        for profil in [Konfiguration::voll(), Konfiguration::schnell()] {
+            assert_eq!(Konfiguration::aus_text(&profil.to_string()), Ok(profil));
+        }
+
+        // das Profil wird unabhängig von der Reihenfolge zuerst angewendet
+        let text: &str = "
+            # Kommentar
+            groessen = 32, 64
+            threads = 1,4,8   # Kommentar am Ende
+            profil = schnell
+            algorithmen = single,tiling
+            verteilung_groessen =
+            pinnen = aufzählung
+            anzahl = 20
+            zeit = 2.5
+        ";
+        let konfiguration: Konfiguration = Konfiguration::aus_text(text).unwrap();
+        assert_eq!(
+            konfiguration,
+            Konfiguration {
+                algorithmen: vec!["single".to_string(), "tiling".to_string()],
+                größen: vec![32, 64],
+                verteilung_größen: Vec::new(),
+                threads: Threadauswahl::Liste(vec![1, 4, 8]),
+                pinnen: Strategie::Aufzählung,
+                anzahl: 20,
+                zeit: Duration::from_millis(2500),
+                ..Konfiguration::schnell()
+            }
+        );
+        assert!(konfiguration.gewählt("tiling") && !konfiguration.gewählt("winograd"));
+        assert!(Konfiguration::voll().gewählt("winograd"));
+
+        // Umgebungsvariablen haben Vorrang vor der Datei
+        let umgebung = |variable: &str| match variable {
+            "MULTIPLIKATION_BENCH_GROESSEN" => Some("128".to_string()),
+            "MULTIPLIKATION_BENCH_THREADS" => Some("maximum".to_string()),
+            "MULTIPLIKATION_BENCH_ALGORITHMEN" => Some("alle".to_string()),
+            _ => None,
+        };
+        let konfiguration: Konfiguration =
+            Konfiguration::aus_quellen(Some(("datei", text)), umgebung).unwrap();
+        assert_eq!(konfiguration.größen, [128]);
+        assert_eq!(konfiguration.threads, Threadauswahl::Maximum);
+        assert!(konfiguration.algorithmen.is_empty());
+        assert_eq!(konfiguration.anzahl, 20);
+
+        assert_eq!(Threadauswahl::Alle.threads(4), [2, 3, 4]);
+        assert_eq!(Threadauswahl::Maximum.threads(4), [4]);
+        assert_eq!(Threadauswahl::Liste(vec![1, 4, 8]).threads(4), [1, 4]);
+
+        // Fehler nennen Herkunft und Zeile
+        assert_eq!(
+            Konfiguration::aus_quellen(Some(("datei", "\nanzahl = 5")), |_| None),
+            Err(Fehler::Argument(
+                "datei:2: Criterion braucht mindestens 10 Messungen, nicht 5".to_string()
+            ))
+        );
+        for falsch in [
+            "groessen",
+            "unbekannt = 1",
+            "profil = langsam",
+            "algorithmen = unbekannt",
+            "groessen = 0",
+            "threads = viele",
+            "pinnen = alle",
+            "zeit = 0",
+            "aufwaermen = nan",
+        ] {
+            assert!(
+                matches!(Konfiguration::aus_text(falsch), Err(Fehler::Argument(_))),
+                "{}",
+                falsch
+            );
+        }
+        let umgebung =
+            |variable: &str| (variable == "MULTIPLIKATION_BENCH_ZEIT").then(|| "lange".to_string());
+        assert!(matches!(
+            Konfiguration::aus_quellen(None, umgebung),
+            Err(Fehler::Argument(grund)) if grund.starts_with("MULTIPLIKATION_BENCH_ZEIT: ")
+        ));
+    }
+
+    #[test]
+    fn regression() {
+        use std::time::Duration;
+
+        // Werte der t-Verteilung: Cauchy bei einem Freiheitsgrad, kritische Werte aus Tabellen
+        assert!((regression::student_t_oben(1.0, 1.0) - 0.25).abs() < 1e-12);
+        assert!((regression::student_t_oben(0.0, 7.0) - 0.5).abs() < 1e-12);
+        assert!((regression::student_t_oben(2.228, 10.0) - 0.025).abs() < 1e-4);
+        assert!((regression::student_t_oben(-2.228, 10.0) - 0.975).abs() < 1e-4);
+        assert!((regression::student_t_oben(1.645, 1e6) - 0.05).abs() < 1e-4);
+        assert!((regression::welch((1.0, 0.0, 5), (1.0, 0.0, 5)) - 0.5).abs() < 1e-12);
+        assert_eq!(regression::welch((2.0, 0.0, 5), (1.0, 0.0, 5)), 0.0);
+
+        let eintrag = |basislinie: &str, algorithmus: &str, mikrosekunden: &[u64]| Eintrag {
+            basislinie: basislinie.to_string(),
+            algorithmus: algorithmus.to_string(),
+            m: 64,
+            k: 32,
+            n: 16,
+            threads: 4,
+            prozessor: "Prozessor\tmit Tabulator".to_string(),
+            commit: "abc1234".to_string(),
+            zeiten: mikrosekunden
+                .iter()
+                .map(|&zeit| Duration::from_micros(zeit))
+                .collect(),
+        };
+        let basis: Vec<Eintrag> = vec![
+            eintrag("basis", "single", &[100, 101, 99, 100, 102, 98]),
+            eintrag("basis", "tiling", &[100, 101, 99, 100, 102, 98]),
+            eintrag("basis", "simd", &[100, 101, 99, 100, 102, 98]),
+            eintrag("basis", "gepack", &[100, 101, 99, 100, 102, 98]),
+            eintrag("basis", "unroll", &[100]),
+            eintrag("andere", "rayon", &[100, 101]),
+        ];
+        let neu: Vec<Eintrag> = vec![
+            // deutlich langsamer, nur wenig langsamer, schneller, unverändert
+            eintrag("neu", "single", &[120, 121, 119, 120, 122, 118]),
+            eintrag("neu", "tiling", &[103, 104, 102, 103, 105, 101]),
+            eintrag("neu", "simd", &[80, 81, 79, 80, 82, 78]),
+            eintrag("neu", "gepack", &[101, 99, 100, 102, 98, 100]),
+            // zu wenige Zeiten in der Basislinie für einen Test
+            eintrag("neu", "unroll", &[200, 201]),
+            // nur in einer anderen Basislinie bzw. auf einem anderen Prozessor
+            eintrag("neu", "rayon", &[100, 101]),
+            Eintrag {
+                prozessor: "anderer".to_string(),
+                ..eintrag("neu", "single", &[100, 101])
+            },
+        ];
+        let urteile: Vec<Urteil> = regression::vergleichen(&basis, "basis", &neu, 0.05, 0.05)
+            .iter()
+            .map(|vergleich| vergleich.urteil)
+            .collect();
+        assert_eq!(
+            urteile,
+            [
+                Urteil::Regression,
+                Urteil::Langsamer,
+                Urteil::Schneller,
+                Urteil::Unverändert,
+                Urteil::Unverändert,
+                Urteil::OhneBasis,
+                Urteil::OhneBasis,
+            ]
+        );
+        // mit höherer Schwelle ist die Verlangsamung um 20 % keine Regression
+        assert_eq!(
+            regression::vergleichen(&basis, "basis", &neu[..1], 0.25, 0.05)[0].urteil,
+            Urteil::Langsamer
+        );
+
+        let vergleiche = regression::vergleichen(&basis, "basis", &neu, 0.05, 0.05);
+        assert!((vergleiche[0].änderung.unwrap() - 0.2).abs() < 1e-9);
+        let bericht: String = regression::bericht(&vergleiche, "basis", 0.05);
+        assert!(bericht.contains("REGRESSION") && bericht.contains("+20.0 %"));
+        assert!(bericht.ends_with("1 von 7 Konfigurationen mit Regression\n"));
+        assert!(
+            regression::bericht(&vergleiche[2..], "basis", 0.05).ends_with("keine Regression\n")
+        );
+
+        // Ergebnisdatei: Tabulatoren in Namen werden ersetzt, sonst bleibt alles erhalten
+        let gelesen: Vec<Eintrag> = regression::lesen(&regression::text(&basis)).unwrap();
+        assert_eq!(gelesen.len(), basis.len());
+        assert_eq!(gelesen[0].prozessor, "Prozessor mit Tabulator");
+        assert_eq!(gelesen[0].zeiten, basis[0].zeiten);
+        for falsch in [
+            "",
+            "falscher Kopf\n",
+            "basislinie\talgorithmus\tm\tk\tn\tthreads\tprozessor\tcommit\tzeiten_ns\nzu\twenige\n",
+            "basislinie\talgorithmus\tm\tk\tn\tthreads\tprozessor\tcommit\tzeiten_ns\na\tb\tx\t1\t1\t1\tc\td\t1\n",
+            "basislinie\talgorithmus\tm\tk\tn\tthreads\tprozessor\tcommit\tzeiten_ns\na\tb\t1\t1\t1\t1\tc\td\t\n",
+        ] {
+            assert!(
+                matches!(regression::lesen(falsch), Err(Fehler::Dateiformat { .. })),
+                "{:?}",
+                falsch
+            );
+        }
+
+        // speichern ersetzt Einträge derselben Basislinie und Konfiguration
+        let datei: std::path::PathBuf = std::env::temp_dir().join(format!(
+            "multiplikation_ergebnisse_{}.tsv",
+            std::process::id()
+        ));
+        assert_eq!(regression::laden(&datei), Ok(Vec::new()));
+        regression::speichern(&datei, &basis).unwrap();
+        let ersetzt: Eintrag = eintrag("basis", "single", &[50, 51]);
+        regression::speichern(&datei, &[ersetzt.clone(), eintrag("neu", "single", &[60])]).unwrap();
+        let gespeichert: Vec<Eintrag> = regression::laden(&datei).unwrap();
+        std::fs::remove_file(&datei).unwrap();
+        assert_eq!(gespeichert.len(), basis.len() + 1);
+        assert_eq!(
+            gespeichert
+                .iter()
+                .filter(|e| e.basislinie == "basis" && e.algorithmus == "single")
+                .map(|e| e.zeiten.clone())
+                .collect::<Vec<_>>(),
+            [ersetzt.zeiten]
+        );
+
+        // Optionen der Kommandozeile
+        let argumente: Vec<String> = [
+            "--ergebnisse",
+            "basis.tsv",
+            "--speichern",
+            "neu",
+            "--vergleichen=alt",
+            "--schwelle",
+            "10",
+            "--signifikanz",
+            "0.01",
+        ]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+        let optionen: Optionen = Optionen::lesen(&argumente).unwrap();
+        assert_eq!(optionen.ergebnisse, std::path::PathBuf::from("basis.tsv"));
+        assert_eq!(optionen.speichern.as_deref(), Some("neu"));
+        assert_eq!(optionen.vergleichen.as_deref(), Some("alt"));
+        assert!((optionen.schwelle - 0.1).abs() < 1e-12 && optionen.signifikanz == 0.01);
+        for falsch in [["--schwelle", "-1"], ["--signifikanz", "1"]] {
+            let argumente: Vec<String> = falsch.iter().map(|a| a.to_string()).collect();
+            assert!(matches!(
+                Optionen::lesen(&argumente),
+                Err(Fehler::Argument(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn roofline() {
+        use std::time::Duration;
+
+        // die Mikrobenchmarks mit wenig Aufwand, beide Threads auf einem Kern
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+        let spitze: f64 = roofline::spitze_messen(&[kerne[0]; 2], 2, 1 << 10).unwrap();
+        let bandbreite: f64 = roofline::bandbreite_messen(&[kerne[0]; 2], 2, 1 << 20).unwrap();
+        assert!(spitze.is_finite() && spitze > 0.0);
+        assert!(bandbreite.is_finite() && bandbreite > 0.0);
+
+        // alles passt in den Cache: jede Matrix wird einmal übertragen
+        assert_eq!(Zugriff::Zeilenweise.bytes(4, 4, 4, 1 << 20), 384.0);
+        assert!((Zugriff::Zeilenweise.intensität(4, 4, 4, 1 << 20) - 1.0 / 3.0).abs() < 1e-12);
+        // nur b passt in den Cache
+        assert_eq!(Zugriff::Zeilenweise.bytes(100, 100, 100, 80_000), 240_000.0);
+        // ohne Cache wird b für jede Zeile von a erneut gelesen
+        assert_eq!(Zugriff::Zeilenweise.bytes(10, 20, 30, 0), 52_000.0);
+        let blöcke: Zugriff = Zugriff::Blöcke {
+            mc: 2,
+            kc: 4,
+            nc: 8,
+        };
+        assert_eq!(blöcke.bytes(10, 10, 10, 0), 7_200.0);
+        assert!(matches!(Zugriff::von("gepack"), Zugriff::Blöcke { .. }));
+        assert_eq!(Zugriff::von("single"), Zugriff::Zeilenweise);
+
+        let maschine: Maschine = Maschine {
+            spitze: 100e9,
+            bandbreite: 10e9,
+            threads: 2,
+            pfad: cpu::pfad(),
+        };
+        assert_eq!(maschine.knick(), 10.0);
+        assert_eq!(maschine.dach(1.0), 10e9);
+        assert_eq!(maschine.dach(100.0), 100e9);
+
+        let ergebnis = |algorithmus: &str, n: usize| Ergebnis {
+            algorithmus: algorithmus.to_string(),
+            threads: 2,
+            m: n,
+            k: n,
+            n,
+            zeit: Duration::from_millis(10),
+        };
+        // 8 Werte passen in den Cache, 1000 nicht
+        let punkte: Vec<Punkt> =
+            roofline::punkte(&[ergebnis("single", 8), ergebnis("gepack", 1000)], 1 << 20);
+        assert_eq!(punkte.len(), 2);
+        assert!((punkte[0].intensität - 2.0 / 3.0).abs() < 1e-12);
+        assert!(punkte[1].intensität > maschine.knick());
+        assert!((punkte[1].leistung - 200e9).abs() < 1.0);
+
+        let csv: String = roofline::csv(&maschine, &punkte);
+        let zeilen: Vec<&str> = csv.lines().collect();
+        assert_eq!(zeilen.len(), 3);
+        assert!(zeilen[0].starts_with("algorithmus,threads,m,k,n,intensität_flop_pro_byte"));
+        assert!(zeilen[1].starts_with("single,2,8,8,8,"));
+        assert!(zeilen[1].contains(",speicher,100,10"));
+        assert!(zeilen[2].starts_with("gepack,2,1000,1000,1000,"));
+        assert!(zeilen[2].contains(",rechnen,100,10"));
+
+        let svg: String = roofline::svg(&maschine, &punkte);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        // ein Kreis mit Titel je Punkt, die Legende hat einen Kreis je Algorithmus
+        assert_eq!(svg.matches("<title>").count(), 2);
+        assert_eq!(svg.matches("<circle").count(), 4);
+
+        // Caches aus einem nachgebauten sysfs
+        let wurzel: std::path::PathBuf =
+            std::env::temp_dir().join(format!("multiplikation_roofline_{}", std::process::id()));
+        for (index, größe) in [("index0", "48K"), ("index2", "2M"), ("index3", "1536K")] {
+            let ordner: std::path::PathBuf = wurzel.join("system/cpu/cpu0/cache").join(index);
+            std::fs::create_dir_all(&ordner).unwrap();
+            std::fs::write(ordner.join("size"), format!("{}\n", größe)).unwrap();
+        }
+        assert_eq!(topologie::größter_cache_aus(&wurzel), Some(2 << 20));
+        assert_eq!(topologie::größter_cache_aus(&wurzel.join("fehlt")), None);
+        std::fs::remove_dir_all(&wurzel).unwrap();
+    }
+
+    #[test]
+    fn zaehler() {
+        let a: Werte = Werte([Some(100), Some(250), None, Some(3), None]);
+        let b: Werte = Werte([Some(50), None, None, Some(1), None]);
+        assert_eq!(
+            Werte::summe(&[a, b]),
+            Werte([Some(150), Some(250), None, Some(4), None])
+        );
+        assert_eq!(a.wert(Ereignis::Instruktionen), Some(250));
+        assert_eq!(a.ipc(), Some(2.5));
+        assert_eq!(b.ipc(), None);
+        assert_eq!(Werte([Some(0), Some(5), None, None, None]).ipc(), None);
+        let erfassung: Erfassung = Erfassung {
+            aufrufer: b,
+            threads: vec![a, b],
+            fehler: None,
+            unvollständig: false,
+        };
+        assert_eq!(
+            erfassung.summe(),
+            Werte([Some(200), Some(250), None, Some(5), None])
+        );
+
+        // je Thread von rechenpool::verteilen, ohne Zähler (z.B. in Containern) bleiben alle Werte leer
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+        for ausführung in [Ausführung::Rechenpool, Ausführung::Spawnen] {
+            assert!(!zaehler::aktiv());
+            let (ergebnis, erfassung): (Result<Vec<u64>, Fehler>, Erfassung) =
+                zaehler::messen(|| {
+                    assert!(zaehler::aktiv());
+                    rechenpool::verteilen(3, &[kerne[0]; 3], ausführung, |z| {
+                        Ok((0..100_000u64)
+                            .map(|i| std::hint::black_box(i * z as u64))
+                            .sum())
+                    })
+                });
+            assert!(!zaehler::aktiv());
+            assert_eq!(ergebnis.unwrap().len(), 3);
+            assert_eq!(erfassung.threads.len(), 3);
+            match &erfassung.fehler {
+                None => assert!(
+                    erfassung
+                        .threads
+                        .iter()
+                        .all(|werte| werte.0.iter().any(Option::is_some))
+                ),
+                Some(f) => {
+                    assert!(matches!(f, Fehler::Zähler(_)));
+                    assert_eq!(erfassung.summe(), Werte::default());
+                }
+            }
+        }
+
+        // eine panic beendet die Erfassung
+        let panik = std::panic::catch_unwind(|| zaehler::messen(|| panic!("Test")));
+        assert!(panik.is_err() && !zaehler::aktiv());
+
+        // Messung mit Zählern über die Kommandozeile
+        let argumente: Vec<String> = [
+            "-a",
+            "single,tiling",
+            "-g",
+            "16",
+            "-t",
+            "1",
+            "-p",
+            "aufzählung",
+            "-w",
+            "2",
+            "--zähler",
+        ]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+        let mut optionen: Optionen = Optionen::lesen(&argumente).unwrap();
+        assert!(optionen.zähler);
+        optionen.seed = Some(startwert());
+        let messungen: Vec<Messung> = kommandozeile::messen(&optionen).unwrap();
+        assert!(messungen.iter().all(|messung| messung.zähler.is_some()));
+        // tiling verteilt auf einen Thread: Aufrufer, Thread 0 und Summe
+        assert_eq!(messungen[1].zähler.as_ref().unwrap().threads.len(), 1);
+
+        let tabelle: String = kommandozeile::ausgeben(&messungen, Ausgabe::Tabelle);
+        let zähler: Vec<&str> = tabelle
+            .lines()
+            .skip_while(|zeile| !zeile.starts_with("Hardware Zähler"))
+            .collect();
+        assert_eq!(zähler.len(), 1 + 1 + 1 + 3);
+        assert!(zähler[1].contains("zyklen") && zähler[1].ends_with("ipc"));
+        assert!(zähler[5].starts_with("tiling") && zähler[5].contains("summe"));
+        let csv: String = kommandozeile::ausgeben(&messungen, Ausgabe::Csv);
+        assert!(csv.lines().next().unwrap().ends_with(
+            ",prüfung,zyklen,instruktionen,l1_fehlzugriffe,llc_fehlzugriffe,sprungfehler,ipc,vollstaendig"
+        ));
+        assert!(csv.lines().all(|zeile| zeile.matches(',').count() == 16));
+        assert!(csv.lines().skip(1).all(|zeile| zeile.ends_with(",true")));
+        let json: String = kommandozeile::ausgeben(&messungen, Ausgabe::Json);
+        assert_eq!(
+            json.matches("\"zaehler\": {\"aufrufer\": {\"zyklen\": ")
+                .count(),
+            2
+        );
+
+        // Teilprodukte von strassen und winograd auf eigenen Threads werden nicht gezählt, die Summe fehlt dann
+        let a: Matrix = zufallsmatrix(64, 64);
+        let b: Matrix = zufallsmatrix(64, 64);
+        let varianten: [&dyn Multiplikator<f64>; 2] = [
+            &strassen::Strassen::neu(16, &tiling::Tiling),
+            &winograd::Winograd::neu(16, &tiling::Tiling),
+        ];
+        for algorithmus in varianten {
+            for (threads, unvollständig) in [(1, false), (3, true)] {
+                let einstellungen: Einstellungen = Einstellungen::neu(threads, vec![kerne[0]; 3]);
+                let mut c: Matrix = Matrix::neu(64, 64);
+                let (ergebnis, erfassung): (Result<(), Fehler>, Erfassung) =
+                    zaehler::messen(|| {
+                        algorithmus.ausführen(&a, &b, &mut c, 64, 64, 64, &einstellungen)
+                    });
+                ergebnis.unwrap();
+                assert_eq!(erfassung.unvollständig, unvollständig);
+            }
+        }
+        let mut ohne_summe: Messung = messungen[1].clone();
+        ohne_summe.zähler.as_mut().unwrap().unvollständig = true;
+        let tabelle: String = kommandozeile::ausgeben(&[ohne_summe.clone()], Ausgabe::Tabelle);
+        assert!(tabelle.lines().last().unwrap().contains("unvollständig"));
+        assert!(!tabelle.contains("summe"));
+        let csv: String = kommandozeile::ausgeben(&[ohne_summe.clone()], Ausgabe::Csv);
+        assert!(csv.lines().nth(1).unwrap().ends_with(",,,,,,,false"));
+        let json: String = kommandozeile::ausgeben(&[ohne_summe], Ausgabe::Json);
+        assert!(json.contains("\"summe\": null, \"vollstaendig\": false"));
+    }
+
+    #[test]
+    fn schranke() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let kerne: Vec<CoreId> = alle_kerne().unwrap();
+        for ausführung in [Ausführung::Spawnen, Ausführung::Rechenpool] {
+            // in jedem Durchgang sind alle Threads angekommen bevor einer weiterläuft
+            let schranke: Schranke = Schranke::neu(3);
+            let angekommen: AtomicUsize = AtomicUsize::new(0);
+            rechenpool::verteilen(3, &[kerne[0]; 3], ausführung, |_| {
+                for durchgang in 1..=5 {
+                    angekommen.fetch_add(1, Ordering::SeqCst);
+                    schranke.warten()?;
+                    assert!(angekommen.load(Ordering::SeqCst) >= 3 * durchgang);
+                    schranke.warten()?;
+                }
+                Ok(())
+            })
+            .unwrap();
+
+            // eine panic vor der Schranke lässt die anderen Threads nicht für immer warten
+            let schranke: Schranke = Schranke::neu(3);
+            let ergebnis: Result<Vec<()>, Fehler> =
+                rechenpool::verteilen(3, &[kerne[0]; 3], ausführung, |z| {
+                    let _wächter = schranke.wächter();
+                    if z == 1 {
+                        panic!("Test");
+                    }
+                    schranke.warten()?;
+                    schranke.warten()
+                });
+            assert_eq!(ergebnis, Err(Fehler::ThreadPanik), "{}", ausführung);
+        }
     }
 }
@@ -0,0 +1,337 @@
+use crate::fehler::{self, Fehler};
+use core_affinity::CoreId;
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/*
+    Art eines Kerns auf Prozessoren mit unterschiedlich schnellen Kernen, z.B. P- und E-Kerne beim i7-14700k.
+    Auf Prozessoren mit nur einer Art sind alle Kerne Leistungskerne
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kernart {
+    Leistung,
+    Effizienz,
+}
+
+/*
+    ein logischer Prozessor (Hardware Thread) aus /sys/devices/system/cpu/cpuN
+
+    id: N, entspricht CoreId::id
+    paket: physical_package_id
+    kern: core_id des physischen Kerns innerhalb des Pakets
+    smt: Position in thread_siblings_list, 0 ist der erste Hardware Thread eines physischen Kerns
+    l2, l3: id des Caches dieser Ebene, None wenn sysfs keinen Cache meldet
+    kapazität: cpu_capacity, falls vorhanden (relative Rechenleistung, 1024 = schnellster Kern)
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Prozessor {
+    pub id: usize,
+    pub paket: usize,
+    pub kern: usize,
+    pub smt: usize,
+    pub art: Kernart,
+    pub l2: Option<usize>,
+    pub l3: Option<usize>,
+    pub kapazität: Option<usize>,
+}
+
+/*
+    Reihenfolge in der die Threads auf die Prozessoren gepinnt werden, Thread z bekommt den z-ten Prozessor
+
+    Aufzählung: Reihenfolge von core_affinity::get_core_ids(), bisheriges Verhalten
+    PhysischZuerst: zuerst ein Hardware Thread je physischem Kern (Leistungskerne vor Effizienzkernen), danach
+                    die SMT Geschwister
+    NurLeistung: nur Leistungskerne, physische Kerne zuerst
+    NurEffizienz: nur Effizienzkerne, physische Kerne zuerst
+    JeL2: ein Prozessor je L2 Cache, z.B. einer je Cluster von 4 E-Kernen, Leistungskerne zuerst
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategie {
+    Aufzählung,
+    PhysischZuerst,
+    NurLeistung,
+    NurEffizienz,
+    JeL2,
+}
+
+impl Strategie {
+    pub const ALLE: [Strategie; 5] = [
+        Strategie::Aufzählung,
+        Strategie::PhysischZuerst,
+        Strategie::NurLeistung,
+        Strategie::NurEffizienz,
+        Strategie::JeL2,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategie::Aufzählung => "aufzählung",
+            Strategie::PhysischZuerst => "physisch_zuerst",
+            Strategie::NurLeistung => "nur_leistung",
+            Strategie::NurEffizienz => "nur_effizienz",
+            Strategie::JeL2 => "je_l2",
+        }
+    }
+
+    // Strategie zum Namen aus name, z.B. für Kommandozeile und Konfiguration
+    pub fn aus_name(name: &str) -> Option<Strategie> {
+        Strategie::ALLE
+            .into_iter()
+            .find(|strategie| strategie.name() == name)
+    }
+}
+
+impl fmt::Display for Strategie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/*
+    alle logischen Prozessoren des Systems, aufsteigend nach id
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Topologie {
+    pub prozessoren: Vec<Prozessor>,
+}
+
+impl Topologie {
+    /*
+        liest die Topologie aus /sys/devices, nur unter Linux verfügbar
+    */
+    pub fn lesen() -> Result<Topologie, Fehler> {
+        Topologie::lesen_aus(Path::new("/sys/devices"))
+    }
+
+    /*
+        liest die Topologie aus einem Verzeichnis mit dem Aufbau von /sys/devices, z.B. für Tests. P- und E-Kerne
+        werden bei Intel über cpu_core/cpus und cpu_atom/cpus erkannt, sonst über unterschiedliche cpu_capacity
+    */
+    pub fn lesen_aus(wurzel: &Path) -> Result<Topologie, Fehler> {
+        let verzeichnis: PathBuf = wurzel.join("system/cpu");
+        let einträge: fs::ReadDir = fs::read_dir(&verzeichnis)
+            .map_err(|f| Fehler::Topologie(format!("{}: {}", verzeichnis.display(), f)))?;
+
+        let mut prozessoren: Vec<Prozessor> = Vec::new();
+        for eintrag in einträge.flatten() {
+            let name: String = eintrag.file_name().to_string_lossy().into_owned();
+            let Some(id) = name
+                .strip_prefix("cpu")
+                .and_then(|n| n.parse::<usize>().ok())
+            else {
+                continue;
+            };
+
+            // ausgeschaltete Prozessoren haben kein topology Verzeichnis
+            let pfad: PathBuf = eintrag.path();
+            if !pfad.join("topology").is_dir() {
+                continue;
+            }
+            prozessoren.push(Topologie::prozessor_lesen(&pfad, id)?);
+        }
+
+        if prozessoren.is_empty() {
+            return Err(Fehler::Topologie(format!(
+                "keine Prozessoren in {}",
+                verzeichnis.display()
+            )));
+        }
+        prozessoren.sort_by_key(|p| p.id);
+
+        Topologie::arten_erkennen(wurzel, &mut prozessoren);
+        Ok(Topologie { prozessoren })
+    }
+
+    fn prozessor_lesen(pfad: &Path, id: usize) -> Result<Prozessor, Fehler> {
+        let zahl = |datei: &str, lesen: fn(&Path) -> Option<usize>| -> Result<usize, Fehler> {
+            lesen(&pfad.join(datei))
+                .ok_or_else(|| Fehler::Topologie(format!("{} fehlt", pfad.join(datei).display())))
+        };
+
+        let geschwister: Vec<usize> =
+            fs::read_to_string(pfad.join("topology/thread_siblings_list"))
+                .map(|text| liste_lesen(&text))
+                .unwrap_or_else(|_| vec![id]);
+
+        // Caches, die Ebene steht in cache/indexN/level
+        let mut l2: Option<usize> = None;
+        let mut l3: Option<usize> = None;
+        if let Ok(caches) = fs::read_dir(pfad.join("cache")) {
+            for cache in caches.flatten() {
+                let ebene: Option<usize> = zahl_lesen(&cache.path().join("level"));
+                let cache_id: Option<usize> = zahl_lesen(&cache.path().join("id"));
+                match ebene {
+                    Some(2) => l2 = l2.or(cache_id),
+                    Some(3) => l3 = l3.or(cache_id),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Prozessor {
+            id,
+            paket: zahl("topology/physical_package_id", paket_lesen)?,
+            kern: zahl("topology/core_id", zahl_lesen)?,
+            smt: geschwister.iter().position(|&g| g == id).unwrap_or(0),
+            art: Kernart::Leistung,
+            l2,
+            l3,
+            kapazität: zahl_lesen(&pfad.join("cpu_capacity")),
+        })
+    }
+
+    fn arten_erkennen(wurzel: &Path, prozessoren: &mut [Prozessor]) {
+        // Intel Hybrid: eigene PMU Geräte für P-Kerne (cpu_core) und E-Kerne (cpu_atom)
+        if let Ok(text) = fs::read_to_string(wurzel.join("cpu_atom/cpus")) {
+            let effizienz: Vec<usize> = liste_lesen(&text);
+            for prozessor in prozessoren.iter_mut() {
+                if effizienz.contains(&prozessor.id) {
+                    prozessor.art = Kernart::Effizienz;
+                }
+            }
+            return;
+        }
+
+        // sonst über die Kapazität, z.B. bei ARM big.LITTLE
+        let höchste: Option<usize> = prozessoren.iter().filter_map(|p| p.kapazität).max();
+        if let Some(höchste) = höchste {
+            for prozessor in prozessoren.iter_mut() {
+                if prozessor.kapazität.is_some_and(|k| k < höchste) {
+                    prozessor.art = Kernart::Effizienz;
+                }
+            }
+        }
+    }
+
+    /*
+        ids der Prozessoren in der Reihenfolge der Strategie
+    */
+    pub fn reihenfolge(&self, strategie: Strategie) -> Vec<usize> {
+        let mut prozessoren: Vec<&Prozessor> = self.prozessoren.iter().collect();
+
+        // physische Kerne zuerst, innerhalb einer SMT Ebene Leistungskerne zuerst
+        let physisch = |p: &&Prozessor| (p.smt, p.art, p.paket, p.kern, p.id);
+
+        match strategie {
+            Strategie::Aufzählung => {}
+            Strategie::PhysischZuerst => prozessoren.sort_by_key(physisch),
+            Strategie::NurLeistung | Strategie::NurEffizienz => {
+                let art: Kernart = if strategie == Strategie::NurLeistung {
+                    Kernart::Leistung
+                } else {
+                    Kernart::Effizienz
+                };
+                prozessoren.retain(|p| p.art == art);
+                prozessoren.sort_by_key(physisch);
+            }
+            Strategie::JeL2 => {
+                // ohne Angabe eines L2 Caches hat jeder physische Kern seinen eigenen
+                let mut gruppen: BTreeMap<(usize, usize, usize), &Prozessor> = BTreeMap::new();
+                for prozessor in prozessoren {
+                    let schlüssel: (usize, usize, usize) = match prozessor.l2 {
+                        Some(l2) => (prozessor.paket, l2, 0),
+                        None => (prozessor.paket, prozessor.kern, 1),
+                    };
+                    let bester: &mut &Prozessor = gruppen.entry(schlüssel).or_insert(prozessor);
+                    if physisch(&prozessor) < physisch(bester) {
+                        *bester = prozessor;
+                    }
+                }
+                prozessoren = gruppen.into_values().collect();
+                prozessoren.sort_by_key(physisch);
+            }
+        }
+        prozessoren.iter().map(|p| p.id).collect()
+    }
+
+    /*
+        Kerne zum pinnen in der Reihenfolge der Strategie. Es werden nur Prozessoren verwendet, auf die der Prozess
+        Threads pinnen darf
+    */
+    pub fn pinnen(&self, strategie: Strategie) -> Result<Vec<CoreId>, Fehler> {
+        let erlaubt: Vec<CoreId> = fehler::kerne()?;
+
+        let kerne: Vec<CoreId> = self
+            .reihenfolge(strategie)
+            .into_iter()
+            .filter_map(|id| erlaubt.iter().copied().find(|kern| kern.id == id))
+            .collect();
+
+        if kerne.is_empty() {
+            return Err(Fehler::KeineKerne);
+        }
+        Ok(kerne)
+    }
+}
+
+/*
+    Kerne zum pinnen für das pinnen Argument aller Algorithmen. Aufzählung funktioniert auch ohne sysfs, die
+    anderen Strategien brauchen die Topologie
+*/
+pub fn kerne(strategie: Strategie) -> Result<Vec<CoreId>, Fehler> {
+    match strategie {
+        Strategie::Aufzählung => fehler::kerne(),
+        _ => Topologie::lesen()?.pinnen(strategie),
+    }
+}
+
+/*
+    Größe des größten Caches von Prozessor 0 in Bytes aus sysfs, z.B. des L3 Caches. None wenn sysfs keinen
+    Cache meldet
+*/
+pub fn größter_cache() -> Option<usize> {
+    größter_cache_aus(Path::new("/sys/devices"))
+}
+
+pub fn größter_cache_aus(wurzel: &Path) -> Option<usize> {
+    fs::read_dir(wurzel.join("system/cpu/cpu0/cache"))
+        .ok()?
+        .flatten()
+        .filter_map(|cache| bytes_lesen(&cache.path().join("size")))
+        .max()
+}
+
+// Größe im Format von sysfs, z.B. "32K", "1280K" oder "36M"
+fn bytes_lesen(pfad: &Path) -> Option<usize> {
+    let text: String = fs::read_to_string(pfad).ok()?;
+    let text: &str = text.trim();
+    let (zahl, faktor): (&str, usize) = match text.chars().last()? {
+        'K' => (&text[..text.len() - 1], 1 << 10),
+        'M' => (&text[..text.len() - 1], 1 << 20),
+        'G' => (&text[..text.len() - 1], 1 << 30),
+        _ => (text, 1),
+    };
+    Some(zahl.parse::<usize>().ok()? * faktor)
+}
+
+fn zahl_lesen(pfad: &Path) -> Option<usize> {
+    fs::read_to_string(pfad).ok()?.trim().parse().ok()
+}
+
+// manche virtualisierten Systeme melden -1 als physical_package_id, dann gehört der Prozessor zu Paket 0
+fn paket_lesen(pfad: &Path) -> Option<usize> {
+    let paket: i64 = fs::read_to_string(pfad).ok()?.trim().parse().ok()?;
+    Some(paket.max(0) as usize)
+}
+
+/*
+    liest eine Liste von Prozessoren im Format von sysfs, z.B. "0-3,8,10-11"
+*/
+pub fn liste_lesen(text: &str) -> Vec<usize> {
+    let mut ids: Vec<usize> = Vec::new();
+
+    for teil in text.trim().split(',').filter(|t| !t.is_empty()) {
+        match teil.split_once('-') {
+            Some((von, bis)) => {
+                if let (Ok(von), Ok(bis)) = (von.parse::<usize>(), bis.parse::<usize>()) {
+                    ids.extend(von..=bis);
+                }
+            }
+            None => ids.extend(teil.parse::<usize>().ok()),
+        }
+    }
+    ids
+}
@@ -0,0 +1,77 @@
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::zahl::Zahl;
+use rand::random;
+
+// Sicherheitsfaktor auf die Fehlerschranke, Strassen und Winograd runden stärker als die direkte Summe
+const SPIELRAUM: f64 = 8.0;
+
+/*
+    prüft mit dem Algorithmus von Freivalds ob c = a * b ist, ohne das Produkt neu zu berechnen
+
+    In jeder Wiederholung wird ein zufälliger Vektor r mit Werten 0 und 1 gewählt und a * (b * r) mit c * r
+    verglichen, das kostet O(m * k + k * n + m * n) statt O(m * k * n). Ist c falsch, wird das in jeder
+    Wiederholung mit Wahrscheinlichkeit von mindestens 1/2 erkannt, nach w Wiederholungen bleibt ein falsches
+    Ergebnis also höchstens mit Wahrscheinlichkeit 2^-w unentdeckt. Ein richtiges Ergebnis wird immer akzeptiert
+
+    Bei Gleitkommazahlen dürfen sich a * (b * r) und c * r in jeder Zeile um die Rundungsfehler der beiden
+    Rechenwege unterscheiden, diese wachsen mit k + n und der Summe der Beträge aller Summanden. Ganze Zahlen
+    müssen exakt übereinstimmen. Die erste abweichende Zeile wird als Fehler::Verifikation gemeldet
+*/
+pub fn freivalds<T: Zahl>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    c: &Matrix<T>,
+    m: usize,
+    k: usize,
+    n: usize,
+    wiederholungen: usize,
+) -> Result<(), Fehler> {
+    fehler::dimensionen_prüfen(a, b, c, m, k, n)?;
+
+    // relativer Fehler der Summen über k bzw. n Summanden
+    let toleranz: f64 = SPIELRAUM * T::EPSILON * (k + n + 2) as f64;
+
+    for wiederholung in 0..wiederholungen {
+        let r: Vec<bool> = (0..n).map(|_| random::<bool>()).collect();
+
+        // b * r und die Summe der Beträge der Summanden je Zeile von b
+        let mut br: Vec<T> = vec![T::NULL; k];
+        let mut br_betrag: Vec<f64> = vec![0.0; k];
+        for p in 0..k {
+            for (j, &wert) in b.zeile(p)[..n].iter().enumerate() {
+                if r[j] {
+                    br[p] += wert;
+                    br_betrag[p] += wert.abstand(T::NULL);
+                }
+            }
+        }
+
+        for i in 0..m {
+            let mut abr: T = T::NULL;
+            let mut grenze: f64 = 0.0;
+            for (p, &wert) in a.zeile(i)[..k].iter().enumerate() {
+                abr += wert * br[p];
+                grenze += wert.abstand(T::NULL) * br_betrag[p];
+            }
+
+            let mut cr: T = T::NULL;
+            for (j, &wert) in c.zeile(i)[..n].iter().enumerate() {
+                if r[j] {
+                    cr += wert;
+                    grenze += wert.abstand(T::NULL);
+                }
+            }
+
+            // NaN in a, b oder c ergibt eine NaN Abweichung
+            let abweichung: f64 = abr.abstand(cr);
+            if abweichung.is_nan() || abweichung > toleranz * grenze {
+                return Err(Fehler::Verifikation {
+                    zeile: i,
+                    wiederholung,
+                });
+            }
+        }
+    }
+    Ok(())
+}
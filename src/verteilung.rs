@@ -0,0 +1,256 @@
+use crate::numa;
+use core_affinity::CoreId;
+use std::{
+    fmt,
+    ops::Range,
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/*
+    legt fest wie die manuellen Algorithmen ihre Zeilen (bei gepack die ic Blöcke) auf die Threads verteilen
+
+    StatischBlock: Thread z bekommt den z-ten von threads gleich großen zusammenhängenden Bereichen
+    StatischZyklisch: Bereiche mit zeilen Zeilen werden vorab reihum auf die Threads verteilt
+    Dynamisch: jeder Thread nimmt sich über einen gemeinsamen Zähler die nächsten zeilen Zeilen
+    Geführt: wie Dynamisch, aber jeder Bereich ist rest / threads Zeilen groß und mindestens minimum
+    Stehlen: jeder Thread beginnt mit seinem statischen Block und nimmt sich davon zeilen Zeilen. Ist der eigene
+             Block leer, stiehlt er die hintere Hälfte des restlichen Blocks eines anderen Threads
+    Knoten: wie Stehlen, aber nur von Threads auf demselben NUMA Knoten. Zusammen mit numa::Platzierung::
+            ErsteBerührung bearbeitet jeder Knoten nur Zeilen in seinem eigenen Speicher. Auf Rechnern mit nur
+            einem Knoten entspricht das Stehlen
+
+    Bereichsgrößen von 0 werden wie 1 behandelt
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verteilung {
+    StatischBlock,
+    StatischZyklisch { zeilen: usize },
+    Dynamisch { zeilen: usize },
+    Geführt { minimum: usize },
+    Stehlen { zeilen: usize },
+    Knoten { zeilen: usize },
+}
+
+impl Default for Verteilung {
+    // bisheriges Verhalten aller manuellen Algorithmen
+    fn default() -> Verteilung {
+        Verteilung::Dynamisch { zeilen: 4 }
+    }
+}
+
+impl fmt::Display for Verteilung {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verteilung::StatischBlock => write!(f, "statisch_block"),
+            Verteilung::StatischZyklisch { zeilen } => write!(f, "statisch_zyklisch_{}", zeilen),
+            Verteilung::Dynamisch { zeilen } => write!(f, "dynamisch_{}", zeilen),
+            Verteilung::Geführt { minimum } => write!(f, "geführt_{}", minimum),
+            Verteilung::Stehlen { zeilen } => write!(f, "stehlen_{}", zeilen),
+            Verteilung::Knoten { zeilen } => write!(f, "knoten_{}", zeilen),
+        }
+    }
+}
+
+/*
+    Zustand einer Verteilung von anzahl Zeilen auf threads Threads. Wird einmal je Aufruf erstellt und von allen
+    Threads gemeinsam verwendet, Thread z holt sich mit nächster(z) so lange Zeilenbereiche bis None kommt.
+    Jede Zeile wird genau einmal ausgegeben
+*/
+pub struct Planer {
+    verteilung: Verteilung,
+    anzahl: usize,
+    threads: usize,
+
+    // nächste freie Zeile bei Dynamisch und Geführt
+    zähler: AtomicUsize,
+
+    // Anzahl der bereits geholten Bereiche je Thread bei StatischBlock und StatischZyklisch
+    runden: Vec<AtomicUsize>,
+
+    // restlicher Block je Thread bei Stehlen und Knoten
+    blöcke: Vec<Mutex<Range<usize>>>,
+
+    // NUMA Knoten je Thread, gestohlen wird nur innerhalb eines Knotens
+    knoten: Vec<usize>,
+}
+
+impl Planer {
+    /*
+        Planer für die Threads auf den Kernen pinnen, die Kerne werden nur bei Knoten für die NUMA Knoten benötigt
+    */
+    pub fn neu(verteilung: Verteilung, anzahl: usize, pinnen: &[CoreId]) -> Planer {
+        let knoten: Vec<usize> = match verteilung {
+            Verteilung::Knoten { .. } => pinnen
+                .iter()
+                .map(|kern| numa::knoten_von(numa::knoten(), kern.id))
+                .collect(),
+            _ => vec![0; pinnen.len()],
+        };
+        Planer::mit_knoten(verteilung, anzahl, knoten)
+    }
+
+    /*
+        wie neu, aber mit fest vorgegebenem Knoten je Thread (knoten.len() Threads), z.B. für Tests
+    */
+    pub fn mit_knoten(verteilung: Verteilung, anzahl: usize, mut knoten: Vec<usize>) -> Planer {
+        if knoten.is_empty() {
+            knoten.push(0);
+        }
+        let threads: usize = knoten.len();
+
+        let planer: Planer = Planer {
+            verteilung,
+            anzahl,
+            threads,
+            zähler: AtomicUsize::new(0),
+            runden: (0..threads).map(|_| AtomicUsize::new(0)).collect(),
+            blöcke: (0..threads).map(|_| Mutex::new(0..0)).collect(),
+            knoten,
+        };
+        planer.zurücksetzen();
+        planer
+    }
+
+    /*
+        beginnt die Verteilung von vorne, z.B. bei gepack für jeden gepackten Block von b. Darf nur aufgerufen
+        werden, wenn gerade kein Thread nächster() aufruft
+    */
+    pub fn zurücksetzen(&self) {
+        self.zähler.store(0, Ordering::Relaxed);
+        for (z, runde) in self.runden.iter().enumerate() {
+            runde.store(0, Ordering::Relaxed);
+            *self.blöcke[z]
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = block(self.anzahl, self.threads, z);
+        }
+    }
+
+    /*
+        nächster Zeilenbereich für Thread z oder None, wenn für diesen Thread keine Zeilen mehr übrig sind
+    */
+    pub fn nächster(&self, z: usize) -> Option<Range<usize>> {
+        let bereich: Range<usize> = match self.verteilung {
+            Verteilung::StatischBlock => {
+                if self.runden[z].fetch_add(1, Ordering::Relaxed) > 0 {
+                    return None;
+                }
+                block(self.anzahl, self.threads, z)
+            }
+            Verteilung::StatischZyklisch { zeilen } => {
+                let zeilen: usize = zeilen.max(1);
+                let runde: usize = self.runden[z].fetch_add(1, Ordering::Relaxed);
+                let anfang: usize = (runde * self.threads + z).saturating_mul(zeilen);
+                anfang.min(self.anzahl)..(anfang.saturating_add(zeilen)).min(self.anzahl)
+            }
+            Verteilung::Dynamisch { zeilen } => {
+                let zeilen: usize = zeilen.max(1);
+                let anfang: usize = self.zähler.fetch_add(zeilen, Ordering::Relaxed);
+                anfang.min(self.anzahl)..(anfang.saturating_add(zeilen)).min(self.anzahl)
+            }
+            Verteilung::Geführt { minimum } => self.geführt(minimum.max(1)),
+            Verteilung::Stehlen { zeilen } | Verteilung::Knoten { zeilen } => {
+                self.stehlen(z, zeilen.max(1))
+            }
+        };
+
+        if bereich.is_empty() {
+            None
+        } else {
+            Some(bereich)
+        }
+    }
+
+    /*
+        die Bereichsgröße hängt von den noch freien Zeilen ab, daher wird der Zähler mit compare_exchange erhöht
+    */
+    fn geführt(&self, minimum: usize) -> Range<usize> {
+        let mut anfang: usize = self.zähler.load(Ordering::Relaxed);
+
+        loop {
+            if anfang >= self.anzahl {
+                return 0..0;
+            }
+            let größe: usize = (self.anzahl - anfang).div_ceil(self.threads).max(minimum);
+            let ende: usize = (anfang + größe).min(self.anzahl);
+
+            match self.zähler.compare_exchange_weak(
+                anfang,
+                ende,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return anfang..ende,
+                Err(aktuell) => anfang = aktuell,
+            }
+        }
+    }
+
+    /*
+        nimmt zeilen Zeilen vom Anfang des eigenen Blocks. Beim Stehlen wird immer nur ein Block gesperrt, damit
+        sich zwei Threads die sich gegenseitig bestehlen nicht blockieren können
+    */
+    fn stehlen(&self, z: usize, zeilen: usize) -> Range<usize> {
+        if let Some(bereich) = Planer::vorne_nehmen(&self.blöcke[z], zeilen) {
+            return bereich;
+        }
+
+        // die anderen Threads ab z + 1 reihum als Opfer versuchen
+        for abstand in 1..self.threads {
+            let opfer: usize = (z + abstand) % self.threads;
+            if self.knoten[opfer] != self.knoten[z] {
+                continue;
+            }
+
+            let beute: Range<usize> = {
+                let mut block = self.blöcke[opfer]
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                if block.is_empty() {
+                    continue;
+                }
+                // hintere Hälfte stehlen, der Besitzer arbeitet vorne weiter
+                let mitte: usize = block.end - block.len().div_ceil(2);
+                let beute: Range<usize> = mitte..block.end;
+                block.end = mitte;
+                beute
+            };
+
+            // nur der Besitzer füllt seinen eigenen leeren Block wieder auf
+            *self.blöcke[z]
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = beute;
+            if let Some(bereich) = Planer::vorne_nehmen(&self.blöcke[z], zeilen) {
+                return bereich;
+            }
+        }
+        0..0
+    }
+
+    fn vorne_nehmen(block: &Mutex<Range<usize>>, zeilen: usize) -> Option<Range<usize>> {
+        let mut block = block.lock().unwrap_or_else(PoisonError::into_inner);
+        if block.is_empty() {
+            return None;
+        }
+        let ende: usize = (block.start + zeilen).min(block.end);
+        let bereich: Range<usize> = block.start..ende;
+        block.start = ende;
+        Some(bereich)
+    }
+}
+
+/*
+    z-ter von threads gleich großen zusammenhängenden Bereichen von 0..anzahl, die ersten anzahl % threads Bereiche
+    sind eine Zeile größer. Statischer Block von Thread z bei StatischBlock, Stehlen und Knoten, und die Zeilen die
+    Thread z bei numa::Platzierung::ErsteBerührung anlegt
+*/
+pub fn block(anzahl: usize, threads: usize, z: usize) -> Range<usize> {
+    let threads: usize = threads.max(1);
+    let basis: usize = anzahl / threads;
+    let rest: usize = anzahl % threads;
+    let anfang: usize = z * basis + z.min(rest);
+    let ende: usize = anfang + basis + usize::from(z < rest);
+    anfang..ende
+}
@@ -0,0 +1,396 @@
+use crate::fehler::Fehler;
+use std::{cell::RefCell, fmt};
+
+/*
+    Hardware Zähler eines Threads mit perf_event_open. Gezählt werden nur Ereignisse im User Space, damit auch
+    perf_event_paranoid = 2 genügt. In Containern ist perf_event_open oft gesperrt, dann liefern alle Funktionen
+    Fehler::Zähler und die Messungen laufen ohne Zähler weiter
+
+    Mit messen werden die Zähler des aufrufenden Threads und aller Threads von rechenpool::verteilen erfasst.
+    Threads von rayon, crossbeam und die Teilprodukte von strassen und winograd werden nicht gezählt, diese
+    Algorithmen markieren die Erfassung mit nicht_erfasst als unvollständig
+*/
+
+/*
+    gezählte Ereignisse, L1 und LLC sind Fehlzugriffe beim Lesen von Daten
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ereignis {
+    Zyklen,
+    Instruktionen,
+    L1Fehlzugriffe,
+    LlcFehlzugriffe,
+    Sprungfehler,
+}
+
+impl Ereignis {
+    pub const ALLE: [Ereignis; 5] = [
+        Ereignis::Zyklen,
+        Ereignis::Instruktionen,
+        Ereignis::L1Fehlzugriffe,
+        Ereignis::LlcFehlzugriffe,
+        Ereignis::Sprungfehler,
+    ];
+
+    // Name in Tabellen, CSV und JSON
+    pub fn name(self) -> &'static str {
+        match self {
+            Ereignis::Zyklen => "zyklen",
+            Ereignis::Instruktionen => "instruktionen",
+            Ereignis::L1Fehlzugriffe => "l1_fehlzugriffe",
+            Ereignis::LlcFehlzugriffe => "llc_fehlzugriffe",
+            Ereignis::Sprungfehler => "sprungfehler",
+        }
+    }
+}
+
+impl fmt::Display for Ereignis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/*
+    Zählerstände in der Reihenfolge von Ereignis::ALLE, None wenn das Ereignis nicht gezählt werden konnte
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Werte(pub [Option<u64>; 5]);
+
+impl Werte {
+    pub fn wert(&self, ereignis: Ereignis) -> Option<u64> {
+        self.0[ereignis as usize]
+    }
+
+    // Summe mehrerer Threads, ein Ereignis fehlt nur wenn es in keinem Thread gezählt wurde
+    pub fn summe(werte: &[Werte]) -> Werte {
+        let mut summe: Werte = Werte::default();
+        for werte in werte {
+            summe.addieren(werte);
+        }
+        summe
+    }
+
+    fn addieren(&mut self, andere: &Werte) {
+        for (wert, anderer) in self.0.iter_mut().zip(andere.0) {
+            if let Some(anderer) = anderer {
+                *wert = Some(wert.unwrap_or(0) + anderer);
+            }
+        }
+    }
+
+    // Instruktionen je Zyklus
+    pub fn ipc(&self) -> Option<f64> {
+        match (
+            self.wert(Ereignis::Instruktionen),
+            self.wert(Ereignis::Zyklen),
+        ) {
+            (Some(instruktionen), Some(zyklen)) if zyklen > 0 => {
+                Some(instruktionen as f64 / zyklen as f64)
+            }
+            _ => None,
+        }
+    }
+}
+
+/*
+    Ergebnis von messen: die Zähler des Aufrufers und je Thread z von rechenpool::verteilen. fehler ist der erste
+    Fehler beim öffnen oder lesen der Zähler, die Werte der betroffenen Threads sind dann leer. unvollständig
+    ist gesetzt wenn ein Teil der Arbeit auf nicht gezählten Threads lief, die Summe ist dann zu klein
+*/
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Erfassung {
+    pub aufrufer: Werte,
+    pub threads: Vec<Werte>,
+    pub fehler: Option<Fehler>,
+    pub unvollständig: bool,
+}
+
+impl Erfassung {
+    // Aufrufer und alle Threads zusammen
+    pub fn summe(&self) -> Werte {
+        let mut summe: Werte = self.aufrufer;
+        summe.addieren(&Werte::summe(&self.threads));
+        summe
+    }
+
+    fn fehler_merken(&mut self, fehler: Fehler) {
+        self.fehler.get_or_insert(fehler);
+    }
+}
+
+thread_local! {
+    // die Zähler dieses Threads, auch ein Fehler beim öffnen wird nur einmal ermittelt
+    static ZÄHLER: RefCell<Option<Result<Zähler, Fehler>>> = const { RefCell::new(None) };
+    // Erfassung eines laufenden messen auf diesem Thread
+    static ERFASSUNG: RefCell<Option<Erfassung>> = const { RefCell::new(None) };
+}
+
+/*
+    führt f aus und erfasst dabei die Hardware Zähler. Schlägt das öffnen der Zähler fehl, wird f trotzdem
+    ausgeführt und der Fehler in der Erfassung zurückgegeben
+*/
+pub fn messen<R>(f: impl FnOnce() -> R) -> (R, Erfassung) {
+    // stellt auch bei einer panic in f den Zustand vor messen wieder her
+    struct Zurücksetzen(Option<Erfassung>);
+    impl Drop for Zurücksetzen {
+        fn drop(&mut self) {
+            let vorher: Option<Erfassung> = self.0.take();
+            ERFASSUNG.with(|erfassung| *erfassung.borrow_mut() = vorher);
+        }
+    }
+
+    let vorher: Option<Erfassung> =
+        ERFASSUNG.with(|erfassung| erfassung.borrow_mut().replace(Erfassung::default()));
+    let zurücksetzen: Zurücksetzen = Zurücksetzen(vorher);
+
+    let (ergebnis, aufrufer): (R, Result<Werte, Fehler>) = zählen(f);
+
+    let mut erfassung: Erfassung = ERFASSUNG
+        .with(|erfassung| erfassung.borrow_mut().take())
+        .unwrap_or_default();
+    drop(zurücksetzen);
+
+    match aufrufer {
+        Ok(werte) => erfassung.aufrufer = werte,
+        Err(f) => erfassung.fehler_merken(f),
+    }
+    (ergebnis, erfassung)
+}
+
+// ob auf diesem Thread gerade messen läuft
+pub(crate) fn aktiv() -> bool {
+    ERFASSUNG.with(|erfassung| erfassung.borrow().is_some())
+}
+
+/*
+    zählt die Ereignisse während f auf diesem Thread. Die Zähler werden beim ersten Aufruf je Thread geöffnet und
+    danach wiederverwendet, Arbeiter des Rechenpools öffnen sie also nur einmal
+*/
+pub(crate) fn zählen<R>(f: impl FnOnce() -> R) -> (R, Result<Werte, Fehler>) {
+    let gestartet: Result<(), Fehler> = mit_zähler(Zähler::starten);
+    let ergebnis: R = f();
+    let werte: Result<Werte, Fehler> = gestartet.and_then(|()| mit_zähler(Zähler::stoppen));
+    (ergebnis, werte)
+}
+
+fn mit_zähler<T>(f: impl FnOnce(&Zähler) -> Result<T, Fehler>) -> Result<T, Fehler> {
+    ZÄHLER.with(|zähler| {
+        let mut zähler = zähler.borrow_mut();
+        match zähler.get_or_insert_with(Zähler::öffnen) {
+            Ok(zähler) => f(zähler),
+            Err(fehler) => Err(fehler.clone()),
+        }
+    })
+}
+
+// markiert die laufende Erfassung, weil der Aufrufer Arbeit an Threads außerhalb von rechenpool::verteilen gibt
+pub(crate) fn nicht_erfasst() {
+    ERFASSUNG.with(|erfassung| {
+        if let Some(erfassung) = erfassung.borrow_mut().as_mut() {
+            erfassung.unvollständig = true;
+        }
+    });
+}
+
+// Werte von Thread z aus rechenpool::verteilen zur Erfassung des aufrufenden Threads addieren
+pub(crate) fn erfassen(z: usize, werte: Result<Werte, Fehler>) {
+    ERFASSUNG.with(|erfassung| {
+        if let Some(erfassung) = erfassung.borrow_mut().as_mut() {
+            if erfassung.threads.len() <= z {
+                erfassung.threads.resize(z + 1, Werte::default());
+            }
+            match werte {
+                Ok(werte) => erfassung.threads[z].addieren(&werte),
+                Err(f) => erfassung.fehler_merken(f),
+            }
+        }
+    });
+}
+
+/*
+    die geöffneten Zähler eines Threads. Ereignisse die der Prozessor nicht kennt werden ausgelassen, nur wenn
+    gar kein Ereignis geöffnet werden kann ist das ein Fehler
+*/
+pub struct Zähler {
+    #[cfg(target_os = "linux")]
+    ereignisse: Vec<(Ereignis, std::fs::File)>,
+}
+
+#[cfg(target_os = "linux")]
+mod perf {
+    use super::Ereignis;
+
+    // Typen und Konstanten aus linux/perf_event.h
+    pub const TYP_HARDWARE: u32 = 0;
+    pub const TYP_CACHE: u32 = 3;
+
+    const HARDWARE_ZYKLEN: u64 = 0;
+    const HARDWARE_INSTRUKTIONEN: u64 = 1;
+    const HARDWARE_SPRUNGFEHLER: u64 = 5;
+    const CACHE_L1D: u64 = 0;
+    const CACHE_LL: u64 = 2;
+    const CACHE_LESEN: u64 = 0;
+    const CACHE_FEHLZUGRIFF: u64 = 1;
+
+    // Bits von flags: disabled, exclude_kernel, exclude_hv
+    pub const AUS: u64 = 1 << 0;
+    pub const OHNE_KERNEL: u64 = 1 << 5;
+    pub const OHNE_HYPERVISOR: u64 = 1 << 6;
+
+    // read liefert Wert, Zeit aktiviert und Zeit gezählt
+    pub const ZEITEN: u64 = 1 | 2;
+    pub const CLOEXEC: libc::c_ulong = 1 << 3;
+
+    // _IO('$', n)
+    pub const EINSCHALTEN: libc::c_ulong = 0x2400;
+    pub const AUSSCHALTEN: libc::c_ulong = 0x2401;
+    pub const ZURÜCKSETZEN: libc::c_ulong = 0x2403;
+
+    /*
+        struct perf_event_attr in der ersten Version (PERF_ATTR_SIZE_VER0), der Kernel nimmt für alle späteren
+        Felder 0 an
+    */
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct Attribute {
+        pub typ: u32,
+        pub größe: u32,
+        pub konfiguration: u64,
+        pub periode: u64,
+        pub sample_typ: u64,
+        pub lese_format: u64,
+        pub flags: u64,
+        pub aufwecken: u32,
+        pub haltepunkt: u32,
+        pub konfiguration1: u64,
+    }
+
+    pub fn konfiguration(ereignis: Ereignis) -> (u32, u64) {
+        let cache = |cache: u64| cache | CACHE_LESEN << 8 | CACHE_FEHLZUGRIFF << 16;
+        match ereignis {
+            Ereignis::Zyklen => (TYP_HARDWARE, HARDWARE_ZYKLEN),
+            Ereignis::Instruktionen => (TYP_HARDWARE, HARDWARE_INSTRUKTIONEN),
+            Ereignis::L1Fehlzugriffe => (TYP_CACHE, cache(CACHE_L1D)),
+            Ereignis::LlcFehlzugriffe => (TYP_CACHE, cache(CACHE_LL)),
+            Ereignis::Sprungfehler => (TYP_HARDWARE, HARDWARE_SPRUNGFEHLER),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Zähler {
+    // öffnet die Zähler aller Ereignisse für den aufrufenden Thread auf jedem Kern, angehalten
+    pub fn öffnen() -> Result<Zähler, Fehler> {
+        use std::{io, os::fd::FromRawFd};
+
+        let mut ereignisse: Vec<(Ereignis, std::fs::File)> = Vec::new();
+        let mut erster_fehler: Option<io::Error> = None;
+
+        for ereignis in Ereignis::ALLE {
+            let (typ, konfiguration): (u32, u64) = perf::konfiguration(ereignis);
+            let attribute: perf::Attribute = perf::Attribute {
+                typ,
+                größe: size_of::<perf::Attribute>() as u32,
+                konfiguration,
+                lese_format: perf::ZEITEN,
+                flags: perf::AUS | perf::OHNE_KERNEL | perf::OHNE_HYPERVISOR,
+                ..perf::Attribute::default()
+            };
+
+            // SAFETY: attribute lebt bis zum Ende des Aufrufs, pid 0 und cpu -1 zählen den aufrufenden Thread
+            let deskriptor: libc::c_long = unsafe {
+                libc::syscall(
+                    libc::SYS_perf_event_open,
+                    &attribute as *const perf::Attribute,
+                    0 as libc::pid_t,
+                    -1 as libc::c_int,
+                    -1 as libc::c_int,
+                    perf::CLOEXEC,
+                )
+            };
+            if deskriptor < 0 {
+                erster_fehler.get_or_insert_with(io::Error::last_os_error);
+                continue;
+            }
+            // SAFETY: der Deskriptor ist neu und gehört nur der Datei
+            let datei: std::fs::File = unsafe { std::fs::File::from_raw_fd(deskriptor as i32) };
+            ereignisse.push((ereignis, datei));
+        }
+
+        if ereignisse.is_empty() {
+            let grund: io::Error = erster_fehler.unwrap_or_else(|| io::Error::other("unbekannt"));
+            return Err(Fehler::Zähler(format!("perf_event_open: {}", grund)));
+        }
+        Ok(Zähler { ereignisse })
+    }
+
+    // alle Zähler auf 0 setzen und starten
+    pub fn starten(&self) -> Result<(), Fehler> {
+        for (_, datei) in &self.ereignisse {
+            steuern(datei, perf::ZURÜCKSETZEN)?;
+            steuern(datei, perf::EINSCHALTEN)?;
+        }
+        Ok(())
+    }
+
+    /*
+        alle Zähler anhalten und lesen. Teilen sich mehr Ereignisse die Zähler des Prozessors als vorhanden sind,
+        wird jeder Wert auf die ganze Laufzeit hochgerechnet
+    */
+    pub fn stoppen(&self) -> Result<Werte, Fehler> {
+        use std::io::Read;
+
+        for (_, datei) in &self.ereignisse {
+            steuern(datei, perf::AUSSCHALTEN)?;
+        }
+
+        let mut werte: Werte = Werte::default();
+        for (ereignis, mut datei) in self.ereignisse.iter().map(|(e, d)| (*e, d)) {
+            let mut bytes: [u8; 24] = [0; 24];
+            datei
+                .read_exact(&mut bytes)
+                .map_err(|f| Fehler::Zähler(format!("{} lesen: {}", ereignis, f)))?;
+            let [wert, aktiviert, gezählt]: [u64; 3] =
+                [0, 1, 2].map(|i| u64::from_ne_bytes(bytes[i * 8..i * 8 + 8].try_into().unwrap()));
+
+            werte.0[ereignis as usize] = match gezählt {
+                // das Ereignis kam nie an die Reihe
+                0 if aktiviert > 0 => None,
+                0 => Some(wert),
+                gezählt => Some((wert as u128 * aktiviert as u128 / gezählt as u128) as u64),
+            };
+        }
+        Ok(werte)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn steuern(datei: &std::fs::File, anfrage: libc::c_ulong) -> Result<(), Fehler> {
+    use std::os::fd::AsRawFd;
+
+    // SAFETY: der Deskriptor gehört zu einem geöffneten perf Ereignis, die Anfragen haben kein Argument
+    if unsafe { libc::ioctl(datei.as_raw_fd(), anfrage as _, 0) } < 0 {
+        return Err(Fehler::Zähler(format!(
+            "ioctl: {}",
+            std::io::Error::last_os_error()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+impl Zähler {
+    pub fn öffnen() -> Result<Zähler, Fehler> {
+        Err(Fehler::Zähler(
+            "perf_event_open gibt es nur unter Linux".to_string(),
+        ))
+    }
+
+    pub fn starten(&self) -> Result<(), Fehler> {
+        Ok(())
+    }
+
+    pub fn stoppen(&self) -> Result<Werte, Fehler> {
+        Ok(Werte::default())
+    }
+}
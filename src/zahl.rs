@@ -0,0 +1,357 @@
+use rand::random_range;
+#[cfg(feature = "nightly-simd")]
+use std::simd::StdFloat;
+use std::{
+    array,
+    fmt::Debug,
+    ops::{Add, AddAssign, Mul, Sub},
+};
+
+/*
+    Elementtyp einer Matrix. Alle Algorithmen sind für jeden Typ mit diesem Trait implementiert
+
+    Vektor ist der Typ eines simd Registers mit L Werten. Für f32, f64, i32 und i64 wird mit dem Feature
+    nightly-simd std::simd verwendet, sonst und für komplexe Zahlen ein Feld mit L Werten
+*/
+pub trait Zahl:
+    Copy
+    + Send
+    + Sync
+    + Debug
+    + PartialEq
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + AddAssign
+{
+    const NULL: Self;
+    const EINS: Self;
+
+    // erlaubte Abweichung beim Vergleich zweier Ergebnisse, bei ganzen Zahlen muss das Ergebnis exakt sein
+    const GENAUIGKEIT: f64;
+
+    // relativer Rundungsfehler einer einzelnen Operation (Maschinengenauigkeit), 0 bei ganzen Zahlen
+    const EPSILON: f64;
+
+    type Vektor<const L: usize>: Copy
+        + Add<Output = Self::Vektor<L>>
+        + Mul<Output = Self::Vektor<L>>;
+
+    // Zufallswert, Gleitkommazahlen im Bereich [-1.0, 1.0], ganze Zahlen im Bereich [-10, 10]
+    fn zufall() -> Self;
+
+    // Betrag der Differenz zweier Werte
+    fn abstand(self, andere: Self) -> f64;
+
+    // self * b + c, bei Gleitkommazahlen als fused multiply-add
+    fn mul_add(self, b: Self, c: Self) -> Self;
+
+    // nächster Wert zu wert, ganze Zahlen werden gerundet, bei komplexen Zahlen ist wert der Realteil
+    fn aus_f64(wert: f64) -> Self;
+
+    // wie aus_f64, komplexe Zahlen nehmen Real- und Imaginärteil aus zwei Aufrufen von wert
+    fn aus_komponenten(wert: impl FnMut() -> f64) -> Self;
+
+    fn splat<const L: usize>(wert: Self) -> Self::Vektor<L>;
+
+    // die ersten L Werte des Slices laden
+    fn laden<const L: usize>(werte: &[Self]) -> Self::Vektor<L>;
+
+    // den Vektor in die ersten L Werte des Slices schreiben
+    fn speichern<const L: usize>(vektor: Self::Vektor<L>, werte: &mut [Self]);
+
+    // a * b + c für ganze Vektoren, bei Gleitkommazahlen als fused multiply-add
+    fn vektor_mul_add<const L: usize>(
+        a: Self::Vektor<L>,
+        b: Self::Vektor<L>,
+        c: Self::Vektor<L>,
+    ) -> Self::Vektor<L>;
+}
+
+// Register für f32, f64, i32 und i64
+#[cfg(feature = "nightly-simd")]
+type Register<T, const L: usize> = std::simd::Simd<T, L>;
+#[cfg(not(feature = "nightly-simd"))]
+type Register<T, const L: usize> = Feld<T, L>;
+
+// Umsetzung der simd Register, z.B. für Benchmark Berichte
+pub const UMSETZUNG: &str = if cfg!(feature = "nightly-simd") {
+    "std::simd"
+} else {
+    "autovektorisiert"
+};
+
+/*
+    Gleitkommazahlen
+*/
+macro_rules! gleitkomma {
+    ($typ:ty, $genauigkeit:expr) => {
+        impl Zahl for $typ {
+            const NULL: $typ = 0.0;
+            const EINS: $typ = 1.0;
+            const GENAUIGKEIT: f64 = $genauigkeit;
+            const EPSILON: f64 = <$typ>::EPSILON as f64;
+
+            type Vektor<const L: usize> = Register<$typ, L>;
+
+            fn zufall() -> $typ {
+                random_range(-1.0..=1.0)
+            }
+
+            fn abstand(self, andere: $typ) -> f64 {
+                (self - andere).abs() as f64
+            }
+
+            fn mul_add(self, b: $typ, c: $typ) -> $typ {
+                <$typ>::mul_add(self, b, c)
+            }
+
+            fn aus_f64(wert: f64) -> $typ {
+                wert as $typ
+            }
+
+            fn aus_komponenten(mut wert: impl FnMut() -> f64) -> $typ {
+                wert() as $typ
+            }
+
+            fn splat<const L: usize>(wert: $typ) -> Register<$typ, L> {
+                Register::splat(wert)
+            }
+
+            fn laden<const L: usize>(werte: &[$typ]) -> Register<$typ, L> {
+                Register::from_slice(werte)
+            }
+
+            fn speichern<const L: usize>(vektor: Register<$typ, L>, werte: &mut [$typ]) {
+                vektor.copy_to_slice(werte);
+            }
+
+            fn vektor_mul_add<const L: usize>(
+                a: Register<$typ, L>,
+                b: Register<$typ, L>,
+                c: Register<$typ, L>,
+            ) -> Register<$typ, L> {
+                a.mul_add(b, c)
+            }
+        }
+    };
+}
+
+/*
+    ganze Zahlen. Ein Überlauf wird wie bei den Operatoren von Rust behandelt
+*/
+macro_rules! ganzzahl {
+    ($typ:ty) => {
+        impl Zahl for $typ {
+            const NULL: $typ = 0;
+            const EINS: $typ = 1;
+            const GENAUIGKEIT: f64 = 0.0;
+            const EPSILON: f64 = 0.0;
+
+            type Vektor<const L: usize> = Register<$typ, L>;
+
+            fn zufall() -> $typ {
+                random_range(-10..=10)
+            }
+
+            fn abstand(self, andere: $typ) -> f64 {
+                self.abs_diff(andere) as f64
+            }
+
+            fn mul_add(self, b: $typ, c: $typ) -> $typ {
+                self * b + c
+            }
+
+            fn aus_f64(wert: f64) -> $typ {
+                wert.round() as $typ
+            }
+
+            fn aus_komponenten(mut wert: impl FnMut() -> f64) -> $typ {
+                wert().round() as $typ
+            }
+
+            fn splat<const L: usize>(wert: $typ) -> Register<$typ, L> {
+                Register::splat(wert)
+            }
+
+            fn laden<const L: usize>(werte: &[$typ]) -> Register<$typ, L> {
+                Register::from_slice(werte)
+            }
+
+            fn speichern<const L: usize>(vektor: Register<$typ, L>, werte: &mut [$typ]) {
+                vektor.copy_to_slice(werte);
+            }
+
+            fn vektor_mul_add<const L: usize>(
+                a: Register<$typ, L>,
+                b: Register<$typ, L>,
+                c: Register<$typ, L>,
+            ) -> Register<$typ, L> {
+                a * b + c
+            }
+        }
+    };
+}
+
+gleitkomma!(f32, 1e-3);
+gleitkomma!(f64, 1e-10);
+ganzzahl!(i32);
+ganzzahl!(i64);
+
+/*
+    komplexe Zahl re + im * i
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Komplex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+impl<T> Komplex<T> {
+    pub const fn neu(re: T, im: T) -> Komplex<T> {
+        Komplex { re, im }
+    }
+}
+
+impl<T: Add<Output = T>> Add for Komplex<T> {
+    type Output = Komplex<T>;
+
+    fn add(self, andere: Komplex<T>) -> Komplex<T> {
+        Komplex::neu(self.re + andere.re, self.im + andere.im)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Komplex<T> {
+    type Output = Komplex<T>;
+
+    fn sub(self, andere: Komplex<T>) -> Komplex<T> {
+        Komplex::neu(self.re - andere.re, self.im - andere.im)
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Mul for Komplex<T> {
+    type Output = Komplex<T>;
+
+    // (a + bi) * (c + di) = (ac - bd) + (ad + bc)i
+    fn mul(self, andere: Komplex<T>) -> Komplex<T> {
+        Komplex::neu(
+            self.re * andere.re - self.im * andere.im,
+            self.re * andere.im + self.im * andere.re,
+        )
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign for Komplex<T> {
+    fn add_assign(&mut self, andere: Komplex<T>) {
+        *self = *self + andere;
+    }
+}
+
+/*
+    Vektor mit L Werten für Typen ohne simd Unterstützung in std::simd und für alle Typen auf stable Rust. Die
+    Operationen werden elementweise ausgeführt, innerhalb der Funktionen mit target_feature vektorisiert der
+    Compiler sie mit der Registerbreite des Pfads
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Feld<T, const L: usize>(pub [T; L]);
+
+impl<T: Zahl, const L: usize> Feld<T, L> {
+    pub fn splat(wert: T) -> Feld<T, L> {
+        Feld([wert; L])
+    }
+
+    // die ersten L Werte des Slices laden
+    pub fn from_slice(werte: &[T]) -> Feld<T, L> {
+        Feld(array::from_fn(|l| werte[l]))
+    }
+
+    pub fn copy_to_slice(self, werte: &mut [T]) {
+        werte[..L].copy_from_slice(&self.0);
+    }
+
+    // self * b + c elementweise mit Zahl::mul_add
+    pub fn mul_add(self, b: Feld<T, L>, c: Feld<T, L>) -> Feld<T, L> {
+        Feld(array::from_fn(|l| self.0[l].mul_add(b.0[l], c.0[l])))
+    }
+}
+
+impl<T: Copy + Add<Output = T>, const L: usize> Add for Feld<T, L> {
+    type Output = Feld<T, L>;
+
+    fn add(self, andere: Feld<T, L>) -> Feld<T, L> {
+        Feld(array::from_fn(|l| self.0[l] + andere.0[l]))
+    }
+}
+
+impl<T: Copy + Mul<Output = T>, const L: usize> Mul for Feld<T, L> {
+    type Output = Feld<T, L>;
+
+    fn mul(self, andere: Feld<T, L>) -> Feld<T, L> {
+        Feld(array::from_fn(|l| self.0[l] * andere.0[l]))
+    }
+}
+
+/*
+    komplexe Zahlen mit Real- und Imaginärteil vom Typ $typ
+*/
+macro_rules! komplex {
+    ($typ:ty) => {
+        impl Zahl for Komplex<$typ> {
+            const NULL: Komplex<$typ> = Komplex::neu(0.0, 0.0);
+            const EINS: Komplex<$typ> = Komplex::neu(1.0, 0.0);
+            const GENAUIGKEIT: f64 = <$typ as Zahl>::GENAUIGKEIT;
+            const EPSILON: f64 = <$typ as Zahl>::EPSILON;
+
+            type Vektor<const L: usize> = Feld<Komplex<$typ>, L>;
+
+            fn zufall() -> Komplex<$typ> {
+                Komplex::neu(<$typ>::zufall(), <$typ>::zufall())
+            }
+
+            fn abstand(self, andere: Komplex<$typ>) -> f64 {
+                let differenz: Komplex<$typ> = self - andere;
+                (differenz.re as f64).hypot(differenz.im as f64)
+            }
+
+            fn mul_add(self, b: Komplex<$typ>, c: Komplex<$typ>) -> Komplex<$typ> {
+                self * b + c
+            }
+
+            fn aus_f64(wert: f64) -> Komplex<$typ> {
+                Komplex::neu(wert as $typ, 0.0)
+            }
+
+            fn aus_komponenten(mut wert: impl FnMut() -> f64) -> Komplex<$typ> {
+                let re: $typ = wert() as $typ;
+                Komplex::neu(re, wert() as $typ)
+            }
+
+            fn splat<const L: usize>(wert: Komplex<$typ>) -> Feld<Komplex<$typ>, L> {
+                Feld::splat(wert)
+            }
+
+            fn laden<const L: usize>(werte: &[Komplex<$typ>]) -> Feld<Komplex<$typ>, L> {
+                Feld::from_slice(werte)
+            }
+
+            fn speichern<const L: usize>(
+                vektor: Feld<Komplex<$typ>, L>,
+                werte: &mut [Komplex<$typ>],
+            ) {
+                vektor.copy_to_slice(werte);
+            }
+
+            fn vektor_mul_add<const L: usize>(
+                a: Feld<Komplex<$typ>, L>,
+                b: Feld<Komplex<$typ>, L>,
+                c: Feld<Komplex<$typ>, L>,
+            ) -> Feld<Komplex<$typ>, L> {
+                a.mul_add(b, c)
+            }
+        }
+    };
+}
+
+komplex!(f32);
+komplex!(f64);
@@ -0,0 +1,147 @@
+use crate::fehler::{self, Fehler};
+use crate::matrix::Matrix;
+use crate::rechenpool::{self, Ausführung};
+use crate::verteilung::{Planer, Verteilung};
+use crate::zahl::Zahl;
+use core_affinity::CoreId;
+use std::{fmt, ops::Range};
+
+/*
+    legt fest in welche Teile die Berechnung von c für die Threads zerlegt wird
+
+    Zeilen: jeder Teil ist eine ganze Zeile von c. Hat c weniger Zeilen als Threads, bleiben Threads ohne Arbeit
+            und jeder Thread liest ganz b
+    Kacheln: jeder Teil ist eine Kachel mit zeilen x spalten Werten von c. Mit k_teile > 1 wird zusätzlich die
+             innere Dimension in k_teile Abschnitte geteilt, die Teilergebnisse werden danach aufsummiert
+
+    Größen von 0 werden wie 1 behandelt
+*/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Zerlegung {
+    // bisheriges Verhalten aller manuellen Algorithmen
+    #[default]
+    Zeilen,
+    Kacheln {
+        zeilen: usize,
+        spalten: usize,
+        k_teile: usize,
+    },
+}
+
+impl fmt::Display for Zerlegung {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Zerlegung::Zeilen => write!(f, "zeilen"),
+            Zerlegung::Kacheln {
+                zeilen,
+                spalten,
+                k_teile,
+            } => write!(f, "kacheln_{}x{}_k{}", zeilen, spalten, k_teile),
+        }
+    }
+}
+
+/*
+    Teil von c = a * b: die Werte c[zeilen][spalten], berechnet nur über die Summanden p in tiefe
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Kachel {
+    pub zeilen: Range<usize>,
+    pub spalten: Range<usize>,
+    pub tiefe: Range<usize>,
+}
+
+impl Zerlegung {
+    /*
+        alle Teile für eine m x k mal k x n Multiplikation. Jedes (i, j, p) liegt in genau einer Kachel, die
+        Abschnitte von k sind bis auf eine Zeile gleich groß
+    */
+    pub fn kacheln(self, m: usize, k: usize, n: usize) -> Vec<Kachel> {
+        let (höhe, breite, k_teile): (usize, usize, usize) = match self {
+            Zerlegung::Zeilen => (1, n.max(1), 1),
+            Zerlegung::Kacheln {
+                zeilen,
+                spalten,
+                k_teile,
+            } => (zeilen.max(1), spalten.max(1), k_teile.max(1).min(k.max(1))),
+        };
+
+        let mut kacheln: Vec<Kachel> = Vec::new();
+        for i in (0..m).step_by(höhe) {
+            for j in (0..n).step_by(breite) {
+                for t in 0..k_teile {
+                    kacheln.push(Kachel {
+                        zeilen: i..(i + höhe).min(m),
+                        spalten: j..(j + breite).min(n),
+                        tiefe: t * k / k_teile..(t + 1) * k / k_teile,
+                    });
+                }
+            }
+        }
+        kacheln
+    }
+}
+
+/*
+    verteilt die Kacheln von zerlegung mit der gewählten Verteilung auf die Threads. f(kachel, werte) berechnet
+    eine Kachel zeilenweise in werte (mit Nullen vorbelegt, kachel.spalten.len() Werte je Zeile). Danach werden
+    alle Teilergebnisse in c aufsummiert. Gemeinsame Grundlage von tiling, simd und simd_tiling
+*/
+pub fn kacheln_berechnen<T, F>(
+    c: &mut Matrix<T>,
+    m: usize,
+    k: usize,
+    n: usize,
+    zerlegung: Zerlegung,
+    threads: usize,
+    pinnen: &[CoreId],
+    ausführung: Ausführung,
+    verteilung: Verteilung,
+    f: F,
+) -> Result<(), Fehler>
+where
+    T: Zahl,
+    F: Fn(&Kachel, &mut [T]) + Sync,
+{
+    fehler::threads_prüfen(threads, pinnen)?;
+
+    let kacheln: Vec<Kachel> = zerlegung.kacheln(m, k, n);
+    let planer: Planer = Planer::neu(verteilung, kacheln.len(), &pinnen[..threads]);
+
+    // von einem Thread berechnete Kacheln mit Index in kacheln
+    let ergebnisse: Vec<Vec<(usize, Vec<T>)>> =
+        rechenpool::verteilen(threads, pinnen, ausführung, |z| {
+            let mut berechnet: Vec<(usize, Vec<T>)> = Vec::new();
+
+            while let Some(bereich) = planer.nächster(z) {
+                for index in bereich {
+                    let kachel: &Kachel = &kacheln[index];
+                    let mut werte: Vec<T> =
+                        vec![T::NULL; kachel.zeilen.len() * kachel.spalten.len()];
+                    f(kachel, &mut werte);
+                    berechnet.push((index, werte));
+                }
+            }
+            Ok(berechnet)
+        })?;
+
+    // Reduktion: Kacheln mit verschiedenen Abschnitten von k liefern Summanden desselben Wertes
+    for i in 0..m {
+        c.zeile_mut(i)[..n].fill(T::NULL);
+    }
+    for (index, werte) in ergebnisse.into_iter().flatten() {
+        let kachel: &Kachel = &kacheln[index];
+        let breite: usize = kachel.spalten.len();
+
+        for (zeile, i) in kachel.zeilen.clone().enumerate() {
+            let ziel: &mut [T] = &mut c.zeile_mut(i)[kachel.spalten.clone()];
+            for (wert, summand) in ziel
+                .iter_mut()
+                .zip(&werte[zeile * breite..(zeile + 1) * breite])
+            {
+                *wert += *summand;
+            }
+        }
+    }
+    Ok(())
+}